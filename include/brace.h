@@ -0,0 +1,50 @@
+#ifndef BRACE_H
+#define BRACE_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The auction was solved; `json_out` holds the result
+ */
+#define BRACE_OK 0
+
+/**
+ * A pointer was null or the input was not UTF-8; `json_out` is untouched
+ */
+#define BRACE_INVALID_ARGUMENT 1
+
+/**
+ * The instance was rejected; `json_out` holds `{"error": "..."}`
+ */
+#define BRACE_AUCTION_ERROR 2
+
+/**
+ * The solver panicked; `json_out` holds `{"error": "..."}`
+ */
+#define BRACE_PANIC 3
+
+/**
+ * Run BRACE on a NUL-terminated JSON `AuctionInstance`.
+ * On success `*json_out` points to the JSON `AuctionResult`; on failure it
+ * points to an error object (see the status codes). Free it with `brace_string_free`.
+ *
+ * # Safety
+ * `json_in` must be null or a valid NUL-terminated string, and `json_out`
+ * null or valid for writing a pointer.
+ */
+int32_t brace_solve(const char *json_in, char **json_out);
+
+/**
+ * Free a string returned by `brace_solve`. Null is ignored.
+ *
+ * # Safety
+ * `s` must be null or a pointer returned by `brace_solve` that was not freed yet.
+ */
+void brace_string_free(char *s);
+
+#endif  /* BRACE_H */
@@ -0,0 +1,57 @@
+use crate::types::{Agent, Bundle};
+use serde::{Deserialize, Serialize};
+
+/// Structural properties of an agent's valuation, checked over its declared bundles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValuationClass {
+    /// v(S) + v(T) >= v(S ∪ T) + v(S ∩ T) whenever the union and intersection are declared (or empty)
+    pub submodular: bool,
+    /// v(S ∪ T) >= v(S) + v(T) for disjoint S, T whose union is declared
+    pub superadditive: bool,
+    /// Every declared bundle is worth its single most valuable good
+    pub unit_demand: bool,
+}
+
+const TOLERANCE: f64 = 1e-9;
+
+/// Classify an agent's valuation. Only relations among declared bundles are
+/// checked, so a class holds vacuously when no bundles relate.
+pub fn classify_valuation(agent: &Agent) -> ValuationClass {
+    let bundles = agent.preference_bundles();
+    let declared = |bundle: &Bundle| bundle.is_empty() || bundles.contains(bundle);
+    let value = |bundle: &Bundle| agent.preference(bundle);
+
+    let mut submodular = true;
+    let mut superadditive = true;
+    for (i, s) in bundles.iter().enumerate() {
+        for t in &bundles[i + 1..] {
+            let union: Bundle = s.union(t).cloned().collect();
+            let intersection: Bundle = s.intersection(t).cloned().collect();
+            if !declared(&union) {
+                continue;
+            }
+            if declared(&intersection)
+                && value(s) + value(t) < value(&union) + value(&intersection) - TOLERANCE
+            {
+                submodular = false;
+            }
+            if intersection.is_empty() && value(&union) < value(s) + value(t) - TOLERANCE {
+                superadditive = false;
+            }
+        }
+    }
+
+    let unit_demand = bundles.iter().filter(|bundle| bundle.len() > 1).all(|bundle| {
+        let best = bundle
+            .iter()
+            .map(|good| value(&[good.clone()].into_iter().collect()))
+            .fold(0.0, f64::max);
+        (value(bundle) - best).abs() < TOLERANCE
+    });
+
+    ValuationClass {
+        submodular,
+        superadditive,
+        unit_demand,
+    }
+}
@@ -1,40 +1,445 @@
-use crate::types::{Agent, Allocation, AuctionResult, Good};
-use crate::brace::BRACEMechanism;
+use crate::types::{Agent, Allocation, AuctionInstance, AuctionResult, BenchmarkRun, Good};
+use crate::brace::{BRACEMechanism, EpsilonPolicy, Improvement, TieBreaker, WarmStart};
+use crate::constraints::Constraints;
+use crate::cooperative::{shapley_values, ShapleyConfig};
+use crate::error::{AuctionError, NonConvergence};
+use crate::objective::Objective;
+use crate::ids::AgentId;
+use crate::mechanisms::{
+    EnvyCycleMechanism, GreedyMechanism, LeximinMechanism, RandomSerialDictatorship, TtcMechanism,
+};
+use crate::observer::{AuctionObserver, Flow};
+use crate::payments::{vcg_payments, PaymentRule, PostedPrices};
+use crate::pricing::{
+    ce_violations, compute_personalized_prices, core_selecting_payments, PriceAdjustmentConfig,
+    PriceVector,
+};
+use crate::wdp::{welfare_upper_bound, ExactSolver, WdpSolver};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Mechanism `CombinatorialAuction::run` allocates with. The alternatives
+/// pool endowments (except top trading cycles) and report zero prices;
+/// `run` rejects settings they would ignore (constraints, capacities they
+/// do not check, automatic epsilon, housing markets).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MechanismKind {
+    /// Bundle-trading with price adjustment
+    #[default]
+    Brace,
+    /// Random serial dictatorship with the given seed
+    RandomSerialDictatorship(u64),
+    /// Top trading cycles over the endowments
+    TopTradingCycles,
+    /// Greedy winner determination
+    Greedy,
+    /// Maximize the worst-off agent's utility
+    Leximin,
+    /// Envy-cycle elimination over single goods
+    EnvyCycle,
+}
+
+impl MechanismKind {
+    /// Whether the mechanism keeps agents within their `max_goods`
+    fn respects_capacity(&self) -> bool {
+        !matches!(self, MechanismKind::TopTradingCycles | MechanismKind::EnvyCycle)
+    }
+
+    /// Allocation chosen by a non-BRACE mechanism (`None` for BRACE)
+    fn allocate(&self, agents: &[Agent], goods: &[Good]) -> Option<Allocation> {
+        match *self {
+            MechanismKind::Brace => None,
+            MechanismKind::RandomSerialDictatorship(seed) => {
+                Some(RandomSerialDictatorship::new(seed).compute_allocation(agents, goods))
+            }
+            MechanismKind::TopTradingCycles => Some(TtcMechanism::new().compute_allocation(agents)),
+            MechanismKind::Greedy => Some(GreedyMechanism::new().run(agents, goods).allocation),
+            MechanismKind::Leximin => Some(LeximinMechanism::new().run(agents, goods).allocation),
+            MechanismKind::EnvyCycle => {
+                Some(EnvyCycleMechanism::new().compute_allocation(agents, goods))
+            }
+        }
+    }
+}
 
 /// Main combinatorial auction interface
 pub struct CombinatorialAuction {
     agents: Vec<Agent>,
     goods: Vec<Good>,
     mechanism: BRACEMechanism,
+    /// Which mechanism `run` allocates with
+    kind: MechanismKind,
+    compute_vcg: bool,
+    compute_core: bool,
+    /// Rule for `AuctionResult::payments`
+    payment_rule: Box<dyn PaymentRule>,
+    /// Shapley values to compute, if requested
+    shapley: Option<ShapleyConfig>,
+    /// Solver whose welfare is reported alongside BRACE's
+    benchmark: Option<Box<dyn WdpSolver>>,
 }
 
 impl CombinatorialAuction {
-    pub fn new(agents: Vec<Agent>, goods: Vec<Good>, epsilon: f64) -> Self {
-        Self {
+    /// Create an auction, rejecting duplicate goods, endowments of unknown
+    /// goods and non-finite preference values
+    pub fn new(
+        agents: Vec<Agent>,
+        goods: Vec<Good>,
+        epsilon: impl Into<EpsilonPolicy>,
+    ) -> Result<Self, AuctionError> {
+        let mut known = HashSet::new();
+        for good in &goods {
+            if !known.insert(good.id) {
+                return Err(AuctionError::DuplicateGood(good.id));
+            }
+        }
+        for agent in &agents {
+            if let Some(good) = agent.endowment.iter().find(|g| !known.contains(&g.id)) {
+                return Err(AuctionError::UnknownEndowedGood {
+                    agent: agent.id,
+                    good: good.id,
+                });
+            }
+            for bundle in agent.preference_bundles() {
+                let value = agent.preference(bundle);
+                if !value.is_finite() {
+                    return Err(AuctionError::NonFiniteValue {
+                        agent: agent.id,
+                        value,
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
             agents,
             goods,
             mechanism: BRACEMechanism::new(epsilon),
+            kind: MechanismKind::default(),
+            compute_vcg: false,
+            compute_core: false,
+            payment_rule: Box::new(PostedPrices),
+            shapley: None,
+            benchmark: None,
+        })
+    }
+
+    /// Create an auction from a stored instance
+    pub fn from_instance(instance: AuctionInstance) -> Result<Self, AuctionError> {
+        Self::new(instance.agents, instance.goods, instance.epsilon)?
+            .with_constraints(instance.constraints)
+    }
+
+    /// Forbid allocations that break `constraints`, rejecting unknown goods
+    pub fn with_constraints(mut self, constraints: Constraints) -> Result<Self, AuctionError> {
+        if let Some(good) = constraints
+            .goods()
+            .find(|id| !self.goods.iter().any(|g| g.id == *id))
+        {
+            return Err(AuctionError::UnknownConstrainedGood(good));
         }
+        self.mechanism.constraints = constraints;
+        Ok(self)
+    }
+
+    /// Tune the equilibrium price adjustment (step size, iterations, damping, numeraire)
+    pub fn with_price_adjustment(mut self, config: PriceAdjustmentConfig) -> Self {
+        self.mechanism.price_config = config;
+        self
+    }
+
+    /// Break ties between candidate trades with the given policy
+    pub fn with_tie_breaker(mut self, tie_breaker: TieBreaker) -> Self {
+        self.mechanism.tie_breaker = tie_breaker;
+        self
+    }
+
+    /// Let agents consent to trades they are indifferent about (see `Improvement`)
+    pub fn with_improvement(mut self, improvement: Improvement) -> Self {
+        self.mechanism.improvement = improvement;
+        self
+    }
+
+    /// Pick among available trades by this objective (recorded in the result)
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.mechanism.objective = objective;
+        self
+    }
+
+    /// Pick the smallest global epsilon in `[min, max]` that works on this
+    /// instance (recorded in the result as `epsilon_used`)
+    pub fn with_auto_epsilon(mut self, min: f64, max: f64) -> Self {
+        self.mechanism = self.mechanism.with_auto_epsilon(min, max);
+        self
+    }
+
+    /// Allocate with `kind` instead of BRACE
+    pub fn with_mechanism_kind(mut self, kind: MechanismKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Reject settings that a non-BRACE `kind` would silently ignore
+    pub(crate) fn check_mechanism_kind(&self) -> Result<(), AuctionError> {
+        let kind = self.kind;
+        let capped = || self.agents.iter().any(|agent| agent.max_goods.is_some());
+        let setting = if kind == MechanismKind::Brace {
+            return Ok(());
+        } else if !self.mechanism.constraints.is_empty() {
+            "conflict and category constraints"
+        } else if !kind.respects_capacity() && capped() {
+            "agent capacities"
+        } else if self.mechanism.auto_epsilon.is_some() {
+            "automatic epsilon selection"
+        } else if !self.mechanism.pricing {
+            "housing markets"
+        } else {
+            return Ok(());
+        };
+        Err(AuctionError::UnsupportedSetting { kind, setting })
+    }
+
+    /// Run as a housing market: pure endowment exchange without money.
+    /// Prices are skipped and the result lists the trade cycles instead.
+    pub fn with_housing_market(mut self) -> Self {
+        self.mechanism = self.mechanism.without_pricing();
+        self
+    }
+
+    /// Charge agents by `rule` (default: `PostedPrices`, at the final prices)
+    pub fn with_payment_rule(mut self, rule: impl PaymentRule + 'static) -> Self {
+        self.payment_rule = Box::new(rule);
+        self
+    }
+
+    /// Also compute VCG payments (exponential in the number of agents)
+    pub fn with_vcg_payments(mut self) -> Self {
+        self.compute_vcg = true;
+        self
+    }
+
+    /// Also compute core-selecting payments (exponential in the number of
+    /// agents; VCG payments past `pricing::MAX_CORE_AGENTS`)
+    pub fn with_core_payments(mut self) -> Self {
+        self.compute_core = true;
+        self
+    }
+
+    /// Also compute Shapley values of the welfare game as a surplus division
+    pub fn with_shapley_values(mut self, config: ShapleyConfig) -> Self {
+        self.shapley = Some(config);
+        self
+    }
+
+    /// Also solve winner determination with `solver` as a welfare benchmark
+    pub fn with_wdp_solver(mut self, solver: impl WdpSolver + 'static) -> Self {
+        self.benchmark = Some(Box::new(solver));
+        self
     }
 
     /// Run the auction and return the result
-    pub fn run(&self) -> AuctionResult {
+    pub fn run(&self) -> Result<AuctionResult, AuctionError> {
+        self.run_observed(&mut ())
+    }
+
+    /// Run BRACE and winner determination (the `with_wdp_solver` solver,
+    /// exact by default) and report both with BRACE's approximation ratio
+    pub fn run_with_benchmark(&self) -> Result<BenchmarkRun, AuctionError> {
+        let brace = self.run()?;
+        let (allocation, _) = match &self.benchmark {
+            Some(solver) => solver.solve(&self.agents, &self.goods),
+            None => ExactSolver.solve(&self.agents, &self.goods),
+        };
+        let optimal = self.evaluate(allocation, &PriceVector::new());
+        Ok(BenchmarkRun {
+            approximation_ratio: brace.approximation_ratio(&optimal),
+            brace,
+            optimal,
+        })
+    }
+
+    /// Run the auction, reporting trades and price updates to `observer`
+    pub fn run_observed(
+        &self,
+        observer: &mut dyn AuctionObserver,
+    ) -> Result<AuctionResult, AuctionError> {
+        self.run_with(None, observer)
+    }
+
+    /// Run the auction starting from an earlier result (typically on a
+    /// slightly different instance) instead of from scratch: its allocation
+    /// seeds the improvement loop and its prices the price adjustment.
+    /// Falls back to the endowments if the old allocation no longer fits.
+    pub fn run_from(&self, previous: &AuctionResult) -> Result<AuctionResult, AuctionError> {
+        let start = WarmStart {
+            allocation: previous.allocation.clone(),
+            prices: PriceVector::from_map(previous.prices.clone()),
+        };
+        self.run_with(Some(&start), &mut ())
+    }
+
+    fn run_with(
+        &self,
+        start: Option<&WarmStart>,
+        observer: &mut dyn AuctionObserver,
+    ) -> Result<AuctionResult, AuctionError> {
+        self.check_mechanism_kind()?;
+        if let Some(allocation) = self.kind.allocate(&self.agents, &self.goods) {
+            return Ok(self.evaluate(allocation, &PriceVector::new()));
+        }
+        let tuned = self.mechanism.auto_epsilon.map(|_| {
+            let epsilon = self.mechanism.select_epsilon(&self.agents, &self.goods);
+            self.mechanism.clone().with_global_epsilon(epsilon)
+        });
+        let mechanism = tuned.as_ref().unwrap_or(&self.mechanism);
+
         // Compute allocation using BRACE mechanism
-        let (allocation, prices) = self.mechanism.compute_allocation(&self.agents, &self.goods);
+        let mut log = TradeLog {
+            inner: observer,
+            trades: Vec::new(),
+        };
+        let (allocation, prices, non_convergence) = match start {
+            Some(start) => {
+                mechanism.compute_allocation_from(&self.agents, &self.goods, start, &mut log)
+            }
+            None => mechanism.compute_allocation_diagnosed(&self.agents, &self.goods, &mut log),
+        };
+        // A still-improving allocation is an error; unsettled prices are reported
+        if let Some(NonConvergence::ImprovementLoop { iterations }) = non_convergence.first() {
+            return Err(AuctionError::NotConverged {
+                iterations: *iterations,
+            });
+        }
+
+        let mut result = self.evaluate_with(mechanism, allocation, &prices);
+        if !mechanism.pricing {
+            result.trade_cycles = Some(log.trades);
+        }
+        result.converged = non_convergence.is_empty();
+        result.non_convergence = non_convergence;
+        result.epsilon_used = tuned.map(|mechanism| mechanism.epsilon.global);
+        Ok(result)
+    }
 
+    /// Check and summarize an allocation at the given prices, as `run` does
+    /// for BRACE's own outcome. Lets other mechanisms report an `AuctionResult`.
+    pub fn evaluate(&self, allocation: Allocation, prices: &PriceVector) -> AuctionResult {
+        self.evaluate_with(&self.mechanism, allocation, prices)
+    }
+
+    fn evaluate_with(
+        &self,
+        mechanism: &BRACEMechanism,
+        allocation: Allocation,
+        prices: &PriceVector,
+    ) -> AuctionResult {
         // Verify properties
-        let is_feasible = self.mechanism.verify_feasibility(&allocation, &self.goods);
+        let is_feasible = mechanism.verify_feasibility(&allocation, &self.goods)
+            && mechanism.verify_reserve_prices(&self.agents, &allocation)
+            && mechanism.verify_capacity(&self.agents, &allocation);
+        // Payments count towards individual rationality whenever prices are nonzero
+        let payments = self
+            .payment_rule
+            .payments(&self.agents, &self.goods, &allocation, prices);
         let is_individually_rational = 
-            self.mechanism.verify_individual_rationality(&self.agents, &allocation);
+            mechanism.verify_individual_rationality(&self.agents, &allocation)
+                && mechanism.verify_quasi_linear_individual_rationality(
+                    &self.agents,
+                    &allocation,
+                    &payments,
+                );
         let is_ordinal_efficient = 
-            self.mechanism.verify_ordinal_efficiency(&self.agents, &allocation);
+            mechanism.verify_ordinal_efficiency(&self.agents, &allocation);
+
+        let is_envy_free =
+            mechanism.verify_envy_freeness(&self.agents, &allocation, prices);
+        let is_epsilon_envy_free = mechanism.verify_epsilon_envy_freeness(
+            &self.agents,
+            &allocation,
+            prices,
+            mechanism.epsilon.global,
+        );
 
         // Calculate total welfare
         let total_welfare = self.calculate_welfare(&allocation);
+        let objective_value = mechanism.objective.evaluate(&self.agents, &allocation);
+
+        // Budget relaxation each agent needs to afford its bundle
+        let budget_relaxation = self
+            .agents
+            .iter()
+            .map(|agent| {
+                let relaxation = allocation
+                    .get_bundle(agent.id)
+                    .map(|bundle| {
+                        prices.budget_relaxation(agent, bundle, allocation.get_units(agent.id))
+                    })
+                    .unwrap_or(0.0);
+                (agent.id, relaxation)
+            })
+            .collect();
+        let is_budget_feasible =
+            mechanism.verify_budget_relaxation(&self.agents, &allocation, prices);
+
+        let unallocated_goods = mechanism.unallocated_goods(&allocation, &self.goods);
+
+        // Fall back to personalized prices if anonymous prices do not support the allocation
+        // (without money there is nothing to support)
+        let epsilon = mechanism.epsilon.global;
+        let ce_violations = if mechanism.pricing {
+            ce_violations(prices, &self.agents, &allocation)
+        } else {
+            HashMap::new()
+        };
+        let max_ce_violation = ce_violations.values().copied().fold(0.0, f64::max);
+        let supported = max_ce_violation <= epsilon;
+        let personalized_prices = (!supported).then(|| {
+            compute_personalized_prices(&self.agents, &self.goods, &allocation, epsilon)
+        });
 
         // Convert prices to HashMap format
         let prices_map = prices.all_prices().clone();
 
+        let revenue: f64 = payments.values().sum();
+        let net_utility = self
+            .agents
+            .iter()
+            .map(|agent| {
+                let value = allocation.get_bundle(agent.id).map_or(0.0, |b| agent.preference(b));
+                (agent.id, value - payments.get(&agent.id).copied().unwrap_or(0.0))
+            })
+            .collect();
+        let money: HashMap<AgentId, f64> = self
+            .agents
+            .iter()
+            .map(|agent| (agent.id, agent.money - payments.get(&agent.id).copied().unwrap_or(0.0)))
+            .collect();
+        let quasi_linear_welfare = total_welfare + money.values().sum::<f64>() + revenue;
+
+        // Optional payment rules
+        let vcg_payments = self
+            .compute_vcg
+            .then(|| vcg_payments(&self.agents, &self.goods, &allocation));
+        let core_payments = self
+            .compute_core
+            .then(|| core_selecting_payments(&self.agents, &self.goods, &allocation));
+        let shapley_values = self
+            .shapley
+            .as_ref()
+            .map(|config| shapley_values(&self.agents, &self.goods, config));
+        let benchmark_welfare = self
+            .benchmark
+            .as_ref()
+            .map(|solver| solver.solve(&self.agents, &self.goods).1);
+
+        // The bound only covers preference bundles, so it is skipped when
+        // some bundle outside them may be worth more
+        let bounded = self.agents.iter().all(|agent| agent.bounded_by_preference_bundles());
+        let upper_bound = bounded.then(|| match &self.benchmark {
+            Some(solver) => solver.upper_bound(&self.agents, &self.goods),
+            None => welfare_upper_bound(&self.agents, &self.goods),
+        });
+
         AuctionResult {
             allocation,
             prices: prices_map,
@@ -42,6 +447,31 @@ impl CombinatorialAuction {
             is_feasible,
             is_individually_rational,
             is_ordinal_efficient,
+            is_envy_free,
+            is_epsilon_envy_free,
+            vcg_payments,
+            core_payments,
+            payments,
+            revenue,
+            net_utility,
+            money,
+            quasi_linear_welfare,
+            shapley_values,
+            budget_relaxation,
+            is_budget_feasible,
+            unallocated_goods,
+            personalized_prices,
+            benchmark_welfare,
+            welfare_upper_bound: upper_bound,
+            welfare_gap: upper_bound.map(|bound| bound - total_welfare),
+            objective: mechanism.objective,
+            objective_value,
+            ce_violations,
+            max_ce_violation,
+            trade_cycles: None,
+            converged: true,
+            non_convergence: Vec::new(),
+            epsilon_used: None,
         }
     }
 
@@ -51,7 +481,7 @@ impl CombinatorialAuction {
             .iter()
             .filter_map(|agent| {
                 allocation
-                    .get_bundle(&agent.id)
+                    .get_bundle(agent.id)
                     .map(|bundle| agent.preference(bundle))
             })
             .sum()
@@ -66,5 +496,30 @@ impl CombinatorialAuction {
     pub fn goods(&self) -> &[Good] {
         &self.goods
     }
+
+    /// Mechanism the auction runs, with the instance's constraints
+    pub fn mechanism(&self) -> &BRACEMechanism {
+        &self.mechanism
+    }
+}
+
+/// Records executed trades while passing every event on
+struct TradeLog<'a> {
+    inner: &'a mut dyn AuctionObserver,
+    trades: Vec<Vec<AgentId>>,
 }
 
+impl AuctionObserver for TradeLog<'_> {
+    fn on_trade(&mut self, agents: &[AgentId], allocation: &Allocation) {
+        self.trades.push(agents.to_vec());
+        self.inner.on_trade(agents, allocation);
+    }
+
+    fn on_price_update(&mut self, iteration: usize, prices: &PriceVector) -> Flow {
+        self.inner.on_price_update(iteration, prices)
+    }
+
+    fn on_iteration_end(&mut self, iteration: usize, allocation: &Allocation) -> Flow {
+        self.inner.on_iteration_end(iteration, allocation)
+    }
+}
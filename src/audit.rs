@@ -0,0 +1,158 @@
+use crate::brace::BRACEMechanism;
+use crate::ids::{AgentId, GoodId};
+use crate::types::{sorted_ids, Agent, Allocation, Bundle, Good};
+use serde::{Deserialize, Serialize};
+
+/// A simple misreport of an agent's preferences
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Misreport {
+    Truthful,
+    /// Every declared value multiplied by a factor
+    Scale(f64),
+    /// One declared bundle omitted (sorted good IDs)
+    Drop(Vec<GoodId>),
+}
+
+/// Which misreports to try
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditConfig {
+    pub scale_factors: Vec<f64>,
+    /// Also try dropping each declared bundle in turn
+    pub drop_bundles: bool,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            scale_factors: vec![0.5, 0.8, 1.25, 2.0],
+            drop_bundles: true,
+        }
+    }
+}
+
+/// Best misreport found for one agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManipulationReport {
+    pub agent: AgentId,
+    /// True value of the bundle received when reporting truthfully
+    pub truthful_utility: f64,
+    /// True value of the bundle received under the best misreport
+    pub best_utility: f64,
+    pub best_misreport: Misreport,
+    pub gain: f64,
+}
+
+/// Manipulation audit over all agents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditReport {
+    pub agents: Vec<ManipulationReport>,
+    /// Largest utility gain any agent achieves by lying
+    pub max_gain: f64,
+}
+
+/// Audit BRACE with the default set of misreports
+pub fn audit(agents: &[Agent], goods: &[Good], epsilon: f64) -> AuditReport {
+    audit_with(agents, goods, epsilon, &AuditConfig::default())
+}
+
+/// Search each agent's misreports (others truthful) for the largest gain.
+/// Utility is the agent's true value for the bundle BRACE assigns it.
+/// Misreports restate the agent over its declared bundles, so bidding-language
+/// combinations outside those bundles are not preserved.
+pub fn audit_with(
+    agents: &[Agent],
+    goods: &[Good],
+    epsilon: f64,
+    config: &AuditConfig,
+) -> AuditReport {
+    let mechanism = BRACEMechanism::new(epsilon);
+    let (truthful, _) = mechanism.compute_allocation(agents, goods);
+
+    let reports: Vec<ManipulationReport> = agents
+        .iter()
+        .enumerate()
+        .map(|(i, agent)| {
+            let truthful_utility = utility(agent, &truthful);
+            let mut report = ManipulationReport {
+                agent: agent.id,
+                truthful_utility,
+                best_utility: truthful_utility,
+                best_misreport: Misreport::Truthful,
+                gain: 0.0,
+            };
+
+            for misreport in misreports(agent, config) {
+                let mut reported = agents.to_vec();
+                reported[i] = apply(agent, &misreport);
+                let (allocation, _) = mechanism.compute_allocation(&reported, goods);
+                let lying_utility = utility(agent, &allocation);
+                if lying_utility > report.best_utility + 1e-9 {
+                    report.best_utility = lying_utility;
+                    report.gain = lying_utility - truthful_utility;
+                    report.best_misreport = misreport;
+                }
+            }
+            report
+        })
+        .collect();
+
+    let max_gain = reports.iter().map(|r| r.gain).fold(0.0, f64::max);
+    AuditReport {
+        agents: reports,
+        max_gain,
+    }
+}
+
+fn utility(agent: &Agent, allocation: &Allocation) -> f64 {
+    allocation
+        .get_bundle(agent.id)
+        .map(|bundle| agent.preference(bundle))
+        .unwrap_or(0.0)
+}
+
+fn misreports(agent: &Agent, config: &AuditConfig) -> Vec<Misreport> {
+    let mut candidates: Vec<Misreport> = config
+        .scale_factors
+        .iter()
+        .map(|&factor| Misreport::Scale(factor))
+        .collect();
+    if config.drop_bundles {
+        candidates.extend(
+            agent
+                .preference_bundles()
+                .iter()
+                .map(|bundle| Misreport::Drop(sorted_ids(bundle))),
+        );
+    }
+    candidates
+}
+
+fn apply(agent: &Agent, misreport: &Misreport) -> Agent {
+    match misreport {
+        Misreport::Truthful => agent.clone(),
+        Misreport::Scale(factor) => restate(agent, |_, value| Some(value * factor)),
+        Misreport::Drop(dropped) => restate(agent, |bundle, value| {
+            (sorted_ids(bundle) != *dropped).then_some(value)
+        }),
+    }
+}
+
+/// Copy an agent, restating its declared bundles as explicit preferences.
+/// `report` maps each bundle and true value to the reported value (None drops it)
+pub(crate) fn restate(agent: &Agent, report: impl Fn(&Bundle, f64) -> Option<f64>) -> Agent {
+    let mut restated = Agent::new(agent.id, agent.endowment.clone());
+    restated.endowment_units = agent.endowment_units.clone();
+    restated.budget = agent.budget;
+    restated.free_disposal = agent.free_disposal;
+    restated.money = agent.money;
+    for bundle in agent.preference_bundles() {
+        let Some(value) = report(bundle, agent.preference(bundle)) else {
+            continue;
+        };
+        match agent.bundle_units(bundle) {
+            Some(units) => restated.add_multi_unit_preference(bundle.clone(), units.clone(), value),
+            None => restated.add_preference(bundle.clone(), value),
+        }
+    }
+    restated
+}
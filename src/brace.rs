@@ -1,15 +1,362 @@
-use crate::types::{Agent, Allocation, Good};
-use crate::pricing::{PriceVector, compute_equilibrium_prices};
+use crate::error::{AuctionError, NonConvergence};
+use crate::constraints::Constraints;
+use crate::objective::Objective;
+use crate::observer::{AuctionObserver, Flow};
+use crate::ids::{AgentId, GoodId};
+use crate::types::{unit_count, Agent, Allocation, Bundle, FractionalAllocation, Good, Units};
+use crate::pricing::{adjust_prices, PriceAdjustmentConfig, PriceVector};
+use crate::lp::sd_dominating_allocation;
+use crate::sybil::{audit_false_names, SybilConfig};
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap};
+
+/// Cap on improvement-loop passes
+const MAX_IMPROVEMENT_ITERATIONS: usize = 100;
+
+/// Relaxation parameters: a global epsilon plus optional per-agent overrides
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpsilonPolicy {
+    /// Used for supply feasibility and for agents without an override
+    pub global: f64,
+    /// Per-agent tolerance (reserve prices, budget relaxation)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub per_agent: HashMap<AgentId, f64>,
+}
+
+impl EpsilonPolicy {
+    pub fn uniform(epsilon: f64) -> Self {
+        Self {
+            global: epsilon,
+            per_agent: HashMap::new(),
+        }
+    }
+
+    /// Override the tolerance for one agent
+    pub fn with_agent(mut self, agent_id: impl Into<AgentId>, epsilon: f64) -> Self {
+        self.per_agent.insert(agent_id.into(), epsilon);
+        self
+    }
+
+    /// Tolerance for an agent (the global epsilon if not overridden)
+    pub fn for_agent(&self, agent_id: impl Into<AgentId>) -> f64 {
+        self.per_agent
+            .get(&agent_id.into())
+            .copied()
+            .unwrap_or(self.global)
+    }
+}
+
+impl From<f64> for EpsilonPolicy {
+    fn from(epsilon: f64) -> Self {
+        Self::uniform(epsilon)
+    }
+}
+
+/// How the improvement loop orders equally acceptable trades
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TieBreaker {
+    /// Agents in input order, goods sorted by id
+    #[default]
+    InputOrder,
+    /// A random order that is reproducible from the seed
+    Seeded(u64),
+}
+
+impl TieBreaker {
+    fn start(&self) -> Ties {
+        match self {
+            TieBreaker::InputOrder => Ties(None),
+            TieBreaker::Seeded(seed) => Ties(Some(StdRng::seed_from_u64(*seed))),
+        }
+    }
+}
+
+/// When an agent agrees to a swap, partial transfer or trading cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Improvement {
+    /// Every participant must be strictly better off
+    #[default]
+    Strict,
+    /// Participants may be indifferent, as long as one is strictly better
+    /// off; allows welfare-neutral reshuffles that unlock trades for others
+    Weak,
+}
+
+impl Improvement {
+    /// Whether the agents accept trading each `(agent, new, old)` holding
+    fn consents(self, moves: &[(&Agent, &Bundle, &Bundle)]) -> bool {
+        let gains = || moves.iter().map(|(agent, new, old)| agent.prefers(new, old));
+        match self {
+            Improvement::Strict => gains().all(|gain| gain),
+            Improvement::Weak => {
+                let accepted = |(agent, new, old): &(&Agent, &Bundle, &Bundle)| {
+                    agent.prefers_or_indifferent(new, old)
+                };
+                moves.iter().all(accepted) && gains().any(|gain| gain)
+            }
+        }
+    }
+}
+
+/// A proposed trade: the new holdings of the agents it involves. Candidates
+/// are judged against the current allocation and only applied once accepted,
+/// so evaluating one never copies the allocation.
+struct Trade<'a> {
+    holdings: Vec<(&'a Agent, Bundle, Units)>,
+}
+
+impl Trade<'_> {
+    /// The participants consent to the trade under `improvement`
+    fn is_accepted(&self, allocation: &Allocation, improvement: Improvement) -> bool {
+        let moves: Vec<(&Agent, &Bundle, &Bundle)> = self
+            .holdings
+            .iter()
+            .filter_map(|(agent, new, _)| Some((*agent, new, allocation.get_bundle(agent.id)?)))
+            .collect();
+        improvement.consents(&moves)
+    }
+
+    /// Every participant's new holdings respect its capacity and the constraints
+    fn is_admissible(&self, constraints: &Constraints, goods: &[Good]) -> bool {
+        self.holdings.iter().all(|(agent, bundle, units)| {
+            agent.within_capacity(bundle, Some(units)) && constraints.permits(agent.id, bundle, goods)
+        })
+    }
+
+    /// Value of `objective` once the trade is applied
+    fn score(&self, objective: Objective, agents: &[Agent], allocation: &Allocation) -> f64 {
+        let values: Vec<f64> = agents
+            .iter()
+            .map(|agent| match self.holdings.iter().find(|(a, ..)| a.id == agent.id) {
+                Some((_, bundle, _)) => agent.preference(bundle),
+                None => allocation
+                    .get_bundle(agent.id)
+                    .map_or(0.0, |bundle| agent.preference(bundle)),
+            })
+            .collect();
+        objective.score(&values)
+    }
+
+    fn apply(self, allocation: &mut Allocation) {
+        for (agent, bundle, units) in self.holdings {
+            allocation.assign_units(agent.id, bundle, units);
+        }
+    }
+}
+
+/// A pairwise trade waiting in the improvement queue, valid while neither
+/// agent's holdings have changed since it was evaluated
+struct Candidate<'a> {
+    /// Total utility gain of the participants
+    gain: f64,
+    /// Position of the pair in the tie-breaking order
+    order: usize,
+    pair: (usize, usize),
+    /// Holdings versions of the two agents when the trade was evaluated
+    stamps: (usize, usize),
+    trade: Trade<'a>,
+}
+
+impl PartialEq for Candidate<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for Candidate<'_> {}
+
+impl PartialOrd for Candidate<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate<'_> {
+    /// Larger gains first, then earlier pairs
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.gain
+            .total_cmp(&other.gain)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+/// Running tie-breaking state for one allocation run
+struct Ties(Option<StdRng>);
+
+impl Ties {
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        if let Some(rng) = &mut self.0 {
+            items.shuffle(rng);
+        }
+    }
+}
+
+/// Allocation and prices a run starts from instead of the endowments and
+/// the reserve prices, e.g. the outcome of an earlier run on a similar instance
+#[derive(Debug, Clone, Default)]
+pub struct WarmStart {
+    pub allocation: Allocation,
+    pub prices: PriceVector,
+}
+
+/// Range searched for the smallest workable epsilon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoEpsilon {
+    pub min: f64,
+    pub max: f64,
+    /// Bisection steps (the range halves each step)
+    pub steps: usize,
+}
 
 /// BRACE (Budget-Relaxed Approximate Competitive Equilibrium) mechanism
+#[derive(Debug, Clone)]
 pub struct BRACEMechanism {
-    /// Approximation parameter for feasibility
-    pub epsilon: f64,
+    /// Approximation parameters for feasibility and relaxation
+    pub epsilon: EpsilonPolicy,
+    /// Parameters of the equilibrium price adjustment
+    pub price_config: PriceAdjustmentConfig,
+    /// Longest trading cycle the improvement loop searches (2 = pairwise swaps only)
+    pub max_cycle_length: usize,
+    /// Most goods moved each way in a partial trade (0 = whole-bundle swaps only)
+    pub max_transfer_size: usize,
+    /// Order in which candidate trades are tried
+    pub tie_breaker: TieBreaker,
+    /// Whether swaps and cycles need every participant strictly better off
+    pub improvement: Improvement,
+    /// Objective used to pick among trades (Utilitarian takes the largest utility gain)
+    pub objective: Objective,
+    /// Rules on which goods may be allocated together
+    pub constraints: Constraints,
+    /// Compute equilibrium prices (false = housing market: exchange only, no money)
+    pub pricing: bool,
+    /// Replace the global epsilon by the smallest workable one in this range
+    pub auto_epsilon: Option<AutoEpsilon>,
 }
 
 impl BRACEMechanism {
-    pub fn new(epsilon: f64) -> Self {
-        Self { epsilon }
+    pub fn new(epsilon: impl Into<EpsilonPolicy>) -> Self {
+        Self {
+            epsilon: epsilon.into(),
+            price_config: PriceAdjustmentConfig::default(),
+            max_cycle_length: 3,
+            max_transfer_size: 2,
+            tie_breaker: TieBreaker::default(),
+            improvement: Improvement::default(),
+            objective: Objective::default(),
+            constraints: Constraints::default(),
+            pricing: true,
+            auto_epsilon: None,
+        }
+    }
+
+    /// Allow partial trades moving up to `size` goods each way
+    pub fn with_max_transfer_size(mut self, size: usize) -> Self {
+        self.max_transfer_size = size;
+        self
+    }
+
+    /// Search trading cycles of up to `k` agents when improving the allocation
+    pub fn with_max_cycle_length(mut self, k: usize) -> Self {
+        self.max_cycle_length = k;
+        self
+    }
+
+    /// Break ties between candidate trades with the given policy
+    pub fn with_tie_breaker(mut self, tie_breaker: TieBreaker) -> Self {
+        self.tie_breaker = tie_breaker;
+        self
+    }
+
+    /// Accept swaps and cycles under strict or weak improvement
+    pub fn with_improvement(mut self, improvement: Improvement) -> Self {
+        self.improvement = improvement;
+        self
+    }
+
+    /// Pick among available trades by this objective
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Only make trades that respect `constraints`
+    pub fn with_constraints(mut self, constraints: Constraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    /// Housing market mode: agents only swap whole endowments along trading
+    /// cycles and no prices are computed (the final prices are empty)
+    pub fn without_pricing(mut self) -> Self {
+        self.pricing = false;
+        self.max_transfer_size = 0;
+        self
+    }
+
+    /// Search `[min, max]` by bisection for the smallest global epsilon at
+    /// which the mechanism converges to a feasible, individually rational
+    /// allocation, and run with that (see `select_epsilon`)
+    pub fn with_auto_epsilon(mut self, min: f64, max: f64) -> Self {
+        self.auto_epsilon = Some(AutoEpsilon {
+            min,
+            max,
+            steps: 20,
+        });
+        self
+    }
+
+    /// Fix the global epsilon, turning off any automatic selection
+    pub fn with_global_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon.global = epsilon;
+        self.auto_epsilon = None;
+        self
+    }
+
+    /// Global epsilon to run with: the configured one, or with automatic
+    /// selection the smallest one found to work. Assumes a larger epsilon
+    /// never turns a working run into a failing one. Returns the top of the
+    /// range if even that fails.
+    pub fn select_epsilon(&self, agents: &[Agent], goods: &[Good]) -> f64 {
+        let Some(search) = self.auto_epsilon else {
+            return self.epsilon.global;
+        };
+        if self.epsilon_works(search.min, agents, goods) {
+            return search.min;
+        }
+        let (mut low, mut high) = (search.min, search.max);
+        for _ in 0..search.steps {
+            let mid = (low + high) / 2.0;
+            if self.epsilon_works(mid, agents, goods) {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        high
+    }
+
+    /// Whether the mechanism converges to a feasible, IR allocation at
+    /// `epsilon`: both the improvement loop and the price adjustment (whose
+    /// stopping rule depends on epsilon) must settle
+    fn epsilon_works(&self, epsilon: f64, agents: &[Agent], goods: &[Good]) -> bool {
+        let candidate = self.clone().with_global_epsilon(epsilon);
+        let (allocation, _, non_convergence) =
+            candidate.compute_allocation_diagnosed(agents, goods, &mut ());
+        non_convergence.is_empty()
+            && candidate.verify_feasibility(&allocation, goods)
+            && candidate.verify_reserve_prices(agents, &allocation)
+            && candidate.verify_capacity(agents, &allocation)
+            && candidate.verify_individual_rationality(agents, &allocation)
+    }
+
+    /// Use custom price adjustment parameters
+    pub fn with_price_config(mut self, config: PriceAdjustmentConfig) -> Self {
+        self.price_config = config;
+        self
     }
 
     /// Compute BRACE allocation
@@ -22,137 +369,593 @@ impl BRACEMechanism {
         agents: &[Agent],
         goods: &[Good],
     ) -> (Allocation, PriceVector) {
-        // Initialize allocation with endowments (ensures individual rationality)
-        let mut allocation = Allocation::new();
-        for agent in agents {
-            allocation.assign(agent.id.clone(), agent.endowment.clone());
+        self.compute_allocation_observed(agents, goods, &mut ())
+    }
+
+    /// Compute the BRACE allocation, reporting progress to `observer`
+    pub fn compute_allocation_observed(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        observer: &mut dyn AuctionObserver,
+    ) -> (Allocation, PriceVector) {
+        let (allocation, prices, _) = self.compute_allocation_diagnosed(agents, goods, observer);
+        (allocation, prices)
+    }
+
+    /// Compute the BRACE allocation, listing every iteration cap that was
+    /// hit before the improvement loop or the price adjustment settled
+    pub fn compute_allocation_diagnosed(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        observer: &mut dyn AuctionObserver,
+    ) -> (Allocation, PriceVector, Vec<NonConvergence>) {
+        self.diagnose(self.allocate(agents, goods, None, observer))
+    }
+
+    /// `compute_allocation_diagnosed`, seeding the improvement loop and the
+    /// price adjustment with `start` (see `WarmStart`)
+    pub fn compute_allocation_from(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        start: &WarmStart,
+        observer: &mut dyn AuctionObserver,
+    ) -> (Allocation, PriceVector, Vec<NonConvergence>) {
+        self.diagnose(self.allocate(agents, goods, Some(start), observer))
+    }
+
+    fn diagnose(
+        &self,
+        (allocation, prices, improved, priced): (Allocation, PriceVector, bool, bool),
+    ) -> (Allocation, PriceVector, Vec<NonConvergence>) {
+        let mut reasons = Vec::new();
+        if !improved {
+            reasons.push(NonConvergence::ImprovementLoop {
+                iterations: MAX_IMPROVEMENT_ITERATIONS,
+            });
+        }
+        if !priced {
+            reasons.push(NonConvergence::PriceAdjustment {
+                iterations: self.price_config.max_iterations,
+            });
+        }
+        (allocation, prices, reasons)
+    }
+
+    /// Compute the BRACE allocation, failing if the improvement loop is still
+    /// finding trades when it hits its iteration cap
+    pub fn try_compute_allocation(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+    ) -> Result<(Allocation, PriceVector), AuctionError> {
+        self.try_compute_allocation_observed(agents, goods, &mut ())
+    }
+
+    /// `try_compute_allocation`, reporting progress to `observer`.
+    /// Stopping early through the observer is not an error.
+    pub fn try_compute_allocation_observed(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        observer: &mut dyn AuctionObserver,
+    ) -> Result<(Allocation, PriceVector), AuctionError> {
+        match self.allocate(agents, goods, None, observer) {
+            (allocation, prices, true, _) => Ok((allocation, prices)),
+            (_, _, false, _) => Err(AuctionError::NotConverged {
+                iterations: MAX_IMPROVEMENT_ITERATIONS,
+            }),
         }
+    }
 
-        // Compute initial prices
+    /// Run the improvement loop and price the result; also reports whether
+    /// the loop stopped because no trade was left and whether the prices
+    /// settled (stopping through the observer counts as settling)
+    fn allocate(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        start: Option<&WarmStart>,
+        observer: &mut dyn AuctionObserver,
+    ) -> (Allocation, PriceVector, bool, bool) {
+        // Initialize allocation with endowments (ensures individual rationality)
+        let mut allocation = match start.and_then(|start| self.seed(agents, goods, start)) {
+            Some(seeded) => seeded,
+            None => {
+                let mut allocation = Allocation::new();
+                for agent in agents {
+                    allocation.assign_units(
+                        agent.id,
+                        agent.endowment.clone(),
+                        agent.endowment_units.clone(),
+                    );
+                }
+                allocation
+            }
+        };
+
+        // Compute initial prices (seller reserves act as a floor)
         let mut prices = PriceVector::new();
         for good in goods {
-            prices.set_price(good.id.clone(), 0.0);
+            prices.set_price(good.id, good.reserve_price);
         }
+        let start_prices = start.map(|start| {
+            let mut seeded = PriceVector::new();
+            for good in goods {
+                let price = start.prices.all_prices().get(&good.id).copied();
+                seeded.set_price(good.id, price.unwrap_or(0.0).max(good.reserve_price));
+            }
+            seeded
+        });
 
         // Iterative improvement: try to find Pareto improvements
-        let max_iterations = 100;
-        for _ in 0..max_iterations {
-            let improved = self.improve_allocation(agents, goods, &mut allocation, &mut prices);
-            if !improved {
+        let mut ties = self.tie_breaker.start();
+        let mut converged = false;
+        for iteration in 0..MAX_IMPROVEMENT_ITERATIONS {
+            let improved = self.improve_allocation(
+                agents,
+                goods,
+                &mut allocation,
+                &mut prices,
+                &mut ties,
+                observer,
+            );
+            let flow = observer.on_iteration_end(iteration, &allocation);
+            if !improved || flow == Flow::Stop {
+                converged = true;
                 break;
             }
         }
 
         // Compute equilibrium prices for the final allocation
-        let final_prices = compute_equilibrium_prices(agents, goods, &allocation, self.epsilon);
+        let (final_prices, priced) = if self.pricing {
+            adjust_prices(
+                agents,
+                goods,
+                &allocation,
+                self.epsilon.global,
+                &self.price_config,
+                start_prices.as_ref(),
+                observer,
+            )
+        } else {
+            (PriceVector::new(), true)
+        };
+
+        (allocation, final_prices, converged, priced)
+    }
+
+    /// The warm start's allocation carried over to these agents and goods
+    /// (agents it does not mention start from their endowments, goods that
+    /// no longer exist are dropped). None if the result is infeasible,
+    /// breaks a capacity or leaves some agent worse off than its endowment.
+    fn seed(&self, agents: &[Agent], goods: &[Good], start: &WarmStart) -> Option<Allocation> {
+        let mut allocation = Allocation::new();
+        for agent in agents {
+            match start.allocation.get_bundle(agent.id) {
+                Some(previous) => {
+                    let bundle: Bundle = goods
+                        .iter()
+                        .filter(|good| previous.contains(*good))
+                        .cloned()
+                        .collect();
+                    let units = start.allocation.get_units(agent.id).cloned().unwrap_or_default();
+                    allocation.assign_units(agent.id, bundle, units);
+                }
+                None => allocation.assign_units(
+                    agent.id,
+                    agent.endowment.clone(),
+                    agent.endowment_units.clone(),
+                ),
+            }
+        }
+        let fits = goods
+            .iter()
+            .all(|good| allocation.allocated_units(good.id) <= good.supply)
+            && self.constraints.is_satisfied(&allocation, goods);
+        (fits
+            && self.verify_capacity(agents, &allocation)
+            && self.verify_individual_rationality(agents, &allocation))
+        .then_some(allocation)
+    }
+
+    /// Compute a fractional BRACE allocation.
+    /// Runs the improvement loop under `samples` random agent orders (seeded)
+    /// and gives each resulting allocation equal probability
+    pub fn compute_fractional_allocation(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        samples: usize,
+        seed: u64,
+    ) -> FractionalAllocation {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut order: Vec<Agent> = agents.to_vec();
+        let samples = samples.max(1);
+        let mut lottery = Vec::with_capacity(samples);
 
-        (allocation, final_prices)
+        for _ in 0..samples {
+            order.shuffle(&mut rng);
+            let (allocation, _) = self.compute_allocation(&order, goods);
+            lottery.push((allocation, 1.0 / samples as f64));
+        }
+
+        FractionalAllocation::from_lottery(&lottery)
     }
 
     /// Try to improve the allocation through Pareto improvements
     fn improve_allocation(
         &self,
         agents: &[Agent],
-        _goods: &[Good],
+        goods: &[Good],
         allocation: &mut Allocation,
         prices: &mut PriceVector,
+        ties: &mut Ties,
+        observer: &mut dyn AuctionObserver,
     ) -> bool {
         let mut improved = false;
 
         // Try to find beneficial trades
-        for i in 0..agents.len() {
-            for j in (i + 1)..agents.len() {
-                if let Some(new_allocation) = self.try_trade(
-                    &agents[i],
-                    &agents[j],
-                    allocation,
-                    prices,
-                ) {
-                    // Check if trade is Pareto improving
-                    if self.is_pareto_improving(agents, allocation, &new_allocation) {
-                        *allocation = new_allocation;
-                        improved = true;
+        let mut pairs: Vec<(usize, usize)> = (0..agents.len())
+            .flat_map(|i| ((i + 1)..agents.len()).map(move |j| (i, j)))
+            .collect();
+        ties.shuffle(&mut pairs);
+        if self.objective == Objective::Utilitarian {
+            let order = (pairs.as_slice(), &mut *ties);
+            improved = self.trade_by_gain(agents, goods, allocation, prices, order, observer);
+        } else {
+            // Fairness objectives: repeatedly make the trade that scores best
+            loop {
+                let mut best: Option<(f64, usize, usize, Trade)> = None;
+                for &(i, j) in &pairs {
+                    let Some(trade) =
+                        self.try_trade(&agents[i], &agents[j], allocation, prices, ties)
+                    else {
+                        continue;
+                    };
+                    if !trade.is_admissible(&self.constraints, goods)
+                        || !trade.is_accepted(allocation, self.improvement)
+                    {
+                        continue;
+                    }
+                    let score = trade.score(self.objective, agents, allocation);
+                    if best.as_ref().is_none_or(|(top, ..)| score > *top) {
+                        best = Some((score, i, j, trade));
                     }
                 }
+                let Some((_, i, j, trade)) = best else { break };
+                trade.apply(allocation);
+                observer.on_trade(&[agents[i].id, agents[j].id], allocation);
+                improved = true;
+            }
+        }
+
+        // Longer cycles: each agent takes the next agent's bundle
+        if self.max_cycle_length >= 3 {
+            while let Some(cycle) = self.find_trading_cycle(agents, goods, allocation) {
+                let moved: Vec<_> = cycle
+                    .iter()
+                    .map(|&i| {
+                        let id = agents[i].id;
+                        let bundle = allocation.get_bundle(id).cloned().unwrap_or_default();
+                        let units = allocation.get_units(id).cloned().unwrap_or_default();
+                        (bundle, units)
+                    })
+                    .collect();
+                for (position, &i) in cycle.iter().enumerate() {
+                    let (bundle, units) = moved[(position + 1) % cycle.len()].clone();
+                    allocation.assign_units(agents[i].id, bundle, units);
+                }
+                let members: Vec<AgentId> = cycle.iter().map(|&i| agents[i].id).collect();
+                observer.on_trade(&members, allocation);
+                improved = true;
             }
         }
 
         improved
     }
 
+    /// Make pairwise trades from a priority queue, largest total utility gain
+    /// first (equal gains in the order of `pairs`). Applying a trade only
+    /// re-evaluates the pairs of its two agents; queued trades that involve
+    /// them are stale and skipped when popped. This keeps each trade at O(n)
+    /// work after the initial O(n²) scan.
+    fn trade_by_gain(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        allocation: &mut Allocation,
+        prices: &PriceVector,
+        (pairs, ties): (&[(usize, usize)], &mut Ties),
+        observer: &mut dyn AuctionObserver,
+    ) -> bool {
+        let n = agents.len();
+        let mut order = vec![0; n * n];
+        for (position, &(i, j)) in pairs.iter().enumerate() {
+            order[i * n + j] = position;
+        }
+        let evaluate = |(i, j): (usize, usize),
+                        allocation: &Allocation,
+                        ties: &mut Ties,
+                        versions: &[usize]| {
+            let (i, j) = (i.min(j), i.max(j));
+            let trade = self.try_trade(&agents[i], &agents[j], allocation, prices, ties)?;
+            if !trade.is_admissible(&self.constraints, goods)
+                || !trade.is_accepted(allocation, self.improvement)
+            {
+                return None;
+            }
+            let gain = trade
+                .holdings
+                .iter()
+                .map(|(agent, new, _)| {
+                    let old = allocation.get_bundle(agent.id).map_or(0.0, |b| agent.preference(b));
+                    agent.preference(new) - old
+                })
+                .sum();
+            Some(Candidate {
+                gain,
+                order: order[i * n + j],
+                pair: (i, j),
+                stamps: (versions[i], versions[j]),
+                trade,
+            })
+        };
+
+        let mut versions = vec![0; n];
+        let mut queue: BinaryHeap<Candidate> = pairs
+            .iter()
+            .filter_map(|&pair| evaluate(pair, allocation, ties, &versions))
+            .collect();
+
+        let mut improved = false;
+        while let Some(candidate) = queue.pop() {
+            let (i, j) = candidate.pair;
+            if candidate.stamps != (versions[i], versions[j]) {
+                continue;
+            }
+            candidate.trade.apply(allocation);
+            observer.on_trade(&[agents[i].id, agents[j].id], allocation);
+            improved = true;
+            versions[i] += 1;
+            versions[j] += 1;
+            let affected = (0..n)
+                .filter(|&k| k != i)
+                .map(|k| (i, k))
+                .chain((0..n).filter(|&k| k != i && k != j).map(|k| (j, k)));
+            queue.extend(affected.filter_map(|pair| evaluate(pair, allocation, ties, &versions)));
+        }
+        improved
+    }
+
+    /// Find a cycle of 3 to `max_cycle_length` agents in which every agent
+    /// can hold the next agent's bundle and the move is an improvement:
+    /// every agent strictly prefers it, or under weak improvement none
+    /// minds it and some agent strictly prefers it
+    fn find_trading_cycle(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        allocation: &Allocation,
+    ) -> Option<Vec<usize>> {
+        let bundles: Vec<Option<&Bundle>> = agents
+            .iter()
+            .map(|agent| allocation.get_bundle(agent.id))
+            .collect();
+        // wants[i][j]: agent i accepts j's bundle, and whether it strictly prefers it
+        let wants: Vec<Vec<Option<bool>>> = (0..agents.len())
+            .map(|i| {
+                (0..agents.len())
+                    .map(|j| match (bundles[i], bundles[j]) {
+                        (Some(own), Some(other)) if i != j => {
+                            let strict = agents[i].prefers(other, own);
+                            let accepts = strict
+                                || self.improvement == Improvement::Weak
+                                    && agents[i].prefers_or_indifferent(other, own);
+                            let units = allocation.get_units(agents[j].id);
+                            (accepts
+                                && agents[i].within_capacity(other, units)
+                                && self.constraints.permits(agents[i].id, other, goods))
+                            .then_some(strict)
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Each cycle is found from its smallest member to avoid rotations
+        let mut path = Vec::with_capacity(self.max_cycle_length);
+        for start in 0..agents.len() {
+            path.push(start);
+            if self.extend_cycle(&wants, &mut path, false) {
+                return Some(path);
+            }
+            path.pop();
+        }
+        None
+    }
+
+    /// `gained`: some agent on the path strictly prefers its next bundle
+    fn extend_cycle(
+        &self,
+        wants: &[Vec<Option<bool>>],
+        path: &mut Vec<usize>,
+        gained: bool,
+    ) -> bool {
+        let start = path[0];
+        let last = *path.last().unwrap();
+        if path.len() >= 3 && wants[last][start].is_some_and(|strict| strict || gained) {
+            return true;
+        }
+        if path.len() == self.max_cycle_length {
+            return false;
+        }
+        for next in (start + 1)..wants.len() {
+            let Some(strict) = wants[last][next] else {
+                continue;
+            };
+            if !path.contains(&next) {
+                path.push(next);
+                if self.extend_cycle(wants, path, gained || strict) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+        false
+    }
+
     /// Try a trade between two agents
-    fn try_trade(
+    fn try_trade<'a>(
         &self,
-        agent1: &Agent,
-        agent2: &Agent,
+        agent1: &'a Agent,
+        agent2: &'a Agent,
         current_allocation: &Allocation,
         _prices: &PriceVector,
-    ) -> Option<Allocation> {
-        let bundle1 = current_allocation.get_bundle(&agent1.id)?.clone();
-        let bundle2 = current_allocation.get_bundle(&agent2.id)?.clone();
+        ties: &mut Ties,
+    ) -> Option<Trade<'a>> {
+        let bundle1 = current_allocation.get_bundle(agent1.id)?;
+        let bundle2 = current_allocation.get_bundle(agent2.id)?;
 
-        // Try swapping bundles
-        let mut new_allocation = current_allocation.clone();
-        new_allocation.assign(agent1.id.clone(), bundle2.clone());
-        new_allocation.assign(agent2.id.clone(), bundle1.clone());
-
-        // Check if both agents are better off
-        let agent1_better = agent1.prefers(&bundle2, &bundle1);
-        let agent2_better = agent2.prefers(&bundle1, &bundle2);
-
-        if agent1_better && agent2_better {
-            Some(new_allocation)
+        // Try swapping bundles (units travel with them) if both agents consent
+        let swap = [(agent1, bundle2, bundle1), (agent2, bundle1, bundle2)];
+        if self.improvement.consents(&swap) {
+            let units = |agent: &Agent| {
+                current_allocation.get_units(agent.id).cloned().unwrap_or_default()
+            };
+            Some(Trade {
+                holdings: vec![
+                    (agent1, bundle2.clone(), units(agent2)),
+                    (agent2, bundle1.clone(), units(agent1)),
+                ],
+            })
         } else {
-            None
+            self.try_partial_trade(agent1, agent2, current_allocation, ties)
         }
     }
 
-    /// Check if new allocation is Pareto improving
-    fn is_pareto_improving(
+    /// Try moving goods between two agents instead of whole bundles: one-sided
+    /// transfers and exchanges of up to `max_transfer_size` goods each way.
+    /// Accepts the first move both agents consent to under `improvement`.
+    /// Skipped when either agent holds multiple units of a good.
+    fn try_partial_trade<'a>(
         &self,
-        agents: &[Agent],
-        old_allocation: &Allocation,
-        new_allocation: &Allocation,
-    ) -> bool {
-        let mut at_least_one_better = false;
+        agent1: &'a Agent,
+        agent2: &'a Agent,
+        current_allocation: &Allocation,
+        ties: &mut Ties,
+    ) -> Option<Trade<'a>> {
+        if self.max_transfer_size == 0 {
+            return None;
+        }
+        let multi_unit = |agent: &Agent| {
+            current_allocation
+                .get_units(agent.id)
+                .is_some_and(|units| !units.is_empty())
+        };
+        if multi_unit(agent1) || multi_unit(agent2) {
+            return None;
+        }
 
-        for agent in agents {
-            let old_bundle = old_allocation.get_bundle(&agent.id);
-            let new_bundle = new_allocation.get_bundle(&agent.id);
-
-            if let (Some(old), Some(new)) = (old_bundle, new_bundle) {
-                if agent.prefers(new, old) {
-                    at_least_one_better = true;
-                } else if agent.prefers(old, new) {
-                    // Someone is worse off, not Pareto improving
-                    return false;
+        let bundle1 = current_allocation.get_bundle(agent1.id)?;
+        let bundle2 = current_allocation.get_bundle(agent2.id)?;
+        let mut subsets1 = small_subsets(bundle1, self.max_transfer_size);
+        let mut subsets2 = small_subsets(bundle2, self.max_transfer_size);
+        ties.shuffle(&mut subsets1);
+        ties.shuffle(&mut subsets2);
+
+        for give in &subsets1 {
+            for take in &subsets2 {
+                if give.is_empty() && take.is_empty() {
+                    continue;
+                }
+                let new1: Bundle = bundle1.difference(give).chain(take).cloned().collect();
+                let new2: Bundle = bundle2.difference(take).chain(give).cloned().collect();
+                let moves = [(agent1, &new1, bundle1), (agent2, &new2, bundle2)];
+                if self.improvement.consents(&moves) {
+                    return Some(Trade {
+                        holdings: vec![(agent1, new1, Units::new()), (agent2, new2, Units::new())],
+                    });
                 }
             }
         }
-
-        at_least_one_better
+        None
     }
 
     /// Verify approximate feasibility
     /// Checks that no good is over-allocated (within epsilon tolerance)
+    /// and that every agent's bundle respects the constraints
     pub fn verify_feasibility(
         &self,
         allocation: &Allocation,
         goods: &[Good],
     ) -> bool {
-        for good in goods {
-            let mut count = 0;
-            for bundle in allocation.assignments.values() {
-                if bundle.contains(good) {
-                    count += 1;
-                }
-            }
-            // Each good should be allocated at most once (within epsilon)
-            if count as f64 > 1.0 + self.epsilon {
-                return false;
-            }
-        }
-        true
+        goods.iter().all(|good| {
+            // Units allocated should not exceed supply (within epsilon)
+            allocation.allocated_units(good.id) as f64 <= good.supply as f64 + self.epsilon.global
+        }) && self.constraints.is_satisfied(allocation, goods)
+    }
+
+    /// Verify that no agent holds more units than its capacity
+    pub fn verify_capacity(&self, agents: &[Agent], allocation: &Allocation) -> bool {
+        agents.iter().all(|agent| {
+            allocation.get_bundle(agent.id).is_none_or(|bundle| {
+                agent.within_capacity(bundle, allocation.get_units(agent.id))
+            })
+        })
+    }
+
+    /// Verify that goods acquired through the mechanism meet their reserve
+    /// Each agent must value its bundle at least at the total reserve of
+    /// the goods it did not bring itself; otherwise those goods should have
+    /// stayed unallocated
+    pub fn verify_reserve_prices(
+        &self,
+        agents: &[Agent],
+        allocation: &Allocation,
+    ) -> bool {
+        agents.iter().all(|agent| {
+            let Some(bundle) = allocation.get_bundle(agent.id) else {
+                return true;
+            };
+            let reserve: f64 = bundle
+                .iter()
+                .filter(|good| !agent.endowment.contains(*good))
+                .map(|good| good.reserve_price * allocation.unit_count(agent.id, good.id) as f64)
+                .sum();
+            agent.preference(bundle) + self.epsilon.for_agent(agent.id) >= reserve
+        })
+    }
+
+    /// Goods not held by any agent, sorted by ID
+    pub fn unallocated_goods(&self, allocation: &Allocation, goods: &[Good]) -> Vec<GoodId> {
+        let mut unallocated: Vec<GoodId> = goods
+            .iter()
+            .filter(|good| !allocation.assignments().values().any(|b| b.contains(*good)))
+            .map(|good| good.id)
+            .collect();
+        unallocated.sort_by_key(|id| id.as_str());
+        unallocated
+    }
+
+    /// Verify approximate feasibility of a fractional allocation
+    /// Checks that the expected number of holders of each good does not exceed its supply
+    pub fn verify_fractional_feasibility(
+        &self,
+        allocation: &FractionalAllocation,
+        goods: &[Good],
+    ) -> bool {
+        goods.iter().all(|good| {
+            let expected: f64 = allocation
+                .shares
+                .keys()
+                .map(|agent_id| allocation.good_share(agent_id, good.id))
+                .sum();
+            expected <= good.supply as f64 + self.epsilon.global
+        })
     }
 
     /// Verify individual rationality
@@ -163,10 +966,10 @@ impl BRACEMechanism {
         allocation: &Allocation,
     ) -> bool {
         for agent in agents {
-            let allocated = allocation.get_bundle(&agent.id);
+            let allocated = allocation.get_bundle(agent.id);
             if let Some(bundle) = allocated {
                 // Agent should not prefer endowment over allocation
-                if agent.prefers(&agent.endowment, bundle) {
+                if !agent.prefers_or_indifferent(bundle, &agent.endowment) {
                     return false;
                 }
             }
@@ -174,6 +977,91 @@ impl BRACEMechanism {
         true
     }
 
+    /// Verify individual rationality with quasi-linear utilities: counting
+    /// what each agent pays (missing = 0), no agent ends up with less utility
+    /// than keeping its endowment and its money
+    pub fn verify_quasi_linear_individual_rationality(
+        &self,
+        agents: &[Agent],
+        allocation: &Allocation,
+        payments: &HashMap<AgentId, f64>,
+    ) -> bool {
+        let empty = Bundle::new();
+        agents.iter().all(|agent| {
+            let bundle = allocation.get_bundle(agent.id).unwrap_or(&empty);
+            let payment = payments.get(&agent.id).copied().unwrap_or(0.0);
+            agent.utility(bundle, payment) >= agent.utility(&agent.endowment, 0.0) - 1e-9
+        })
+    }
+
+    /// Verify that no agent needs more budget relaxation than its epsilon allows
+    pub fn verify_budget_relaxation(
+        &self,
+        agents: &[Agent],
+        allocation: &Allocation,
+        prices: &PriceVector,
+    ) -> bool {
+        agents.iter().all(|agent| {
+            allocation.get_bundle(agent.id).is_none_or(|bundle| {
+                prices.budget_relaxation(agent, bundle, allocation.get_units(agent.id))
+                    <= self.epsilon.for_agent(agent.id) + 1e-9
+            })
+        })
+    }
+
+    /// Verify envy-freeness at the quoted prices
+    /// No agent strictly prefers another agent's bundle at its price
+    pub fn verify_envy_freeness(
+        &self,
+        agents: &[Agent],
+        allocation: &Allocation,
+        prices: &PriceVector,
+    ) -> bool {
+        self.verify_epsilon_envy_freeness(agents, allocation, prices, 1e-9)
+    }
+
+    /// Verify epsilon-envy-freeness at the quoted prices
+    /// No agent gains more than `epsilon` net utility from another agent's bundle
+    pub fn verify_epsilon_envy_freeness(
+        &self,
+        agents: &[Agent],
+        allocation: &Allocation,
+        prices: &PriceVector,
+        epsilon: f64,
+    ) -> bool {
+        let empty = Bundle::new();
+        let net = |agent: &Agent, owner: &Agent| {
+            let bundle = allocation.get_bundle(owner.id).unwrap_or(&empty);
+            agent.preference(bundle)
+                - prices.bundle_price_units(bundle, allocation.get_units(owner.id))
+        };
+        agents.iter().all(|agent| {
+            let own = net(agent, agent);
+            agents
+                .iter()
+                .filter(|other| other.id != agent.id)
+                .all(|other| net(agent, other) <= own + epsilon)
+        })
+    }
+
+    /// Verify envy-freeness up to one good (no money): any envy an agent
+    /// has towards another bundle disappears after removing some single good from it
+    pub fn verify_ef1(&self, agents: &[Agent], allocation: &Allocation) -> bool {
+        let empty = Bundle::new();
+        agents.iter().all(|agent| {
+            let own = agent.preference(allocation.get_bundle(agent.id).unwrap_or(&empty));
+            agents.iter().filter(|other| other.id != agent.id).all(|other| {
+                let theirs = allocation.get_bundle(other.id).unwrap_or(&empty);
+                agent.preference(theirs) <= own + 1e-9
+                    || theirs.iter().any(|good| {
+                        let mut rest = theirs.clone();
+                        rest.remove(good);
+                        agent.preference(&rest) <= own + 1e-9
+                    })
+            })
+        })
+    }
+
     /// Verify ordinal efficiency
     /// No other allocation should make all agents strictly better off
     pub fn verify_ordinal_efficiency(
@@ -188,8 +1076,8 @@ impl BRACEMechanism {
         // For now, we check that no simple swap would make both agents better off
         for i in 0..agents.len() {
             for j in (i + 1)..agents.len() {
-                let bundle_i = allocation.get_bundle(&agents[i].id);
-                let bundle_j = allocation.get_bundle(&agents[j].id);
+                let bundle_i = allocation.get_bundle(agents[i].id);
+                let bundle_j = allocation.get_bundle(agents[j].id);
                 
                 if let (Some(bi), Some(bj)) = (bundle_i, bundle_j) {
                     // If swapping would make both better, not efficient
@@ -201,5 +1089,121 @@ impl BRACEMechanism {
         }
         true
     }
+    /// Verify sd-efficiency of a random allocation: no other random
+    /// allocation gives every agent a lottery that first-order
+    /// stochastically dominates its own (see `lp::sd_dominating_allocation`)
+    pub fn verify_sd_efficiency(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        allocation: &FractionalAllocation,
+    ) -> bool {
+        sd_dominating_allocation(agents, goods, allocation).is_none()
+    }
+
+    /// Verify false-name proofness: no agent gains by splitting into two
+    /// identities. Searches a bounded number of splits per agent, so it is
+    /// only meaningful for small instances
+    pub fn verify_false_name_proofness(&self, agents: &[Agent], goods: &[Good]) -> bool {
+        audit_false_names(self, agents, goods, &SybilConfig::default())
+            .iter()
+            .all(|report| report.gain <= 1e-9)
+    }
+
+    /// Verify core stability: no coalition can block the allocation
+    pub fn verify_core_stability(&self, agents: &[Agent], allocation: &Allocation) -> bool {
+        self.find_blocking_coalition(agents, allocation).is_none()
+    }
+
+    /// Find a coalition that could reallocate its pooled endowments so that
+    /// every member is strictly better off than under `allocation`.
+    /// Coalitions are tried smallest first. Enumerates all coalitions, so it
+    /// is only suitable for small instances.
+    pub fn find_blocking_coalition(
+        &self,
+        agents: &[Agent],
+        allocation: &Allocation,
+    ) -> Option<Vec<AgentId>> {
+        let empty = Bundle::new();
+        let current: Vec<&Bundle> = agents
+            .iter()
+            .map(|agent| allocation.get_bundle(agent.id).unwrap_or(&empty))
+            .collect();
+
+        for size in 1..=agents.len() {
+            for members in (0..agents.len()).combinations(size) {
+                // Pool the members' endowments
+                let mut pool: HashMap<GoodId, u32> = HashMap::new();
+                for &i in &members {
+                    let agent = &agents[i];
+                    for good in &agent.endowment {
+                        *pool.entry(good.id).or_insert(0) +=
+                            unit_count(&agent.endowment, Some(&agent.endowment_units), good.id);
+                    }
+                }
+
+                // Bundles each member strictly prefers that the pool could cover
+                let candidates: Vec<Vec<(&Bundle, Option<&Units>)>> = members
+                    .iter()
+                    .map(|&i| {
+                        let agent = &agents[i];
+                        agent
+                            .preference_bundles()
+                            .iter()
+                            .filter(|bundle| agent.prefers(bundle, current[i]))
+                            .filter(|bundle| bundle.iter().all(|g| pool.contains_key(&g.id)))
+                            .map(|bundle| (bundle, agent.bundle_units(bundle)))
+                            .collect()
+                    })
+                    .collect();
+                if candidates.iter().any(|options| options.is_empty()) {
+                    continue;
+                }
+
+                if Self::pool_covers(&candidates, 0, &mut pool) {
+                    return Some(members.iter().map(|&i| agents[i].id).collect());
+                }
+            }
+        }
+        None
+    }
+
+    /// Whether members from `index` on can each get a candidate bundle from the pool
+    fn pool_covers(
+        candidates: &[Vec<(&Bundle, Option<&Units>)>],
+        index: usize,
+        pool: &mut HashMap<GoodId, u32>,
+    ) -> bool {
+        let Some(options) = candidates.get(index) else {
+            return true;
+        };
+        for &(bundle, units) in options {
+            let fits = bundle
+                .iter()
+                .all(|good| unit_count(bundle, units, good.id) <= pool[&good.id]);
+            if !fits {
+                continue;
+            }
+            for good in bundle {
+                *pool.get_mut(&good.id).unwrap() -= unit_count(bundle, units, good.id);
+            }
+            let covered = Self::pool_covers(candidates, index + 1, pool);
+            for good in bundle {
+                *pool.get_mut(&good.id).unwrap() += unit_count(bundle, units, good.id);
+            }
+            if covered {
+                return true;
+            }
+        }
+        false
+    }
 }
 
+/// All subsets of a bundle with at most `max_size` goods (including the empty set)
+fn small_subsets(bundle: &Bundle, max_size: usize) -> Vec<Bundle> {
+    let goods: Vec<&Good> = bundle.iter().sorted_by_key(|good| good.id.as_str()).collect();
+    (0..=max_size.min(goods.len()))
+        .flat_map(|size| goods.iter().copied().combinations(size))
+        .map(|subset| subset.into_iter().cloned().collect())
+        .collect()
+}
@@ -0,0 +1,149 @@
+use crate::ids::GoodId;
+use crate::types::{Bundle, Good};
+use std::collections::HashMap;
+
+/// Dense indices for a fixed list of goods
+#[derive(Debug, Clone, Default)]
+pub struct GoodIndex {
+    ids: Vec<GoodId>,
+    index: HashMap<GoodId, usize>,
+}
+
+impl GoodIndex {
+    pub fn new(goods: &[Good]) -> Self {
+        let ids: Vec<GoodId> = goods.iter().map(|g| g.id).collect();
+        let index = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        Self { ids, index }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn index_of(&self, good_id: GoodId) -> Option<usize> {
+        self.index.get(&good_id).copied()
+    }
+
+    pub fn id_of(&self, index: usize) -> Option<GoodId> {
+        self.ids.get(index).copied()
+    }
+
+    /// Convert a bundle, ignoring goods that are not indexed
+    pub fn compact(&self, bundle: &Bundle) -> CompactBundle {
+        let mut compact = CompactBundle::new();
+        for good in bundle {
+            if let Some(i) = self.index_of(good.id) {
+                compact.insert(i);
+            }
+        }
+        compact
+    }
+
+    /// Convert back to a bundle using the goods the index was built from
+    pub fn expand(&self, compact: &CompactBundle, goods: &[Good]) -> Bundle {
+        compact
+            .iter()
+            .filter_map(|i| goods.get(i))
+            .cloned()
+            .collect()
+    }
+}
+
+/// A bundle stored as a bitset over `GoodIndex` positions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CompactBundle {
+    /// Bit words, with no trailing zero words so equal sets compare equal
+    words: Vec<u64>,
+}
+
+impl CompactBundle {
+    pub fn new() -> Self {
+        Self { words: Vec::new() }
+    }
+
+    pub fn insert(&mut self, index: usize) {
+        let word = index / 64;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (index % 64);
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if let Some(word) = self.words.get_mut(index / 64) {
+            *word &= !(1 << (index % 64));
+        }
+        self.normalize();
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.words
+            .get(index / 64)
+            .is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let len = self.words.len().max(other.words.len());
+        let words = (0..len).map(|i| self.word(i) | other.word(i)).collect();
+        Self { words }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let len = self.words.len().min(other.words.len());
+        let mut result = Self {
+            words: (0..len).map(|i| self.words[i] & other.words[i]).collect(),
+        };
+        result.normalize();
+        result
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = Self {
+            words: (0..self.words.len())
+                .map(|i| self.words[i] & !other.word(i))
+                .collect(),
+        };
+        result.normalize();
+        result
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        (0..self.words.len()).all(|i| self.words[i] & !other.word(i) == 0)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .all(|(a, b)| a & b == 0)
+    }
+
+    /// Indices in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, &word)| {
+            (0..64).filter(move |bit| word & (1 << bit) != 0).map(move |bit| w * 64 + bit)
+        })
+    }
+
+    fn word(&self, i: usize) -> u64 {
+        self.words.get(i).copied().unwrap_or(0)
+    }
+
+    fn normalize(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::ids::{AgentId, GoodId};
+use crate::types::{Allocation, Bundle, Category, Good};
+use serde::{Deserialize, Serialize};
+
+/// Instance-level rules on which goods may end up together
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Constraints {
+    /// Groups of mutually conflicting goods (e.g. interfering licenses):
+    /// no agent may hold more than one good from a group
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<Vec<GoodId>>,
+    /// Per-category allocation rules (see `Good::category`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<CategoryRule>,
+}
+
+/// Limits on how the goods of one category are allocated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryRule {
+    pub category: Category,
+    /// Most goods of the category one agent may hold (None = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_per_agent: Option<u32>,
+    /// Agents allowed to hold goods of the category (None = everyone)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eligible: Option<Vec<AgentId>>,
+}
+
+impl CategoryRule {
+    pub fn new(category: impl Into<Category>) -> Self {
+        Self {
+            category: category.into(),
+            max_per_agent: None,
+            eligible: None,
+        }
+    }
+
+    /// Let each agent hold at most `max` goods of the category
+    pub fn with_max_per_agent(mut self, max: u32) -> Self {
+        self.max_per_agent = Some(max);
+        self
+    }
+
+    /// Only let these agents hold goods of the category
+    pub fn with_eligible<A: Into<AgentId>>(mut self, agents: impl IntoIterator<Item = A>) -> Self {
+        self.eligible = Some(agents.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Whether `agent` may hold `count` goods of the category
+    fn allows(&self, agent: AgentId, count: u32) -> bool {
+        count == 0
+            || (self.max_per_agent.is_none_or(|max| count <= max)
+                && self.eligible.as_ref().is_none_or(|eligible| eligible.contains(&agent)))
+    }
+}
+
+impl Constraints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forbid any agent from holding two of `goods`
+    pub fn with_conflict<G: Into<GoodId>>(mut self, goods: impl IntoIterator<Item = G>) -> Self {
+        self.conflicts.push(goods.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Add a per-category rule
+    pub fn with_category_rule(mut self, rule: CategoryRule) -> Self {
+        self.categories.push(rule);
+        self
+    }
+
+    /// Whether no rules are set
+    pub fn is_empty(&self) -> bool {
+        self.conflicts.is_empty() && self.categories.is_empty()
+    }
+
+    /// Every good mentioned by a rule
+    pub fn goods(&self) -> impl Iterator<Item = GoodId> + '_ {
+        self.conflicts.iter().flatten().copied()
+    }
+
+    /// Whether `agent` may hold `bundle`. Categories are looked up in `goods`.
+    pub fn permits(&self, agent: AgentId, bundle: &Bundle, goods: &[Good]) -> bool {
+        let conflicts_ok = self.conflicts.iter().all(|group| {
+            group.iter().filter(|&&id| bundle.iter().any(|g| g.id == id)).count() <= 1
+        });
+        conflicts_ok
+            && self.categories.iter().all(|rule| {
+                let count = bundle
+                    .iter()
+                    .filter(|held| {
+                        goods.iter().any(|g| {
+                            g.id == held.id && g.category.as_ref() == Some(&rule.category)
+                        })
+                    })
+                    .count() as u32;
+                rule.allows(agent, count)
+            })
+    }
+
+    /// Whether every bundle in the allocation is permitted
+    pub fn is_satisfied(&self, allocation: &Allocation, goods: &[Good]) -> bool {
+        allocation
+            .iter()
+            .all(|(&agent, bundle)| self.permits(agent, bundle, goods))
+    }
+}
@@ -0,0 +1,109 @@
+use crate::ids::AgentId;
+use crate::types::{Agent, Good};
+use crate::wdp::solve_exact;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::HashMap;
+
+/// How Shapley values are computed
+#[derive(Debug, Clone)]
+pub struct ShapleyConfig {
+    /// Largest number of agents for which every coalition is enumerated
+    pub exact_limit: usize,
+    /// Random agent orders sampled above `exact_limit`
+    pub samples: usize,
+    /// Seed for the sampled orders
+    pub seed: u64,
+}
+
+impl Default for ShapleyConfig {
+    fn default() -> Self {
+        Self {
+            exact_limit: 8,
+            samples: 1000,
+            seed: 0,
+        }
+    }
+}
+
+/// Optimal welfare the `members` (indices into `agents`) reach on their own
+pub fn coalition_welfare(agents: &[Agent], goods: &[Good], members: &[usize]) -> f64 {
+    let coalition: Vec<Agent> = members.iter().map(|&i| agents[i].clone()).collect();
+    solve_exact(&coalition, goods).1
+}
+
+/// Shapley value of every agent in the cooperative game whose worth of a
+/// coalition is its optimal welfare: each agent's average marginal
+/// contribution over all orders in which the agents could arrive. The
+/// values sum to the optimal total welfare, so they can be read as a fair
+/// division of the surplus. Exact up to `config.exact_limit` agents
+/// (one winner determination per coalition), sampled beyond that.
+pub fn shapley_values(
+    agents: &[Agent],
+    goods: &[Good],
+    config: &ShapleyConfig,
+) -> HashMap<AgentId, f64> {
+    let n = agents.len();
+    let values = if n <= config.exact_limit {
+        exact_shapley(agents, goods)
+    } else {
+        sampled_shapley(agents, goods, config)
+    };
+    agents.iter().map(|agent| agent.id).zip(values).collect()
+}
+
+fn exact_shapley(agents: &[Agent], goods: &[Good]) -> Vec<f64> {
+    let n = agents.len();
+    let worth: Vec<f64> = (0..1usize << n)
+        .map(|mask| {
+            let members: Vec<usize> = (0..n).filter(|i| mask & (1 << i) != 0).collect();
+            coalition_welfare(agents, goods, &members)
+        })
+        .collect();
+
+    // weight[s] = s! (n - s - 1)! / n!
+    let mut factorial = vec![1.0; n + 1];
+    for k in 1..=n {
+        factorial[k] = factorial[k - 1] * k as f64;
+    }
+    let weight = |size: usize| factorial[size] * factorial[n - size - 1] / factorial[n];
+
+    (0..n)
+        .map(|i| {
+            (0..1usize << n)
+                .filter(|mask| mask & (1 << i) == 0)
+                .map(|mask| {
+                    let size = mask.count_ones() as usize;
+                    weight(size) * (worth[mask | (1 << i)] - worth[mask])
+                })
+                .sum()
+        })
+        .collect()
+}
+
+fn sampled_shapley(agents: &[Agent], goods: &[Good], config: &ShapleyConfig) -> Vec<f64> {
+    let n = agents.len();
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut order: Vec<usize> = (0..n).collect();
+    let mut worth: HashMap<Vec<usize>, f64> = HashMap::new();
+    let mut totals = vec![0.0; n];
+    let samples = config.samples.max(1);
+
+    for _ in 0..samples {
+        order.shuffle(&mut rng);
+        let mut members = Vec::with_capacity(n);
+        let mut previous = 0.0;
+        for &i in &order {
+            members.push(i);
+            let mut key = members.clone();
+            key.sort_unstable();
+            let current = *worth
+                .entry(key)
+                .or_insert_with(|| coalition_welfare(agents, goods, &members));
+            totals[i] += current - previous;
+            previous = current;
+        }
+    }
+    totals.into_iter().map(|total| total / samples as f64).collect()
+}
@@ -0,0 +1,181 @@
+use crate::brace::BRACEMechanism;
+use crate::ids::AgentId;
+use crate::pricing::PriceVector;
+use crate::types::{Agent, Allocation, Bundle, BundleKey, Good};
+use std::collections::HashSet;
+
+/// An agent the mechanism queries instead of reading its preferences
+pub trait Respondent {
+    fn id(&self) -> AgentId;
+
+    /// Goods the agent brings to the exchange
+    fn endowment(&self) -> Bundle;
+
+    /// Value query: what the agent would pay for `bundle`
+    fn value(&mut self, bundle: &Bundle) -> f64;
+
+    /// Demand query: a bundle maximizing value minus price at `prices`
+    /// (empty if nothing is worth its price)
+    fn demand(&mut self, prices: &PriceVector) -> Bundle;
+}
+
+/// A truthful agent answers from its own preferences
+impl Respondent for Agent {
+    fn id(&self) -> AgentId {
+        self.id
+    }
+
+    fn endowment(&self) -> Bundle {
+        self.endowment.clone()
+    }
+
+    fn value(&mut self, bundle: &Bundle) -> f64 {
+        self.preference(bundle)
+    }
+
+    fn demand(&mut self, prices: &PriceVector) -> Bundle {
+        prices
+            .demand_set(self)
+            .into_iter()
+            .next()
+            .filter(|bundle| prices.net_utility(self, bundle) > 0.0)
+            .unwrap_or_default()
+    }
+}
+
+impl<R: Respondent + ?Sized> Respondent for Box<R> {
+    fn id(&self) -> AgentId {
+        (**self).id()
+    }
+
+    fn endowment(&self) -> Bundle {
+        (**self).endowment()
+    }
+
+    fn value(&mut self, bundle: &Bundle) -> f64 {
+        (**self).value(bundle)
+    }
+
+    fn demand(&mut self, prices: &PriceVector) -> Bundle {
+        (**self).demand(prices)
+    }
+}
+
+/// Outcome of an elicitation run
+#[derive(Debug, Clone)]
+pub struct Elicitation {
+    pub allocation: Allocation,
+    pub prices: PriceVector,
+    /// What was learned: one agent per respondent, valuing the queried bundles
+    pub agents: Vec<Agent>,
+    pub value_queries: usize,
+    pub demand_queries: usize,
+    /// Allocations computed, one per round
+    pub rounds: usize,
+    /// Every respondent's demand at `prices` was known and no better (within
+    /// its epsilon) than its allocated bundle, so the allocation is an
+    /// equilibrium of the true preferences and not just of the learned ones
+    pub certified: bool,
+}
+
+/// Runs BRACE on preferences gathered by querying agents instead of
+/// requiring full preference lists up front.
+///
+/// Each round runs the mechanism on what has been learned so far, asks for
+/// the value of every allocated bundle not yet valued, and once those are
+/// known asks every respondent for its demand at the resulting prices.
+/// Demanded bundles that are new are valued and the round repeats; the run
+/// ends when a round learns nothing new.
+#[derive(Debug, Clone)]
+pub struct Elicitor {
+    pub mechanism: BRACEMechanism,
+    /// Rounds after which the run stops, certified or not
+    pub max_rounds: usize,
+}
+
+impl Elicitor {
+    pub fn new(mechanism: BRACEMechanism) -> Self {
+        Self {
+            mechanism,
+            max_rounds: 100,
+        }
+    }
+
+    /// Stop after at most `rounds` rounds
+    pub fn with_max_rounds(mut self, rounds: usize) -> Self {
+        self.max_rounds = rounds;
+        self
+    }
+
+    /// Elicit preferences from `respondents` and allocate `goods` among them
+    pub fn run<R: Respondent>(&self, respondents: &mut [R], goods: &[Good]) -> Elicitation {
+        let mut learned: Vec<Agent> = respondents
+            .iter()
+            .map(|respondent| Agent::new(respondent.id(), respondent.endowment()))
+            .collect();
+        let mut known: Vec<HashSet<BundleKey>> = vec![HashSet::new(); respondents.len()];
+        let mut value_queries = 0;
+        let mut demand_queries = 0;
+
+        // Ask for the value of a bundle unless it is already known
+        let mut learn = |i: usize, bundle: &Bundle, respondents: &mut [R], learned: &mut [Agent]| {
+            if bundle.is_empty() || !known[i].insert(BundleKey::from_bundle(bundle)) {
+                return false;
+            }
+            value_queries += 1;
+            learned[i].add_preference(bundle.clone(), respondents[i].value(bundle));
+            true
+        };
+
+        // The endowments ground individual rationality
+        for i in 0..respondents.len() {
+            let endowment = learned[i].endowment.clone();
+            learn(i, &endowment, respondents, &mut learned);
+        }
+
+        let mut rounds = 0;
+        loop {
+            rounds += 1;
+            let (allocation, prices) = self.mechanism.compute_allocation(&learned, goods);
+            let empty = Bundle::new();
+            let mut news = false;
+            for i in 0..respondents.len() {
+                let held = allocation.get_bundle(learned[i].id).unwrap_or(&empty);
+                news |= learn(i, held, respondents, &mut learned);
+            }
+
+            let mut certified = false;
+            if !news {
+                certified = true;
+                for i in 0..respondents.len() {
+                    let demanded = respondents[i].demand(&prices);
+                    demand_queries += 1;
+                    if learn(i, &demanded, respondents, &mut learned) {
+                        news = true;
+                        continue;
+                    }
+                    let agent = &learned[i];
+                    let held = allocation.get_bundle(agent.id).unwrap_or(&empty);
+                    let slack = self.mechanism.epsilon.for_agent(agent.id);
+                    let better = prices.net_utility(agent, &demanded);
+                    if better > prices.net_utility(agent, held) + slack {
+                        certified = false;
+                    }
+                }
+                certified &= !news;
+            }
+
+            if !news || rounds >= self.max_rounds {
+                return Elicitation {
+                    allocation,
+                    prices,
+                    agents: learned,
+                    value_queries,
+                    demand_queries,
+                    rounds,
+                    certified,
+                };
+            }
+        }
+    }
+}
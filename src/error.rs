@@ -0,0 +1,64 @@
+use crate::auction::MechanismKind;
+use crate::ids::{AgentId, GoodId};
+use crate::io::ParseError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Errors raised by the auction API
+#[derive(Debug, Error)]
+pub enum AuctionError {
+    #[error("duplicate good ID {}", .0.as_str())]
+    DuplicateGood(GoodId),
+    #[error("agent {} is endowed with unknown good {}", .agent.as_str(), .good.as_str())]
+    UnknownEndowedGood { agent: AgentId, good: GoodId },
+    #[error("agent {} values a bundle containing unknown good {}", .agent.as_str(), .good.as_str())]
+    UnknownPreferenceGood { agent: AgentId, good: GoodId },
+    #[error("agent {} has a non-finite value {value} for a bundle", .agent.as_str())]
+    NonFiniteValue { agent: AgentId, value: f64 },
+    #[error("duplicate agent ID {}", .0.as_str())]
+    DuplicateAgent(AgentId),
+    #[error("unknown agent {}", .0.as_str())]
+    UnknownAgent(AgentId),
+    #[error("agent {} is endowed with good {}, which is already committed", .agent.as_str(), .good.as_str())]
+    CommittedGood { agent: AgentId, good: GoodId },
+    #[error("constraint refers to unknown good {}", .0.as_str())]
+    UnknownConstrainedGood(GoodId),
+    #[error("allocation still improving after {iterations} iterations")]
+    NotConverged { iterations: usize },
+    #[error("agent {} is not assigned a lottery over single goods", .0.as_str())]
+    NotAnAssignment(AgentId),
+    #[error("good {} is assigned beyond its supply", .0.as_str())]
+    OverAssigned(GoodId),
+    #[error("mechanism {kind:?} does not support {setting}")]
+    UnsupportedSetting { kind: MechanismKind, setting: &'static str },
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+}
+
+/// A problem found by `AuctionInstance::validate`
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum Violation {
+    #[error("duplicate good ID {}", .0.as_str())]
+    DuplicateGood(GoodId),
+    #[error("duplicate agent ID {}", .0.as_str())]
+    DuplicateAgent(AgentId),
+    #[error("agent {} is endowed with unknown good {}", .agent.as_str(), .good.as_str())]
+    UnknownEndowedGood { agent: AgentId, good: GoodId },
+    #[error("agent {} values a bundle containing unknown good {}", .agent.as_str(), .good.as_str())]
+    UnknownPreferenceGood { agent: AgentId, good: GoodId },
+    #[error("agent {} has a non-finite value {value} for a bundle", .agent.as_str())]
+    NonFiniteValue { agent: AgentId, value: f64 },
+    #[error("constraint refers to unknown good {}", .0.as_str())]
+    UnknownConstrainedGood(GoodId),
+    #[error("good {} is endowed {endowed} times but has supply {supply}", .good.as_str())]
+    OverEndowed { good: GoodId, endowed: u32, supply: u32 },
+}
+
+/// An iteration cap hit before the computation settled
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+pub enum NonConvergence {
+    #[error("allocation still improving after {iterations} iterations")]
+    ImprovementLoop { iterations: usize },
+    #[error("prices still adjusting after {iterations} iterations")]
+    PriceAdjustment { iterations: usize },
+}
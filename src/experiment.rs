@@ -0,0 +1,158 @@
+use crate::auction::CombinatorialAuction;
+use crate::observer::{AuctionObserver, Flow};
+use crate::types::{Allocation, AuctionInstance};
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::Instant;
+
+/// Outcome of one instance in an experiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunStats {
+    pub seed: u64,
+    pub total_welfare: f64,
+    pub is_feasible: bool,
+    pub is_individually_rational: bool,
+    pub is_ordinal_efficient: bool,
+    /// Improvement-loop passes
+    pub iterations: usize,
+    pub runtime_ms: f64,
+    /// Why the instance could not be solved, if it failed
+    pub error: Option<String>,
+}
+
+/// Aggregate statistics over many random instances
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentSummary {
+    pub instances: usize,
+    pub failures: usize,
+    /// Means and fractions below are over the solved instances
+    pub mean_welfare: f64,
+    pub feasible_fraction: f64,
+    pub individually_rational_fraction: f64,
+    pub ordinal_efficient_fraction: f64,
+    pub mean_iterations: f64,
+    pub max_iterations: usize,
+    pub mean_runtime_ms: f64,
+    pub runs: Vec<RunStats>,
+}
+
+impl ExperimentSummary {
+    fn from_runs(runs: Vec<RunStats>) -> Self {
+        let solved: Vec<&RunStats> = runs.iter().filter(|run| run.error.is_none()).collect();
+        let count = solved.len().max(1) as f64;
+        let mean = |f: &dyn Fn(&RunStats) -> f64| solved.iter().map(|run| f(run)).sum::<f64>() / count;
+        let fraction = |f: &dyn Fn(&RunStats) -> bool| {
+            solved.iter().filter(|run| f(run)).count() as f64 / count
+        };
+
+        Self {
+            instances: runs.len(),
+            failures: runs.len() - solved.len(),
+            mean_welfare: mean(&|run| run.total_welfare),
+            feasible_fraction: fraction(&|run| run.is_feasible),
+            individually_rational_fraction: fraction(&|run| run.is_individually_rational),
+            ordinal_efficient_fraction: fraction(&|run| run.is_ordinal_efficient),
+            mean_iterations: mean(&|run| run.iterations as f64),
+            max_iterations: solved.iter().map(|run| run.iterations).max().unwrap_or(0),
+            mean_runtime_ms: mean(&|run| run.runtime_ms),
+            runs,
+        }
+    }
+
+    /// One CSV row per instance, with a header
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "seed,total_welfare,is_feasible,is_individually_rational,is_ordinal_efficient,iterations,runtime_ms,error\n",
+        );
+        for run in &self.runs {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                run.seed,
+                run.total_welfare,
+                run.is_feasible,
+                run.is_individually_rational,
+                run.is_ordinal_efficient,
+                run.iterations,
+                run.runtime_ms,
+                run.error.as_deref().unwrap_or("").replace(',', ";"),
+            ));
+        }
+        csv
+    }
+}
+
+/// Monte Carlo runner: solves many generated instances with BRACE
+pub struct Experiment;
+
+impl Experiment {
+    /// Solve `n` instances, the i-th built by `generator(seed + i)`, spread
+    /// across the available cores
+    pub fn run_many<G>(generator: G, n: usize, seed: u64) -> ExperimentSummary
+    where
+        G: Fn(u64) -> AuctionInstance + Sync,
+    {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get()).min(n.max(1));
+        let seeds: Vec<u64> = (0..n as u64).map(|i| seed.wrapping_add(i)).collect();
+        let chunk = n.div_ceil(threads).max(1);
+
+        let runs = thread::scope(|scope| {
+            let workers: Vec<_> = seeds
+                .chunks(chunk)
+                .map(|seeds| {
+                    let generator = &generator;
+                    scope.spawn(move || {
+                        seeds.iter().map(|&seed| run_one(generator(seed), seed)).collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("experiment worker panicked"))
+                .collect()
+        });
+
+        ExperimentSummary::from_runs(runs)
+    }
+}
+
+/// Counts improvement-loop passes
+#[derive(Default)]
+struct IterationCounter(usize);
+
+impl AuctionObserver for IterationCounter {
+    fn on_iteration_end(&mut self, _iteration: usize, _allocation: &Allocation) -> Flow {
+        self.0 += 1;
+        Flow::Continue
+    }
+}
+
+fn run_one(instance: AuctionInstance, seed: u64) -> RunStats {
+    let start = Instant::now();
+    let mut counter = IterationCounter::default();
+    let result =
+        CombinatorialAuction::from_instance(instance).and_then(|auction| auction.run_observed(&mut counter));
+    let runtime_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    match result {
+        Ok(result) => RunStats {
+            seed,
+            total_welfare: result.total_welfare,
+            is_feasible: result.is_feasible,
+            is_individually_rational: result.is_individually_rational,
+            is_ordinal_efficient: result.is_ordinal_efficient,
+            iterations: counter.0,
+            runtime_ms,
+            error: None,
+        },
+        Err(err) => RunStats {
+            seed,
+            total_welfare: 0.0,
+            is_feasible: false,
+            is_individually_rational: false,
+            is_ordinal_efficient: false,
+            iterations: counter.0,
+            runtime_ms,
+            error: Some(err.to_string()),
+        },
+    }
+}
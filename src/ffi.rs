@@ -0,0 +1,57 @@
+//! C ABI for embedding BRACE in other runtimes. The header is
+//! `include/brace.h`, generated with `cbindgen --config cbindgen.toml --output include/brace.h`.
+
+use crate::io::solve_json;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+/// The auction was solved; `json_out` holds the result
+pub const BRACE_OK: i32 = 0;
+/// A pointer was null or the input was not UTF-8; `json_out` is untouched
+pub const BRACE_INVALID_ARGUMENT: i32 = 1;
+/// The instance was rejected; `json_out` holds `{"error": "..."}`
+pub const BRACE_AUCTION_ERROR: i32 = 2;
+/// The solver panicked; `json_out` holds `{"error": "..."}`
+pub const BRACE_PANIC: i32 = 3;
+
+/// Run BRACE on a NUL-terminated JSON `AuctionInstance`.
+/// On success `*json_out` points to the JSON `AuctionResult`; on failure it
+/// points to an error object (see the status codes). Free it with `brace_string_free`.
+///
+/// # Safety
+/// `json_in` must be null or a valid NUL-terminated string, and `json_out`
+/// null or valid for writing a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn brace_solve(json_in: *const c_char, json_out: *mut *mut c_char) -> i32 {
+    if json_in.is_null() || json_out.is_null() {
+        return BRACE_INVALID_ARGUMENT;
+    }
+    let Ok(input) = CStr::from_ptr(json_in).to_str() else {
+        return BRACE_INVALID_ARGUMENT;
+    };
+
+    let (status, output) = match catch_unwind(AssertUnwindSafe(|| solve_json(input))) {
+        Ok(Ok(result)) => (BRACE_OK, result),
+        Ok(Err(err)) => (BRACE_AUCTION_ERROR, error_json(&err.to_string())),
+        Err(_) => (BRACE_PANIC, error_json("solver panicked")),
+    };
+    // JSON escapes control characters, so the output has no interior NUL
+    *json_out = CString::new(output).map_or(ptr::null_mut(), CString::into_raw);
+    status
+}
+
+/// Free a string returned by `brace_solve`. Null is ignored.
+///
+/// # Safety
+/// `s` must be null or a pointer returned by `brace_solve` that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn brace_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+fn error_json(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
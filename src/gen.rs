@@ -0,0 +1,588 @@
+use crate::types::{Agent, AuctionInstance, Bundle, Good};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// How bundle values are drawn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ValueDistribution {
+    /// Uniform in [min, max)
+    Uniform { min: f64, max: f64 },
+    /// Normal, truncated at zero
+    Normal { mean: f64, std_dev: f64 },
+    /// Sum of per-agent uniform good values in [min, max), scaled by
+    /// (1 + complementarity * (size - 1)) so bundles can be super- or subadditive
+    Additive {
+        min: f64,
+        max: f64,
+        complementarity: f64,
+    },
+    /// Additive over goods, where an agent's value for a good is
+    /// `mean + std_dev * (sqrt(correlation) * common + sqrt(1 - correlation) * private)`
+    /// for standard normal common (per good) and private (per agent and
+    /// good) shocks, truncated at zero: `correlation` is the correlation
+    /// between two agents' values for the same good
+    Correlated {
+        mean: f64,
+        std_dev: f64,
+        correlation: f64,
+    },
+    /// Common values: each good has one true value, uniform in [min, max),
+    /// and every agent values it at its own noisy estimate, off by at most
+    /// `noise` either way. Bundles are valued additively.
+    CommonValue { min: f64, max: f64, noise: f64 },
+}
+
+/// Source of bundle values for `generate_with`; implement it to plug in
+/// value distributions beyond `ValueDistribution`
+pub trait ValueModel {
+    /// Called once per instance before any agent is valued (e.g. to draw
+    /// values shared by all agents)
+    fn prepare(&mut self, _rng: &mut StdRng, _goods: &[Good]) {}
+
+    /// Called once per agent before its bundles are valued
+    fn start_agent(&mut self, _rng: &mut StdRng, _goods: &[Good]) {}
+
+    /// Value of `bundle` to the current agent
+    fn value(&mut self, rng: &mut StdRng, goods: &[Good], bundle: &Bundle) -> f64;
+}
+
+/// How goods are distributed as initial endowments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EndowmentScheme {
+    /// Nobody owns anything (pure auction)
+    Empty,
+    /// Goods dealt to agents in turn
+    RoundRobin,
+    /// Each good given to a uniformly random agent
+    Random,
+}
+
+/// Parameters for random instance generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratorConfig {
+    pub num_goods: usize,
+    pub num_agents: usize,
+    /// Random bundles per agent (besides the endowment)
+    pub bundles_per_agent: usize,
+    /// Largest random bundle size
+    pub max_bundle_size: usize,
+    pub values: ValueDistribution,
+    pub endowments: EndowmentScheme,
+    /// Whether agents declare a value for their own endowment
+    pub value_endowment: bool,
+    pub epsilon: f64,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            num_goods: 5,
+            num_agents: 3,
+            bundles_per_agent: 3,
+            max_bundle_size: 3,
+            values: ValueDistribution::Uniform {
+                min: 1.0,
+                max: 10.0,
+            },
+            endowments: EndowmentScheme::RoundRobin,
+            value_endowment: true,
+            epsilon: 0.01,
+        }
+    }
+}
+
+/// Generate a random instance; the same config and seed always give the same instance
+pub fn generate(config: &GeneratorConfig, seed: u64) -> AuctionInstance {
+    let mut values = DistributionModel {
+        distribution: &config.values,
+        common: Vec::new(),
+        good_values: Vec::new(),
+    };
+    generate_with(config, &mut values, seed)
+}
+
+/// Like `generate`, but with bundle values from `values` (`config.values` is ignored)
+pub fn generate_with(
+    config: &GeneratorConfig,
+    values: &mut dyn ValueModel,
+    seed: u64,
+) -> AuctionInstance {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let goods: Vec<Good> = (0..config.num_goods)
+        .map(|i| Good::new(format!("G{}", i), format!("Good {}", i)))
+        .collect();
+
+    let mut endowments: Vec<Bundle> = vec![Bundle::new(); config.num_agents];
+    if config.num_agents > 0 {
+        for (i, good) in goods.iter().enumerate() {
+            let owner = match config.endowments {
+                EndowmentScheme::Empty => continue,
+                EndowmentScheme::RoundRobin => i % config.num_agents,
+                EndowmentScheme::Random => rng.gen_range(0..config.num_agents),
+            };
+            endowments[owner].insert(good.clone());
+        }
+    }
+
+    values.prepare(&mut rng, &goods);
+    let mut agents = Vec::with_capacity(config.num_agents);
+    for (i, endowment) in endowments.into_iter().enumerate() {
+        let mut agent = Agent::new(format!("Agent{}", i), endowment);
+        values.start_agent(&mut rng, &goods);
+
+        if config.value_endowment && !agent.endowment.is_empty() {
+            let value = values.value(&mut rng, &goods, &agent.endowment);
+            agent.add_preference(agent.endowment.clone(), value);
+        }
+
+        let max_size = config.max_bundle_size.min(goods.len());
+        if max_size > 0 {
+            for _ in 0..config.bundles_per_agent {
+                let size = rng.gen_range(1..=max_size);
+                let bundle: Bundle = goods.choose_multiple(&mut rng, size).cloned().collect();
+                let value = values.value(&mut rng, &goods, &bundle);
+                agent.add_preference(bundle, value);
+            }
+        }
+        agents.push(agent);
+    }
+
+    AuctionInstance::new(agents, goods, config.epsilon)
+}
+
+/// Generate a random exchange instance from a seed.
+/// Goods are dealt round-robin as endowments, and each agent values its
+/// endowment plus `bundles_per_agent` random bundles of one to three goods.
+pub fn random_instance(
+    num_goods: usize,
+    num_agents: usize,
+    bundles_per_agent: usize,
+    seed: u64,
+) -> AuctionInstance {
+    let config = GeneratorConfig {
+        num_goods,
+        num_agents,
+        bundles_per_agent,
+        ..GeneratorConfig::default()
+    };
+    generate(&config, seed)
+}
+
+/// CATS bid distributions (Leyton-Brown, Pearson and Shoham), which give
+/// instances the complement and substitute structure of real auctions.
+/// Agents own nothing and each bids on substitutable bundles.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CatsDistribution {
+    /// Goods are roads between cities placed at random in the unit square.
+    /// Each agent wants to connect two cities and bids the same value on
+    /// every route at most `budget_factor` times as long as the shortest.
+    Paths { budget_factor: f64 },
+    /// Goods are cells of a square grid and bundles are connected regions,
+    /// valued at the agent's cell values raised by `additivity` per extra cell
+    Regions { additivity: f64 },
+    /// Like `Regions`, but bundles grow along random pairwise closeness of
+    /// the goods instead of grid adjacency
+    Arbitrary { additivity: f64 },
+    /// Goods are consecutive time slots. Each agent has a job of fixed
+    /// length and bids on finishing it by successively later, less
+    /// valuable deadlines.
+    Scheduling,
+}
+
+/// Parameters for CATS-style instance generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatsConfig {
+    pub distribution: CatsDistribution,
+    pub num_goods: usize,
+    pub num_agents: usize,
+    /// Substitutable bundles each agent bids on (fewer if the structure allows fewer)
+    pub bids_per_agent: usize,
+    /// Largest bundle size (routes, regions or job lengths)
+    pub max_bundle_size: usize,
+    pub epsilon: f64,
+}
+
+impl Default for CatsConfig {
+    fn default() -> Self {
+        Self {
+            distribution: CatsDistribution::Regions { additivity: 0.2 },
+            num_goods: 16,
+            num_agents: 5,
+            bids_per_agent: 3,
+            max_bundle_size: 4,
+            epsilon: 0.01,
+        }
+    }
+}
+
+/// A bundle (indices into the goods) and its value
+type Bid = (Vec<usize>, f64);
+/// A point in the unit square
+type City = (f64, f64);
+/// The two cities a road joins and its length
+type Road = (usize, usize, f64);
+
+/// Generate a CATS-style instance; the same config and seed always give the same instance
+pub fn generate_cats(config: &CatsConfig, seed: u64) -> AuctionInstance {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let n = config.num_goods;
+    let max_size = config.max_bundle_size.min(n);
+
+    let (goods, bids): (Vec<Good>, Vec<Vec<Bid>>) = match config.distribution {
+        CatsDistribution::Paths { budget_factor } => {
+            let (goods, cities, roads) = road_network(&mut rng, n);
+            let bids = (0..config.num_agents)
+                .map(|_| {
+                    let limits = (config.bids_per_agent, max_size);
+                    route_bids(&mut rng, &cities, &roads, budget_factor, limits)
+                })
+                .collect();
+            (goods, bids)
+        }
+        CatsDistribution::Regions { additivity } => {
+            let width = (n as f64).sqrt().ceil().max(1.0) as usize;
+            let goods = (0..n)
+                .map(|i| {
+                    let name = format!("Cell ({}, {})", i % width, i / width);
+                    Good::new(format!("G{}", i), name)
+                })
+                .collect();
+            let adjacent = |a: usize, b: usize| {
+                let (ax, ay, bx, by) = (a % width, a / width, b % width, b / width);
+                ax.abs_diff(bx) + ay.abs_diff(by) == 1
+            };
+            let closeness: Vec<Vec<f64>> = (0..n)
+                .map(|a| (0..n).map(|b| if adjacent(a, b) { 1.0 } else { 0.0 }).collect())
+                .collect();
+            let bids = region_bids(&mut rng, &closeness, additivity, config, max_size);
+            (goods, bids)
+        }
+        CatsDistribution::Arbitrary { additivity } => {
+            let goods = (0..n)
+                .map(|i| Good::new(format!("G{}", i), format!("Good {}", i)))
+                .collect();
+            let raw: Vec<Vec<f64>> = (0..n).map(|_| (0..n).map(|_| rng.gen()).collect()).collect();
+            let closeness: Vec<Vec<f64>> = (0..n)
+                .map(|a| {
+                    (0..n)
+                        .map(|b| if a == b { 0.0 } else { raw[a.min(b)][a.max(b)] })
+                        .collect()
+                })
+                .collect();
+            let bids = region_bids(&mut rng, &closeness, additivity, config, max_size);
+            (goods, bids)
+        }
+        CatsDistribution::Scheduling => {
+            let goods = (0..n)
+                .map(|i| Good::new(format!("G{}", i), format!("Slot {}", i)))
+                .collect();
+            let bids = (0..config.num_agents)
+                .map(|_| job_bids(&mut rng, n, config.bids_per_agent, max_size))
+                .collect();
+            (goods, bids)
+        }
+    };
+
+    let agents = bids
+        .into_iter()
+        .enumerate()
+        .map(|(i, bids)| {
+            let mut agent = Agent::new(format!("Agent{}", i), Bundle::new());
+            for (members, value) in bids {
+                let bundle: Bundle = members.iter().map(|&g| goods[g].clone()).collect();
+                if !agent.preference_bundles().contains(&bundle) {
+                    agent.add_preference(bundle, value);
+                }
+            }
+            agent
+        })
+        .collect();
+    AuctionInstance::new(agents, goods, config.epsilon)
+}
+
+/// Cities in the unit square joined by the `num_roads` shortest possible roads
+fn road_network(
+    rng: &mut StdRng,
+    num_roads: usize,
+) -> (Vec<Good>, Vec<City>, Vec<Road>) {
+    // About three roads per city
+    let mut num_cities = (2 * num_roads / 3).max(2);
+    while num_cities * (num_cities - 1) / 2 < num_roads {
+        num_cities += 1;
+    }
+    let cities: Vec<City> = (0..num_cities).map(|_| (rng.gen(), rng.gen())).collect();
+    let distance =
+        |a: usize, b: usize| (cities[a].0 - cities[b].0).hypot(cities[a].1 - cities[b].1);
+
+    let mut pairs: Vec<Road> = (0..num_cities)
+        .flat_map(|a| (a + 1..num_cities).map(move |b| (a, b)))
+        .map(|(a, b)| (a, b, distance(a, b)))
+        .collect();
+    pairs.sort_by(|x, y| x.2.total_cmp(&y.2));
+    pairs.truncate(num_roads);
+
+    let goods = pairs
+        .iter()
+        .enumerate()
+        .map(|(i, (a, b, _))| Good::new(format!("G{}", i), format!("Road {}-{}", a, b)))
+        .collect();
+    (goods, cities, pairs)
+}
+
+/// Routes between two random connected cities, found by repeatedly
+/// penalizing the roads of earlier routes
+fn route_bids(
+    rng: &mut StdRng,
+    cities: &[City],
+    roads: &[Road],
+    budget_factor: f64,
+    (bids, max_size): (usize, usize),
+) -> Vec<Bid> {
+    for _ in 0..10 {
+        let from = rng.gen_range(0..cities.len());
+        let to = rng.gen_range(0..cities.len());
+        if from == to {
+            continue;
+        }
+        let lengths: Vec<f64> = roads.iter().map(|road| road.2).collect();
+        let Some(shortest) = shortest_route(cities.len(), roads, &lengths, from, to) else {
+            continue;
+        };
+        if shortest.len() > max_size {
+            continue;
+        }
+        let length = |route: &[usize]| route.iter().map(|&r| roads[r].2).sum::<f64>();
+        let budget = budget_factor.max(1.0) * length(&shortest);
+        let (dx, dy) = (cities[from].0 - cities[to].0, cities[from].1 - cities[to].1);
+        let value = 10.0 * dx.hypot(dy) * sample_uniform(rng, 1.0, budget_factor.max(1.0));
+
+        let mut penalized = lengths;
+        let mut routes: Vec<Vec<usize>> = Vec::new();
+        let mut route = shortest;
+        for _ in 0..bids * 3 {
+            for &r in &route {
+                penalized[r] *= 1.5;
+            }
+            let fits = length(&route) <= budget + 1e-9 && route.len() <= max_size;
+            if fits && !routes.contains(&route) {
+                routes.push(route.clone());
+                if routes.len() == bids {
+                    break;
+                }
+            }
+            match shortest_route(cities.len(), roads, &penalized, from, to) {
+                Some(next) => route = next,
+                None => break,
+            }
+        }
+        return routes.into_iter().map(|route| (route, value)).collect();
+    }
+    Vec::new()
+}
+
+/// Roads on a shortest route by `lengths` (Dijkstra), if the cities are connected
+fn shortest_route(
+    num_cities: usize,
+    roads: &[Road],
+    lengths: &[f64],
+    from: usize,
+    to: usize,
+) -> Option<Vec<usize>> {
+    let mut distance = vec![f64::INFINITY; num_cities];
+    let mut via: Vec<Option<usize>> = vec![None; num_cities];
+    let mut done = vec![false; num_cities];
+    distance[from] = 0.0;
+    while let Some(city) = (0..num_cities)
+        .filter(|&c| !done[c] && distance[c].is_finite())
+        .min_by(|&a, &b| distance[a].total_cmp(&distance[b]))
+    {
+        done[city] = true;
+        for (r, &(a, b, _)) in roads.iter().enumerate() {
+            let next = match city {
+                c if c == a => b,
+                c if c == b => a,
+                _ => continue,
+            };
+            if distance[city] + lengths[r] < distance[next] {
+                distance[next] = distance[city] + lengths[r];
+                via[next] = Some(r);
+            }
+        }
+    }
+    if !distance[to].is_finite() {
+        return None;
+    }
+    let mut route = Vec::new();
+    let mut city = to;
+    while let Some(r) = via[city] {
+        route.push(r);
+        city = if roads[r].0 == city { roads[r].1 } else { roads[r].0 };
+    }
+    Some(route)
+}
+
+/// Bundles grown from a seed good, each next good drawn with weight equal
+/// to its total closeness to the bundle. An agent's later bundles start
+/// inside its first, so they overlap and act as substitutes.
+fn region_bids(
+    rng: &mut StdRng,
+    closeness: &[Vec<f64>],
+    additivity: f64,
+    config: &CatsConfig,
+    max_size: usize,
+) -> Vec<Vec<Bid>> {
+    let n = closeness.len();
+    if n == 0 || max_size == 0 {
+        return vec![Vec::new(); config.num_agents];
+    }
+    let common: Vec<f64> = (0..n).map(|_| sample_uniform(rng, 1.0, 10.0)).collect();
+    (0..config.num_agents)
+        .map(|_| {
+            let own: Vec<f64> = common.iter().map(|c| c * sample_uniform(rng, 0.5, 1.5)).collect();
+            let mut bids: Vec<Bid> = Vec::new();
+            for _ in 0..config.bids_per_agent {
+                let seed = match bids.first() {
+                    Some((first, _)) => *first.choose(rng).expect("bundles are nonempty"),
+                    None => rng.gen_range(0..n),
+                };
+                let mut size = 1;
+                while size < max_size && rng.gen_bool(0.55) {
+                    size += 1;
+                }
+                let mut members = vec![seed];
+                while members.len() < size {
+                    let candidates: Vec<(usize, f64)> = (0..n)
+                        .filter(|g| !members.contains(g))
+                        .map(|g| (g, members.iter().map(|&m| closeness[m][g]).sum()))
+                        .filter(|&(_, weight)| weight > 0.0)
+                        .collect();
+                    let Ok(&(next, _)) = candidates.choose_weighted(rng, |c| c.1) else {
+                        break;
+                    };
+                    members.push(next);
+                }
+                members.sort_unstable();
+                let base: f64 = members.iter().map(|&g| own[g]).sum();
+                let value = base * (1.0 + additivity * (members.len() - 1) as f64);
+                bids.push((members, value.max(0.0)));
+            }
+            bids
+        })
+        .collect()
+}
+
+/// A job of random length bid for at successively later deadlines, each
+/// taking the latest slots before its deadline
+fn job_bids(rng: &mut StdRng, num_slots: usize, bids: usize, max_size: usize) -> Vec<Bid> {
+    if max_size == 0 || bids == 0 {
+        return Vec::new();
+    }
+    let length = rng.gen_range(1..=max_size);
+    let first_deadline = rng.gen_range(length..=num_slots);
+    let value = length as f64 * sample_uniform(rng, 1.0, 10.0);
+    let step = ((num_slots - first_deadline) / bids).max(1);
+    (0..bids)
+        .map(|k| first_deadline + k * step)
+        .take_while(|&deadline| deadline <= num_slots)
+        .map(|deadline| {
+            let slots = (deadline - length..deadline).collect();
+            (slots, value * first_deadline as f64 / deadline as f64)
+        })
+        .collect()
+}
+
+/// Bundle values drawn from a `ValueDistribution`
+struct DistributionModel<'a> {
+    distribution: &'a ValueDistribution,
+    /// Per-good values shared by all agents (standard normal shocks for
+    /// `Correlated`, true values for `CommonValue`)
+    common: Vec<f64>,
+    /// The current agent's values for each good, for additive distributions
+    good_values: Vec<f64>,
+}
+
+impl ValueModel for DistributionModel<'_> {
+    fn prepare(&mut self, rng: &mut StdRng, goods: &[Good]) {
+        self.common = match *self.distribution {
+            ValueDistribution::Correlated { .. } => {
+                goods.iter().map(|_| sample_normal(rng)).collect()
+            }
+            ValueDistribution::CommonValue { min, max, .. } => {
+                goods.iter().map(|_| sample_uniform(rng, min, max)).collect()
+            }
+            _ => Vec::new(),
+        };
+    }
+
+    fn start_agent(&mut self, rng: &mut StdRng, goods: &[Good]) {
+        self.good_values = match *self.distribution {
+            ValueDistribution::Additive { min, max, .. } => {
+                goods.iter().map(|_| sample_uniform(rng, min, max)).collect()
+            }
+            ValueDistribution::Correlated {
+                mean,
+                std_dev,
+                correlation,
+            } => {
+                let rho = correlation.clamp(0.0, 1.0);
+                self.common
+                    .iter()
+                    .map(|common| {
+                        let shock = rho.sqrt() * common + (1.0 - rho).sqrt() * sample_normal(rng);
+                        (mean + std_dev * shock).max(0.0)
+                    })
+                    .collect()
+            }
+            ValueDistribution::CommonValue { noise, .. } => self
+                .common
+                .iter()
+                .map(|value| (value + sample_uniform(rng, -noise, noise)).max(0.0))
+                .collect(),
+            _ => Vec::new(),
+        };
+    }
+
+    fn value(&mut self, rng: &mut StdRng, goods: &[Good], bundle: &Bundle) -> f64 {
+        let additive = || -> f64 {
+            goods
+                .iter()
+                .zip(&self.good_values)
+                .filter(|(good, _)| bundle.contains(*good))
+                .map(|(_, value)| value)
+                .sum()
+        };
+        match *self.distribution {
+            ValueDistribution::Uniform { min, max } => sample_uniform(rng, min, max),
+            ValueDistribution::Normal { mean, std_dev } => {
+                (mean + std_dev * sample_normal(rng)).max(0.0)
+            }
+            ValueDistribution::Additive {
+                complementarity, ..
+            } => {
+                let synergy = 1.0 + complementarity * (bundle.len().saturating_sub(1)) as f64;
+                (additive() * synergy).max(0.0)
+            }
+            ValueDistribution::Correlated { .. } | ValueDistribution::CommonValue { .. } => {
+                additive()
+            }
+        }
+    }
+}
+
+/// A standard normal draw (Box-Muller transform)
+fn sample_normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+fn sample_uniform(rng: &mut StdRng, min: f64, max: f64) -> f64 {
+    if max > min {
+        rng.gen_range(min..max)
+    } else {
+        min
+    }
+}
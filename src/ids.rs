@@ -0,0 +1,210 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+
+/// Most distinct names each of `GoodId` and `AgentId` interns
+pub const MAX_INTERNED_IDS: usize = 1 << 20;
+
+/// String-to-index interner.
+/// Interned strings are leaked so that IDs can hand out `&'static str`
+/// without holding a lock: nothing is ever freed, so a long-running process
+/// keeps every name it has seen. The ID tables are therefore capped at
+/// `MAX_INTERNED_IDS` names each; input from untrusted sources should go
+/// through `try_new` or deserialization, which fail instead of growing
+/// past the cap.
+#[derive(Debug)]
+pub struct Interner {
+    names: Vec<&'static str>,
+    index: HashMap<&'static str, u32>,
+    limit: usize,
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Self::with_limit(u32::MAX as usize)
+    }
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An interner holding at most `limit` names
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            names: Vec::new(),
+            index: HashMap::new(),
+            limit,
+        }
+    }
+
+    /// Index of a name, interning it if needed.
+    /// Panics if the name is new and the interner is full.
+    pub fn intern(&mut self, name: &str) -> u32 {
+        self.try_intern(name).expect("interner full")
+    }
+
+    /// Index of a name, interning it if needed; None if the name is new
+    /// and the interner already holds its limit
+    pub fn try_intern(&mut self, name: &str) -> Option<u32> {
+        if let Some(&index) = self.index.get(name) {
+            return Some(index);
+        }
+        if self.names.len() >= self.limit {
+            return None;
+        }
+        let index = u32::try_from(self.names.len()).ok()?;
+        let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+        self.names.push(name);
+        self.index.insert(name, index);
+        Some(index)
+    }
+
+    /// Index of a name, if interned
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.index.get(name).copied()
+    }
+
+    /// Name behind an index
+    pub fn resolve(&self, index: u32) -> &'static str {
+        self.names[index as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+fn intern_in(interner: &RwLock<Interner>, name: &str) -> Option<u32> {
+    if let Some(index) = interner.read().unwrap().get(name) {
+        return Some(index);
+    }
+    interner.write().unwrap().try_intern(name)
+}
+
+macro_rules! interned_id {
+    ($(#[$doc:meta])* $name:ident, $interner:ident) => {
+        static $interner: OnceLock<RwLock<Interner>> = OnceLock::new();
+
+        $(#[$doc])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(u32);
+
+        impl $name {
+            fn interner() -> &'static RwLock<Interner> {
+                $interner.get_or_init(|| RwLock::new(Interner::with_limit(MAX_INTERNED_IDS)))
+            }
+
+            /// Intern a name.
+            /// Panics once `MAX_INTERNED_IDS` distinct names have been interned.
+            pub fn new(name: &str) -> Self {
+                Self::try_new(name).expect("too many distinct IDs")
+            }
+
+            /// Intern a name; None if it is new and the table is full
+            pub fn try_new(name: &str) -> Option<Self> {
+                intern_in(Self::interner(), name).map(Self)
+            }
+
+            /// Look up a name without interning it
+            pub fn lookup(name: &str) -> Option<Self> {
+                Self::interner().read().unwrap().get(name).map(Self)
+            }
+
+            /// Dense index (in interning order)
+            pub fn index(self) -> u32 {
+                self.0
+            }
+
+            /// Original name
+            pub fn as_str(self) -> &'static str {
+                Self::interner().read().unwrap().resolve(self.0)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}({:?})", stringify!($name), self.as_str())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(name: &str) -> Self {
+                Self::new(name)
+            }
+        }
+
+        impl From<&String> for $name {
+            fn from(name: &String) -> Self {
+                Self::new(name)
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(name: String) -> Self {
+                Self::new(&name)
+            }
+        }
+
+        impl From<&$name> for $name {
+            fn from(id: &$name) -> Self {
+                *id
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.as_str() == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.as_str() == *other
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let name = String::deserialize(deserializer)?;
+                Self::try_new(&name).ok_or_else(|| {
+                    de::Error::custom(format!(
+                        "too many distinct {} names (at most {})",
+                        stringify!($name),
+                        MAX_INTERNED_IDS
+                    ))
+                })
+            }
+        }
+    };
+}
+
+interned_id!(
+    /// Interned identifier of a good
+    GoodId,
+    GOOD_IDS
+);
+
+interned_id!(
+    /// Interned identifier of an agent
+    AgentId,
+    AGENT_IDS
+);
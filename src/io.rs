@@ -0,0 +1,359 @@
+use crate::auction::CombinatorialAuction;
+use crate::error::AuctionError;
+use crate::types::{
+    Agent, AtomicBid, AuctionInstance, BidLanguage, Bundle, Good, OrBid, OrOfXorBid, XorBid,
+};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+/// Errors raised while reading auction instances
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("failed to read instance: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "toml")]
+    #[error("invalid TOML: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[cfg(feature = "yaml")]
+    #[error("invalid YAML: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("unsupported scenario format {0:?}")]
+    UnsupportedFormat(String),
+    #[error("line {line}: {message}")]
+    Syntax { line: usize, message: String },
+    #[error("bid column {column}: {message}")]
+    BidSyntax { column: usize, message: String },
+}
+
+impl ParseError {
+    fn syntax(line: usize, message: impl Into<String>) -> Self {
+        ParseError::Syntax {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+/// Read a JSON auction instance
+pub fn read_instance<P: AsRef<Path>>(path: P) -> Result<AuctionInstance, ParseError> {
+    let contents = fs::read_to_string(path)?;
+    Ok(AuctionInstance::from_json(&contents)?)
+}
+
+/// Write an auction instance as JSON
+pub fn write_instance<P: AsRef<Path>>(path: P, instance: &AuctionInstance) -> Result<(), ParseError> {
+    fs::write(path, instance.to_json()?)?;
+    Ok(())
+}
+
+/// Run BRACE on a JSON `AuctionInstance` and return the `AuctionResult` as JSON
+pub fn solve_json(instance: &str) -> Result<String, AuctionError> {
+    let instance = AuctionInstance::from_json(instance).map_err(ParseError::from)?;
+    let result = CombinatorialAuction::from_instance(instance)?.run()?;
+    Ok(serde_json::to_string(&result).map_err(ParseError::from)?)
+}
+
+/// Read a CATS (Combinatorial Auction Test Suite) file
+pub fn read_cats<P: AsRef<Path>>(path: P) -> Result<(Vec<Agent>, Vec<Good>), ParseError> {
+    let contents = fs::read_to_string(path)?;
+    parse_cats(&contents)
+}
+
+/// Parse an instance in CATS format.
+///
+/// Goods are named by their index. Bids sharing a dummy good (index >= the
+/// number of goods) come from the same bidder and are combined into one XOR
+/// bid; every other bid becomes its own single-minded bidder. Agents start
+/// with empty endowments.
+pub fn parse_cats(input: &str) -> Result<(Vec<Agent>, Vec<Good>), ParseError> {
+    let mut num_goods: Option<usize> = None;
+    let mut num_bids: Option<usize> = None;
+    let mut goods: Vec<Good> = Vec::new();
+    // Dummy good index -> atomic bids of that bidder
+    let mut xor_bidders: BTreeMap<usize, XorBid> = BTreeMap::new();
+    let mut single_bids: Vec<(usize, Bundle, f64)> = Vec::new();
+    let mut bids_seen = 0;
+
+    for (index, raw_line) in input.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+
+        let mut tokens = line.split_whitespace();
+        let first = tokens.next().unwrap_or_default();
+        match first {
+            "goods" => {
+                let count = parse_count(tokens.next(), line_no, "goods")?;
+                goods = (0..count)
+                    .map(|i| Good::new(i.to_string(), format!("Good {}", i)))
+                    .collect();
+                num_goods = Some(count);
+            }
+            "bids" => num_bids = Some(parse_count(tokens.next(), line_no, "bids")?),
+            "dummy" => {
+                parse_count(tokens.next(), line_no, "dummy")?;
+            }
+            _ => {
+                let num_goods = num_goods
+                    .ok_or_else(|| ParseError::syntax(line_no, "bid before 'goods' header"))?;
+                let bid_id: usize = first
+                    .parse()
+                    .map_err(|_| ParseError::syntax(line_no, format!("invalid bid id '{}'", first)))?;
+                let value: f64 = tokens
+                    .next()
+                    .and_then(|t| t.parse().ok())
+                    .ok_or_else(|| ParseError::syntax(line_no, "missing or invalid bid value"))?;
+
+                let mut bundle: Bundle = HashSet::new();
+                let mut dummy = None;
+                let mut terminated = false;
+                for token in tokens {
+                    if token == "#" {
+                        terminated = true;
+                        break;
+                    }
+                    let good: usize = token.parse().map_err(|_| {
+                        ParseError::syntax(line_no, format!("invalid good index '{}'", token))
+                    })?;
+                    if good < num_goods {
+                        bundle.insert(goods[good].clone());
+                    } else {
+                        dummy = Some(good);
+                    }
+                }
+                if !terminated {
+                    return Err(ParseError::syntax(line_no, "bid not terminated by '#'"));
+                }
+
+                match dummy {
+                    Some(dummy) => xor_bidders.entry(dummy).or_default().add(bundle, value),
+                    None => single_bids.push((bid_id, bundle, value)),
+                }
+                bids_seen += 1;
+            }
+        }
+    }
+
+    if num_goods.is_none() {
+        return Err(ParseError::syntax(0, "missing 'goods' header"));
+    }
+    if let Some(expected) = num_bids {
+        if expected != bids_seen {
+            return Err(ParseError::syntax(
+                0,
+                format!("expected {} bids, found {}", expected, bids_seen),
+            ));
+        }
+    }
+
+    let mut agents = Vec::new();
+    for (bid_id, bundle, value) in single_bids {
+        let mut agent = Agent::new(format!("Bid{}", bid_id), HashSet::new());
+        agent.set_xor_bid(XorBid::new().with(bundle, value));
+        agents.push(agent);
+    }
+    for (dummy, bid) in xor_bidders {
+        let mut agent = Agent::new(format!("Bidder{}", dummy), HashSet::new());
+        agent.set_xor_bid(bid);
+        agents.push(agent);
+    }
+
+    Ok((agents, goods))
+}
+
+fn parse_count(token: Option<&str>, line: usize, header: &str) -> Result<usize, ParseError> {
+    token
+        .and_then(|t| t.parse().ok())
+        .ok_or_else(|| ParseError::syntax(line, format!("invalid '{}' count", header)))
+}
+
+/// Parse a textual bid such as `"(A & B):10 XOR C:5"`, resolving good IDs
+/// against `goods`.
+///
+/// An atom is a bundle of good IDs joined by `&` (parentheses optional),
+/// then `:` and its value. `XOR` binds tighter than `OR`, and parentheses
+/// group XOR clauses: `(A:3 XOR B:4) OR C:2`. A single clause becomes an
+/// XOR bid, clauses of one atom each an OR bid, anything else OR-of-XOR.
+pub fn parse_bid(input: &str, goods: &[Good]) -> Result<BidLanguage, ParseError> {
+    let mut parser = BidParser {
+        tokens: tokenize_bid(input),
+        next: 0,
+        end: input.chars().count() + 1,
+        goods,
+    };
+    if parser.tokens.is_empty() {
+        return Err(parser.error("empty bid"));
+    }
+    let mut clauses = vec![parser.clause()?];
+    while parser.peek() == Some(BidToken::Or) {
+        parser.next += 1;
+        clauses.push(parser.clause()?);
+    }
+    if parser.peek().is_some() {
+        return Err(parser.error("expected OR, XOR or the end of the bid"));
+    }
+
+    Ok(match clauses.len() {
+        1 => BidLanguage::Xor(clauses.remove(0)),
+        _ if clauses.iter().all(|clause| clause.atoms.len() == 1) => BidLanguage::Or(OrBid {
+            atoms: clauses.into_iter().flat_map(|clause| clause.atoms).collect(),
+        }),
+        _ => BidLanguage::OrOfXor(OrOfXorBid { clauses }),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BidToken<'a> {
+    Open,
+    Close,
+    And,
+    Colon,
+    Xor,
+    Or,
+    Word(&'a str),
+}
+
+/// Tokens with their (1-based) column
+fn tokenize_bid(input: &str) -> Vec<(usize, BidToken<'_>)> {
+    let mut tokens = Vec::new();
+    let mut word: Option<(usize, usize)> = None;
+    let flush = |word: &mut Option<(usize, usize)>, end: usize, tokens: &mut Vec<_>| {
+        if let Some((column, start)) = word.take() {
+            let text = &input[start..end];
+            let token = match text.to_ascii_uppercase().as_str() {
+                "XOR" => BidToken::Xor,
+                "OR" => BidToken::Or,
+                _ => BidToken::Word(text),
+            };
+            tokens.push((column, token));
+        }
+    };
+    for (column, (offset, c)) in input.char_indices().enumerate() {
+        let symbol = match c {
+            '(' => Some(BidToken::Open),
+            ')' => Some(BidToken::Close),
+            '&' => Some(BidToken::And),
+            ':' => Some(BidToken::Colon),
+            _ => None,
+        };
+        if symbol.is_some() || c.is_whitespace() {
+            flush(&mut word, offset, &mut tokens);
+        } else if word.is_none() {
+            word = Some((column + 1, offset));
+        }
+        if let Some(symbol) = symbol {
+            tokens.push((column + 1, symbol));
+        }
+    }
+    flush(&mut word, input.len(), &mut tokens);
+    tokens
+}
+
+struct BidParser<'a> {
+    tokens: Vec<(usize, BidToken<'a>)>,
+    next: usize,
+    /// Column just past the input, for errors at the end
+    end: usize,
+    goods: &'a [Good],
+}
+
+impl<'a> BidParser<'a> {
+    fn peek(&self) -> Option<BidToken<'a>> {
+        self.tokens.get(self.next).map(|(_, token)| *token)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::BidSyntax {
+            column: self.tokens.get(self.next).map_or(self.end, |(column, _)| *column),
+            message: message.into(),
+        }
+    }
+
+    fn expect(&mut self, token: BidToken, what: &str) -> Result<(), ParseError> {
+        if self.peek() != Some(token) {
+            return Err(self.error(format!("expected {}", what)));
+        }
+        self.next += 1;
+        Ok(())
+    }
+
+    /// Terms joined by XOR
+    fn clause(&mut self) -> Result<XorBid, ParseError> {
+        let mut clause = self.term()?;
+        while self.peek() == Some(BidToken::Xor) {
+            self.next += 1;
+            clause.atoms.extend(self.term()?.atoms);
+        }
+        Ok(clause)
+    }
+
+    /// An atom, or a parenthesized XOR clause
+    fn term(&mut self) -> Result<XorBid, ParseError> {
+        if self.peek() == Some(BidToken::Open) && !self.bundle_ahead() {
+            self.next += 1;
+            let clause = self.clause()?;
+            self.expect(BidToken::Close, "')'")?;
+            return Ok(clause);
+        }
+        Ok(XorBid {
+            atoms: vec![self.atom()?],
+        })
+    }
+
+    /// Whether the parenthesis at the cursor encloses a bundle
+    fn bundle_ahead(&self) -> bool {
+        let mut rest = self.tokens[self.next + 1..].iter().map(|(_, token)| *token);
+        loop {
+            if !matches!(rest.next(), Some(BidToken::Word(_))) {
+                return false;
+            }
+            match rest.next() {
+                Some(BidToken::And) => continue,
+                Some(BidToken::Close) => return rest.next() == Some(BidToken::Colon),
+                _ => return false,
+            }
+        }
+    }
+
+    /// A bundle and its value
+    fn atom(&mut self) -> Result<AtomicBid, ParseError> {
+        let enclosed = self.peek() == Some(BidToken::Open);
+        if enclosed {
+            self.next += 1;
+        }
+        let mut bundle = Bundle::new();
+        loop {
+            let Some(BidToken::Word(id)) = self.peek() else {
+                return Err(self.error("expected a good ID"));
+            };
+            let good = self
+                .goods
+                .iter()
+                .find(|good| good.id.as_str() == id)
+                .ok_or_else(|| self.error(format!("unknown good '{}'", id)))?;
+            bundle.insert(good.clone());
+            self.next += 1;
+            if self.peek() != Some(BidToken::And) {
+                break;
+            }
+            self.next += 1;
+        }
+        if enclosed {
+            self.expect(BidToken::Close, "')'")?;
+        }
+        self.expect(BidToken::Colon, "':' and a value")?;
+        let value = match self.peek() {
+            Some(BidToken::Word(text)) => text.parse::<f64>().ok().filter(|v| v.is_finite()),
+            _ => None,
+        };
+        let value = value.ok_or_else(|| self.error("expected a finite value"))?;
+        self.next += 1;
+        Ok(AtomicBid { bundle, value })
+    }
+}
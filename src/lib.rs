@@ -2,9 +2,51 @@ pub mod auction;
 pub mod types;
 pub mod brace;
 pub mod pricing;
+pub mod payments;
+pub mod wdp;
+pub mod io;
+pub mod gen;
+pub mod compact;
+pub mod ids;
+pub mod mechanisms;
+pub mod report;
+pub mod audit;
+pub mod sybil;
+pub mod error;
+mod macros;
+mod sorted;
+pub mod valuation;
+pub mod observer;
+pub mod online;
+pub mod simulation;
+pub mod experiment;
+pub mod analysis;
+pub mod lp;
+pub mod objective;
+pub mod viz;
+pub mod scenario;
+pub mod constraints;
+pub mod cooperative;
+pub mod verify;
+pub mod lottery;
+pub mod testcases;
+pub mod elicit;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod ffi;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "tokio")]
+pub mod remote;
 
-pub use auction::CombinatorialAuction;
+pub use auction::{CombinatorialAuction, MechanismKind};
+pub use online::OnlineAuction;
 pub use types::*;
-pub use brace::BRACEMechanism;
+pub use constraints::{CategoryRule, Constraints};
+pub use brace::{AutoEpsilon, BRACEMechanism, EpsilonPolicy, Improvement, TieBreaker, WarmStart};
 pub use pricing::PriceVector;
+pub use ids::{AgentId, GoodId};
+pub use error::{AuctionError, NonConvergence, Violation};
+pub use objective::Objective;
+pub use observer::{AuctionObserver, Flow, PriceTrajectory};
 
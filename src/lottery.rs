@@ -0,0 +1,149 @@
+use crate::error::AuctionError;
+use crate::ids::AgentId;
+use crate::types::{Allocation, Bundle, FractionalAllocation, Good};
+
+const TOLERANCE: f64 = 1e-9;
+
+/// Birkhoff-von Neumann decomposition of a random assignment into a lottery
+/// over deterministic allocations whose expected outcome is `allocation`.
+/// Every agent's lottery must be over single goods (or nothing), with each
+/// agent receiving at most one good in total and no good over its supply
+/// in expectation. Probability left unassigned means receiving nothing.
+///
+/// The assignment is padded into a doubly stochastic matrix (agents and
+/// unit copies of the goods, plus "nothing" and "unsold" slots), from which
+/// perfect matchings are peeled off one at a time, each weighted by its
+/// smallest entry.
+pub fn decompose(
+    allocation: &FractionalAllocation,
+    goods: &[Good],
+) -> Result<Vec<(Allocation, f64)>, AuctionError> {
+    let mut agents: Vec<AgentId> = allocation.shares.keys().copied().collect();
+    agents.sort_by_key(|id| id.as_str());
+
+    // Unit copies of every good; an agent's share fills the copies in turn
+    let mut copies: Vec<usize> = Vec::new();
+    let mut shares: Vec<Vec<f64>> = vec![Vec::new(); agents.len()];
+    for (g, good) in goods.iter().enumerate() {
+        let first = copies.len();
+        copies.extend(std::iter::repeat_n(g, good.supply as usize));
+        for row in &mut shares {
+            row.resize(copies.len(), 0.0);
+        }
+        let mut copy = first;
+        let mut room = 1.0;
+        for (i, agent_id) in agents.iter().enumerate() {
+            let mut share = allocation.good_share(*agent_id, good.id);
+            while share > TOLERANCE {
+                if copy == copies.len() {
+                    return Err(AuctionError::OverAssigned(good.id));
+                }
+                let poured = share.min(room);
+                shares[i][copy] += poured;
+                share -= poured;
+                room -= poured;
+                if room <= TOLERANCE {
+                    copy += 1;
+                    room = 1.0;
+                }
+            }
+        }
+    }
+
+    for (i, agent_id) in agents.iter().enumerate() {
+        let known = allocation.get_shares(*agent_id).iter().all(|(bundle, p)| {
+            *p <= TOLERANCE
+                || bundle.len() <= 1 && bundle.iter().all(|good| goods.contains(good))
+        });
+        if !known || shares[i].iter().sum::<f64>() > 1.0 + TOLERANCE {
+            return Err(AuctionError::NotAnAssignment(*agent_id));
+        }
+    }
+
+    // Rows: agents, then one "unsold" slot per copy.
+    // Columns: copies, then one "nothing" slot per agent.
+    let (n, m) = (agents.len(), copies.len());
+    let size = n + m;
+    let mut matrix = vec![vec![0.0; size]; size];
+    for i in 0..n {
+        let assigned: f64 = shares[i].iter().sum();
+        for c in 0..m {
+            matrix[i][c] = shares[i][c];
+            matrix[n + c][m + i] = shares[i][c];
+        }
+        matrix[i][m + i] = (1.0 - assigned).max(0.0);
+    }
+    for c in 0..m {
+        let sold: f64 = (0..n).map(|i| shares[i][c]).sum();
+        matrix[n + c][c] = (1.0 - sold).max(0.0);
+    }
+
+    let mut lottery: Vec<(Allocation, f64)> = Vec::new();
+    let mut remaining = 1.0;
+    while remaining > TOLERANCE {
+        let Some(matching) = perfect_matching(&matrix) else {
+            break;
+        };
+        let weight = (0..size)
+            .map(|row| matrix[row][matching[row]])
+            .fold(f64::INFINITY, f64::min);
+        for row in 0..size {
+            matrix[row][matching[row]] -= weight;
+        }
+        remaining -= weight;
+
+        let mut outcome = Allocation::new();
+        for (i, agent_id) in agents.iter().enumerate() {
+            let bundle = match matching[i] {
+                c if c < m => Bundle::from([goods[copies[c]].clone()]),
+                _ => Bundle::new(),
+            };
+            outcome.assign(*agent_id, bundle);
+        }
+        match lottery.iter_mut().find(|(a, _)| a.assignments() == outcome.assignments()) {
+            Some((_, p)) => *p += weight,
+            None => lottery.push((outcome, weight)),
+        }
+    }
+
+    // Rounding can leave a sliver unassigned; spread it proportionally
+    let total: f64 = lottery.iter().map(|(_, p)| p).sum();
+    for (_, p) in &mut lottery {
+        *p /= total;
+    }
+    Ok(lottery)
+}
+
+/// A perfect matching (row -> column) on the positive entries, if any
+fn perfect_matching(matrix: &[Vec<f64>]) -> Option<Vec<usize>> {
+    fn augment(
+        row: usize,
+        matrix: &[Vec<f64>],
+        owner: &mut [Option<usize>],
+        seen: &mut [bool],
+    ) -> bool {
+        for column in 0..matrix.len() {
+            if matrix[row][column] > TOLERANCE && !seen[column] {
+                seen[column] = true;
+                if owner[column].is_none_or(|other| augment(other, matrix, owner, seen)) {
+                    owner[column] = Some(row);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    let size = matrix.len();
+    let mut owner = vec![None; size];
+    for row in 0..size {
+        if !augment(row, matrix, &mut owner, &mut vec![false; size]) {
+            return None;
+        }
+    }
+    let mut matching = vec![0; size];
+    for (column, row) in owner.iter().enumerate() {
+        matching[row.expect("perfect matching")] = column;
+    }
+    Some(matching)
+}
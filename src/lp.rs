@@ -0,0 +1,277 @@
+use crate::ids::{AgentId, GoodId};
+use crate::pricing::PriceVector;
+use crate::types::{unit_count, Agent, Bundle, FractionalAllocation, Good};
+use std::collections::HashMap;
+
+const TOLERANCE: f64 = 1e-9;
+
+/// Optimal solution of the LP relaxation of winner determination
+#[derive(Debug, Clone)]
+pub struct LpSolution {
+    pub allocation: FractionalAllocation,
+    /// Optimal LP welfare (an upper bound on any integral allocation's welfare)
+    pub welfare: f64,
+    /// Duals of the supply constraints: competitive (linear) prices
+    pub prices: PriceVector,
+    /// Duals of the one-bundle-per-agent constraints: each agent's surplus
+    pub utilities: HashMap<AgentId, f64>,
+}
+
+/// Solve the LP relaxation of the winner determination problem:
+/// maximize total value over fractional assignments of preference bundles,
+/// with at most one bundle per agent in total and no good over its supply.
+/// Solved with an in-crate dense simplex, so meant for small and medium instances.
+pub fn solve_lp_relaxation(agents: &[Agent], goods: &[Good]) -> LpSolution {
+    let good_rows: HashMap<GoodId, usize> = goods
+        .iter()
+        .enumerate()
+        .map(|(row, good)| (good.id, agents.len() + row))
+        .collect();
+
+    // One column per (agent, bundle) with positive value over known goods
+    let mut columns: Vec<(usize, &Bundle)> = Vec::new();
+    for (i, agent) in agents.iter().enumerate() {
+        for bundle in agent.preference_bundles() {
+            if agent.preference(bundle) > 0.0
+                && bundle.iter().all(|good| good_rows.contains_key(&good.id))
+            {
+                columns.push((i, bundle));
+            }
+        }
+    }
+
+    let rows = agents.len() + goods.len();
+    let mut a = vec![vec![0.0; columns.len()]; rows];
+    let mut c = vec![0.0; columns.len()];
+    for (j, &(i, bundle)) in columns.iter().enumerate() {
+        let agent = &agents[i];
+        a[i][j] = 1.0;
+        for good in bundle {
+            let units = unit_count(bundle, agent.bundle_units(bundle), good.id);
+            a[good_rows[&good.id]][j] = units as f64;
+        }
+        c[j] = agent.preference(bundle);
+    }
+    let b: Vec<f64> = (0..agents.len())
+        .map(|_| 1.0)
+        .chain(goods.iter().map(|good| good.supply as f64))
+        .collect();
+
+    let (x, y, welfare) = simplex(&a, &b, &c);
+
+    let mut allocation = FractionalAllocation::new();
+    for (j, &(i, bundle)) in columns.iter().enumerate() {
+        if x[j] > TOLERANCE {
+            allocation.add_share(agents[i].id, bundle.clone(), x[j]);
+        }
+    }
+    let mut prices = PriceVector::new();
+    for good in goods {
+        prices.set_price(good.id, y[good_rows[&good.id]]);
+    }
+    let utilities = agents
+        .iter()
+        .enumerate()
+        .map(|(i, agent)| (agent.id, y[i]))
+        .collect();
+
+    LpSolution {
+        allocation,
+        welfare,
+        prices,
+        utilities,
+    }
+}
+
+/// A random assignment that stochastically dominates `allocation` for
+/// every agent and strictly for some, or None if `allocation` is
+/// sd-efficient (ordinally efficient). Solves the LP that maximizes the
+/// total gain in the probability of each upper contour set, over lotteries
+/// on each agent's preference bundles, its current bundles and the empty
+/// bundle that keep every good within its expected supply (`allocation`
+/// itself is assumed to). Probability an agent's lottery leaves unassigned
+/// counts as the empty bundle.
+pub fn sd_dominating_allocation(
+    agents: &[Agent],
+    goods: &[Good],
+    allocation: &FractionalAllocation,
+) -> Option<FractionalAllocation> {
+    // Columns: (agent, bundle, current probability, indifference class
+    // counted from the best); one deviation pair (gain, loss) per column
+    let mut columns: Vec<(usize, Bundle, f64, usize)> = Vec::new();
+    let mut classes = Vec::new();
+    for (i, agent) in agents.iter().enumerate() {
+        let mut bundles: Vec<Bundle> = Vec::new();
+        let candidates = agent
+            .preference_bundles()
+            .iter()
+            .filter(|bundle| bundle.iter().all(|good| goods.contains(good)))
+            .chain(allocation.get_shares(agent.id).iter().map(|(bundle, _)| bundle));
+        for bundle in candidates.chain([&Bundle::new()]) {
+            if !bundles.contains(bundle) {
+                bundles.push(bundle.clone());
+            }
+        }
+        bundles.sort_by(|a, b| {
+            if agent.prefers(a, b) {
+                std::cmp::Ordering::Less
+            } else if agent.prefers(b, a) {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        });
+
+        let assigned: f64 = allocation.get_shares(agent.id).iter().map(|(_, p)| p).sum();
+        let mut class = 0;
+        for (k, bundle) in bundles.iter().enumerate() {
+            if k > 0 && agent.prefers(&bundles[k - 1], bundle) {
+                class += 1;
+            }
+            let mut current: f64 = allocation
+                .get_shares(agent.id)
+                .iter()
+                .filter(|(b, _)| b == bundle)
+                .map(|(_, p)| p)
+                .sum();
+            if bundle.is_empty() {
+                current += (1.0 - assigned).max(0.0);
+            }
+            columns.push((i, bundle.clone(), current, class));
+        }
+        classes.push(class + 1);
+    }
+
+    let n = 2 * columns.len();
+    let mut a: Vec<Vec<f64>> = Vec::new();
+    let mut b: Vec<f64> = Vec::new();
+    let mut row = |entries: &[(usize, f64)], bound: f64| {
+        let mut coefficients = vec![0.0; n];
+        for &(j, coefficient) in entries {
+            // Column j's gain, then its loss with the opposite sign
+            coefficients[2 * j] += coefficient;
+            coefficients[2 * j + 1] -= coefficient;
+        }
+        a.push(coefficients);
+        b.push(bound);
+    };
+    for (i, &count) in classes.iter().enumerate() {
+        let own: Vec<usize> = (0..columns.len()).filter(|&j| columns[j].0 == i).collect();
+        // Never less likely to get a bundle at least this good
+        for top in 0..count - 1 {
+            let entries: Vec<(usize, f64)> = own
+                .iter()
+                .filter(|&&j| columns[j].3 <= top)
+                .map(|&j| (j, -1.0))
+                .collect();
+            row(&entries, 0.0);
+        }
+        // Still one bundle in total
+        let total: Vec<(usize, f64)> = own.iter().map(|&j| (j, 1.0)).collect();
+        row(&total, 0.0);
+        let negated: Vec<(usize, f64)> = own.iter().map(|&j| (j, -1.0)).collect();
+        row(&negated, 0.0);
+    }
+    // Probabilities stay nonnegative
+    for (j, column) in columns.iter().enumerate() {
+        row(&[(j, -1.0)], column.2);
+    }
+    // Expected supply
+    for good in goods {
+        let mut load = 0.0;
+        let mut entries = Vec::new();
+        for (j, (i, bundle, current, _)) in columns.iter().enumerate() {
+            if bundle.contains(good) {
+                let units = unit_count(bundle, agents[*i].bundle_units(bundle), good.id) as f64;
+                load += units * current;
+                entries.push((j, units));
+            }
+        }
+        row(&entries, (good.supply as f64 - load).max(0.0));
+    }
+
+    // Total gain over all upper contour sets but the last (everything)
+    let mut c = vec![0.0; n];
+    for (j, (i, _, _, class)) in columns.iter().enumerate() {
+        let weight = (classes[*i] - 1 - class) as f64;
+        c[2 * j] = weight;
+        c[2 * j + 1] = -weight;
+    }
+
+    let (deviation, _, gain) = simplex(&a, &b, &c);
+    if gain <= 1e-7 {
+        return None;
+    }
+    let mut dominating = FractionalAllocation::new();
+    for (j, (i, bundle, current, _)) in columns.iter().enumerate() {
+        let probability = current + deviation[2 * j] - deviation[2 * j + 1];
+        if probability > TOLERANCE {
+            dominating.add_share(agents[*i].id, bundle.clone(), probability);
+        }
+    }
+    Some(dominating)
+}
+
+/// Maximize `c·x` subject to `a x <= b`, `x >= 0`, with `b >= 0`.
+/// Returns the primal solution, the duals of the rows, and the optimal value.
+/// Uses Bland's rule, so it never cycles.
+pub(crate) fn simplex(a: &[Vec<f64>], b: &[f64], c: &[f64]) -> (Vec<f64>, Vec<f64>, f64) {
+    let m = b.len();
+    let n = c.len();
+    let width = n + m + 1;
+
+    // Tableau rows: constraints with slack columns, then the objective row
+    let mut tableau: Vec<Vec<f64>> = (0..m)
+        .map(|i| {
+            let mut row = vec![0.0; width];
+            row[..n].copy_from_slice(&a[i]);
+            row[n + i] = 1.0;
+            row[width - 1] = b[i];
+            row
+        })
+        .collect();
+    let mut objective = vec![0.0; width];
+    for (j, value) in c.iter().enumerate() {
+        objective[j] = -value;
+    }
+    tableau.push(objective);
+    let mut basis: Vec<usize> = (n..n + m).collect();
+
+    while let Some(entering) = (0..n + m).find(|&j| tableau[m][j] < -TOLERANCE) {
+        let leaving = (0..m)
+            .filter(|&i| tableau[i][entering] > TOLERANCE)
+            .min_by(|&i, &k| {
+                let ratio_i = tableau[i][width - 1] / tableau[i][entering];
+                let ratio_k = tableau[k][width - 1] / tableau[k][entering];
+                ratio_i
+                    .total_cmp(&ratio_k)
+                    .then(basis[i].cmp(&basis[k]))
+            });
+        // Constraints bound every column, so the LP is never unbounded
+        let Some(leaving) = leaving else { break };
+
+        let pivot = tableau[leaving][entering];
+        for value in &mut tableau[leaving] {
+            *value /= pivot;
+        }
+        let pivot_row = tableau[leaving].clone();
+        for (i, row) in tableau.iter_mut().enumerate() {
+            let factor = row[entering];
+            if i != leaving && factor.abs() > TOLERANCE {
+                for (value, pivot_value) in row.iter_mut().zip(&pivot_row) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+        basis[leaving] = entering;
+    }
+
+    let mut x = vec![0.0; n];
+    for (i, &column) in basis.iter().enumerate() {
+        if column < n {
+            x[column] = tableau[i][width - 1];
+        }
+    }
+    let duals = (0..m).map(|i| tableau[m][n + i]).collect();
+    (x, duals, tableau[m][width - 1])
+}
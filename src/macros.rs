@@ -0,0 +1,69 @@
+/// Build a bundle from good IDs: `bundle!["A", "B"]`.
+/// Goods are compared by ID, so the result matches goods built elsewhere.
+#[macro_export]
+macro_rules! bundle {
+    () => {
+        $crate::Bundle::new()
+    };
+    ($($id:expr),+ $(,)?) => {{
+        let mut bundle = $crate::Bundle::new();
+        $(
+            let id: &str = $id;
+            bundle.insert($crate::Good::new(id, id));
+        )+
+        bundle
+    }};
+}
+
+/// Build an `AuctionInstance` from good IDs.
+///
+/// ```
+/// use brace_sybil::auction;
+///
+/// let instance = auction! {
+///     goods: ["A", "B"],
+///     agents: [
+///         "Agent1" => { endow: ["A"], prefer: [["A", "B"] => 10.0, ["A"] => 5.0] },
+///         "Agent2" => { endow: ["B"], prefer: [["A"] => 6.0] },
+///     ],
+///     epsilon: 0.01,
+/// };
+/// assert_eq!(instance.agents.len(), 2);
+/// ```
+///
+/// Panics if an agent refers to a good that is not listed.
+#[macro_export]
+macro_rules! auction {
+    (
+        goods: [$($good:expr),* $(,)?],
+        agents: [
+            $($agent:expr => {
+                endow: [$($endowed:expr),* $(,)?],
+                prefer: [$([$($item:expr),* $(,)?] => $value:expr),* $(,)?] $(,)?
+            }),* $(,)?
+        ],
+        epsilon: $epsilon:expr $(,)?
+    ) => {{
+        let goods: Vec<$crate::Good> = vec![$({
+            let id: &str = $good;
+            $crate::Good::new(id, id)
+        }),*];
+        let agents: Vec<$crate::Agent> = vec![$(
+            $crate::Agent::builder($agent)
+                .endow({
+                    let ids: &[&str] = &[$($endowed),*];
+                    ids.iter().copied()
+                })
+                $(.prefer(
+                    {
+                        let ids: &[&str] = &[$($item),*];
+                        ids.iter().copied()
+                    },
+                    $value,
+                ))*
+                .build(&goods)
+                .expect("auction!: unknown good")
+        ),*];
+        $crate::AuctionInstance::new(agents, goods, $epsilon)
+    }};
+}
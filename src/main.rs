@@ -1,113 +1,262 @@
-use brace_sybil::{CombinatorialAuction, Agent, Good};
-use std::collections::HashSet;
+use brace_sybil::gen::{generate, EndowmentScheme, GeneratorConfig};
+use brace_sybil::io::{parse_bid, read_instance, ParseError};
+use brace_sybil::scenario::{is_scenario_path, Scenario};
+use brace_sybil::{
+    AgentId, Allocation, AuctionError, AuctionInstance, CombinatorialAuction, MechanismKind,
+};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-fn main() {
-    println!("BRACE Combinatorial Auction Example\n");
-
-    // Create goods
-    let good_a = Good {
-        id: "A".to_string(),
-        name: "Good A".to_string(),
-    };
-    let good_b = Good {
-        id: "B".to_string(),
-        name: "Good B".to_string(),
-    };
-    let good_c = Good {
-        id: "C".to_string(),
-        name: "Good C".to_string(),
-    };
-    let goods = vec![good_a.clone(), good_b.clone(), good_c.clone()];
-
-    // Create agents with endowments and preferences
-    let mut agent1 = Agent::new("Agent1".to_string(), {
-        let mut e = HashSet::new();
-        e.insert(good_a.clone());
-        e
-    });
-
-    // Agent 1 prefers bundle {B, C} most, then {A, B}, then {A}
-    let mut bundle_bc = HashSet::new();
-    bundle_bc.insert(good_b.clone());
-    bundle_bc.insert(good_c.clone());
-    agent1.add_preference(bundle_bc, 10.0);
-
-    let mut bundle_ab = HashSet::new();
-    bundle_ab.insert(good_a.clone());
-    bundle_ab.insert(good_b.clone());
-    agent1.add_preference(bundle_ab, 7.0);
-
-    let mut bundle_a = HashSet::new();
-    bundle_a.insert(good_a.clone());
-    agent1.add_preference(bundle_a, 5.0);
-
-    let mut agent2 = Agent::new("Agent2".to_string(), {
-        let mut e = HashSet::new();
-        e.insert(good_b.clone());
-        e
-    });
-
-    // Agent 2 prefers bundle {A, C} most, then {B, C}, then {B}
-    let mut bundle_ac = HashSet::new();
-    bundle_ac.insert(good_a.clone());
-    bundle_ac.insert(good_c.clone());
-    agent2.add_preference(bundle_ac, 12.0);
-
-    let mut bundle_bc2 = HashSet::new();
-    bundle_bc2.insert(good_b.clone());
-    bundle_bc2.insert(good_c.clone());
-    agent2.add_preference(bundle_bc2, 8.0);
-
-    let mut bundle_b = HashSet::new();
-    bundle_b.insert(good_b.clone());
-    agent2.add_preference(bundle_b, 4.0);
-
-    let mut agent3 = Agent::new("Agent3".to_string(), {
-        let mut e = HashSet::new();
-        e.insert(good_c.clone());
-        e
-    });
-
-    // Agent 3 prefers bundle {A, B} most, then {A, C}, then {C}
-    let mut bundle_ab3 = HashSet::new();
-    bundle_ab3.insert(good_a.clone());
-    bundle_ab3.insert(good_b.clone());
-    agent3.add_preference(bundle_ab3, 9.0);
-
-    let mut bundle_ac3 = HashSet::new();
-    bundle_ac3.insert(good_a.clone());
-    bundle_ac3.insert(good_c.clone());
-    agent3.add_preference(bundle_ac3, 6.0);
-
-    let mut bundle_c = HashSet::new();
-    bundle_c.insert(good_c.clone());
-    agent3.add_preference(bundle_c, 3.0);
+/// BRACE combinatorial exchange
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
 
-    let agents = vec![agent1, agent2, agent3];
+#[derive(Subcommand)]
+enum Command {
+    /// Run the BRACE mechanism (or another, see --mechanism) on an instance file
+    Solve {
+        /// JSON auction instance, or a TOML/YAML scenario (which may also
+        /// choose the objective, tie-breaking and payments)
+        instance: PathBuf,
+        /// Override the instance's epsilon
+        #[arg(long)]
+        epsilon: Option<f64>,
+        /// Value an agent's bundles with a bid expression (repeatable),
+        /// e.g. --bid 'Agent1=(A & B):10 XOR C:5'
+        #[arg(long = "bid", value_name = "AGENT=EXPR", value_parser = parse_agent_bid)]
+        bids: Vec<(String, String)>,
+        /// Allocate with another mechanism: brace, rsd:SEED, ttc, greedy,
+        /// leximin or envy-cycle (overrides the scenario's)
+        #[arg(long, value_parser = parse_mechanism_kind)]
+        mechanism: Option<MechanismKind>,
+        /// Also compute VCG payments
+        #[arg(long)]
+        vcg: bool,
+        /// Also compute core-selecting payments (VCG above 12 agents)
+        #[arg(long)]
+        core: bool,
+        /// Pure exchange without money: report trade cycles instead of prices
+        #[arg(long)]
+        housing_market: bool,
+        /// Print a per-agent explanation of the outcome to stderr
+        #[arg(long)]
+        explain: bool,
+        /// Write the result here instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Verify mechanism properties of an existing allocation
+    Verify {
+        /// JSON auction instance or TOML/YAML scenario
+        instance: PathBuf,
+        /// JSON allocation to check
+        allocation: PathBuf,
+    },
+    /// Generate a random instance
+    Generate {
+        #[arg(long, default_value_t = 5)]
+        goods: usize,
+        #[arg(long, default_value_t = 3)]
+        agents: usize,
+        /// Random bundles per agent (besides the endowment)
+        #[arg(long, default_value_t = 3)]
+        bundles: usize,
+        /// Largest random bundle size
+        #[arg(long, default_value_t = 3)]
+        max_bundle_size: usize,
+        /// Deal goods randomly instead of round-robin
+        #[arg(long)]
+        random_endowments: bool,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Write the instance here instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Serve the HTTP API
+    #[cfg(feature = "server")]
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:3000")]
+        addr: std::net::SocketAddr,
+    },
+}
 
-    // Create and run auction
-    let auction = CombinatorialAuction::new(agents, goods, 0.01);
-    let result = auction.run();
+/// Property report for the `verify` subcommand
+#[derive(Serialize)]
+struct VerifyReport {
+    is_feasible: bool,
+    is_individually_rational: bool,
+    is_ordinal_efficient: bool,
+}
 
-    // Display results
-    println!("Auction Results:");
-    println!("===============\n");
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    println!("Allocation:");
-    for (agent_id, bundle) in &result.allocation.assignments {
-        let good_names: Vec<String> = bundle.iter().map(|g| g.name.clone()).collect();
-        println!("  {}: [{}]", agent_id, good_names.join(", "));
+fn run(cli: Cli) -> Result<(), AuctionError> {
+    match cli.command {
+        Command::Solve {
+            instance,
+            epsilon,
+            bids,
+            mechanism,
+            vcg,
+            core,
+            housing_market,
+            explain,
+            output,
+        } => {
+            let scenario = is_scenario_path(&instance)
+                .then(|| Scenario::from_path(&instance))
+                .transpose()?;
+            let mut instance = match &scenario {
+                Some(scenario) => scenario.to_instance()?,
+                None => read_instance(instance)?,
+            };
+            if let Some(epsilon) = epsilon {
+                instance.epsilon = epsilon;
+            }
+            for (agent_id, bid) in &bids {
+                let valuation = parse_bid(bid, &instance.goods)?;
+                let agent = instance
+                    .agents
+                    .iter_mut()
+                    .find(|agent| agent.id.as_str() == agent_id)
+                    .ok_or_else(|| AuctionError::UnknownAgent(AgentId::from(agent_id.as_str())))?;
+                agent.set_valuation(valuation);
+            }
+            if let Err(violations) = instance.validate() {
+                for violation in violations {
+                    eprintln!("warning: {}", violation);
+                }
+            }
+            let mut auction = CombinatorialAuction::from_instance(instance)?;
+            if let Some(scenario) = &scenario {
+                auction = scenario.mechanism.configure(auction);
+            }
+            if let Some(kind) = mechanism {
+                auction = auction.with_mechanism_kind(kind);
+            }
+            if vcg {
+                auction = auction.with_vcg_payments();
+            }
+            if core {
+                auction = auction.with_core_payments();
+            }
+            if housing_market {
+                auction = auction.with_housing_market();
+            }
+            let result = auction.run()?;
+            for reason in &result.non_convergence {
+                eprintln!("warning: {}", reason);
+            }
+            if explain {
+                eprint!("{}", result.explain(auction.agents()));
+            }
+            Ok(emit(&result, output.as_deref())?)
+        }
+        Command::Verify {
+            instance,
+            allocation,
+        } => {
+            let allocation = read_allocation(&allocation)?;
+            // Check against the same mechanism (constraints included) `solve` runs
+            let instance = AuctionInstance::from_path(instance)?;
+            let auction = CombinatorialAuction::from_instance(instance)?;
+            let (mechanism, agents) = (auction.mechanism(), auction.agents());
+            let report = VerifyReport {
+                is_feasible: mechanism.verify_feasibility(&allocation, auction.goods())
+                    && mechanism.verify_reserve_prices(agents, &allocation)
+                    && mechanism.verify_capacity(agents, &allocation),
+                is_individually_rational: mechanism
+                    .verify_individual_rationality(agents, &allocation),
+                is_ordinal_efficient: mechanism.verify_ordinal_efficiency(agents, &allocation),
+            };
+            Ok(emit(&report, None)?)
+        }
+        Command::Generate {
+            goods,
+            agents,
+            bundles,
+            max_bundle_size,
+            random_endowments,
+            seed,
+            output,
+        } => {
+            let config = GeneratorConfig {
+                num_goods: goods,
+                num_agents: agents,
+                bundles_per_agent: bundles,
+                max_bundle_size,
+                endowments: if random_endowments {
+                    EndowmentScheme::Random
+                } else {
+                    EndowmentScheme::RoundRobin
+                },
+                ..GeneratorConfig::default()
+            };
+            Ok(emit(&generate(&config, seed), output.as_deref())?)
+        }
+        #[cfg(feature = "server")]
+        Command::Serve { addr } => {
+            let runtime = tokio::runtime::Runtime::new().map_err(ParseError::from)?;
+            eprintln!("listening on http://{}", addr);
+            Ok(runtime
+                .block_on(brace_sybil::server::serve(addr))
+                .map_err(ParseError::from)?)
+        }
     }
+}
+
+/// Split a `--bid` argument into agent ID and expression
+fn parse_agent_bid(arg: &str) -> Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(agent, bid)| (agent.trim().to_string(), bid.to_string()))
+        .ok_or_else(|| format!("expected AGENT=EXPR, got '{}'", arg))
+}
 
-    println!("\nPrices:");
-    for (good_id, price) in &result.prices {
-        println!("  {}: {:.2}", good_id, price);
+/// Parse a `--mechanism` name
+fn parse_mechanism_kind(arg: &str) -> Result<MechanismKind, String> {
+    match arg.split_once(':') {
+        Some(("rsd", seed)) => seed
+            .parse()
+            .map(MechanismKind::RandomSerialDictatorship)
+            .map_err(|_| format!("expected rsd:SEED, got '{}'", arg)),
+        _ => match arg {
+            "brace" => Ok(MechanismKind::Brace),
+            "ttc" => Ok(MechanismKind::TopTradingCycles),
+            "greedy" => Ok(MechanismKind::Greedy),
+            "leximin" => Ok(MechanismKind::Leximin),
+            "envy-cycle" => Ok(MechanismKind::EnvyCycle),
+            _ => Err(format!("unknown mechanism '{}'", arg)),
+        },
     }
+}
 
-    println!("\nTotal Welfare: {:.2}", result.total_welfare);
-    println!("\nProperties:");
-    println!("  Feasible: {}", result.is_feasible);
-    println!("  Individually Rational: {}", result.is_individually_rational);
-    println!("  Ordinal Efficient: {}", result.is_ordinal_efficient);
+/// Read a JSON allocation
+fn read_allocation(path: &Path) -> Result<Allocation, ParseError> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
 }
 
+/// Print JSON to stdout or write it to a file
+fn emit<T: Serialize>(value: &T, output: Option<&Path>) -> Result<(), ParseError> {
+    let json = serde_json::to_string_pretty(value)?;
+    match output {
+        Some(path) => fs::write(path, json)?,
+        None => println!("{}", json),
+    }
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use crate::ids::{AgentId, GoodId};
+use crate::pricing::{core_selecting_payments, PriceVector};
+use crate::types::{unit_count, Agent, Allocation, Bundle, Good};
+use crate::wdp::solve_exact;
+use std::collections::{HashMap, HashSet};
+
+/// Combinatorial Clock Auction (CCA), the two-stage spectrum format.
+/// Clock stage: every round each agent bids on one most-profitable bundle
+/// at the current prices, and the price of every over-demanded good rises
+/// by `increment` until demand fits supply. Supplementary stage: agents
+/// submit sealed bundle bids (their values, limited by the final-cap
+/// activity rule), winners are picked from all bids and pay core-selecting
+/// prices. Endowments are ignored; goods start out with the seller.
+#[derive(Debug, Clone)]
+pub struct CombinatorialClockAuction {
+    /// Price increase per clock round for over-demanded goods
+    pub increment: f64,
+    /// Safety cap on the number of clock rounds
+    pub max_rounds: usize,
+}
+
+/// Outcome of a CCA
+#[derive(Debug, Clone)]
+pub struct CcaResult {
+    pub allocation: Allocation,
+    /// Core-selecting payment of each agent (0 for losers)
+    pub payments: HashMap<AgentId, f64>,
+    pub revenue: f64,
+    /// Prices when the clock stage ended
+    pub clock_prices: PriceVector,
+    /// Bundle each agent bid on in each clock round (agents that dropped out are absent)
+    pub clock_bids: Vec<HashMap<AgentId, Bundle>>,
+    /// Sealed bids of the supplementary round
+    pub supplementary_bids: HashMap<AgentId, Vec<(Bundle, f64)>>,
+    /// False if the round cap was hit while goods were still over-demanded
+    pub converged: bool,
+}
+
+impl CombinatorialClockAuction {
+    pub fn new(increment: f64) -> Self {
+        Self {
+            increment,
+            max_rounds: 10_000,
+        }
+    }
+
+    pub fn with_max_rounds(mut self, max_rounds: usize) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    pub fn run(&self, agents: &[Agent], goods: &[Good]) -> CcaResult {
+        let mut prices = PriceVector::new();
+        for good in goods {
+            prices.set_price(good.id, good.reserve_price);
+        }
+
+        // Clock stage
+        let mut clock_bids = Vec::new();
+        let mut converged = false;
+        while clock_bids.len() < self.max_rounds {
+            let bids: HashMap<AgentId, Bundle> = agents
+                .iter()
+                .filter_map(|agent| clock_demand(agent, &prices).map(|b| (agent.id, b)))
+                .collect();
+
+            let mut demand: HashMap<GoodId, u32> = HashMap::new();
+            for agent in agents {
+                if let Some(bundle) = bids.get(&agent.id) {
+                    for good in bundle {
+                        *demand.entry(good.id).or_insert(0) +=
+                            unit_count(bundle, agent.bundle_units(bundle), good.id);
+                    }
+                }
+            }
+            clock_bids.push(bids);
+
+            let over: Vec<&Good> = goods
+                .iter()
+                .filter(|good| demand.get(&good.id).copied().unwrap_or(0) > good.supply)
+                .collect();
+            if over.is_empty() {
+                converged = true;
+                break;
+            }
+            for good in over {
+                prices.set_price(good.id, prices.get_price(good.id) + self.increment);
+            }
+        }
+
+        // Supplementary stage: bids are capped relative to the final clock bundle
+        let last = clock_bids.last().cloned().unwrap_or_default();
+        let mut supplementary_bids = HashMap::new();
+        let mut bidders = Vec::new();
+        for agent in agents {
+            let final_bundle = last.get(&agent.id);
+            let anchor = final_bundle.map(|bundle| {
+                (agent.preference(bundle), prices.agent_bundle_price(agent, bundle))
+            });
+
+            let mut bids = Vec::new();
+            let mut bidder = Agent::new(agent.id, HashSet::new());
+            for bundle in agent.preference_bundles() {
+                let value = agent.preference(bundle);
+                let price = prices.agent_bundle_price(agent, bundle);
+                let cap = if final_bundle == Some(bundle) {
+                    f64::INFINITY
+                } else if let Some((anchor_bid, anchor_price)) = anchor {
+                    anchor_bid + price - anchor_price
+                } else {
+                    // Dropped out: no more than the final clock price
+                    price
+                };
+                let bid = value.min(cap);
+                if bundle.is_empty() || bid <= 0.0 {
+                    continue;
+                }
+                match agent.bundle_units(bundle) {
+                    Some(units) => {
+                        bidder.add_multi_unit_preference(bundle.clone(), units.clone(), bid)
+                    }
+                    None => bidder.add_preference(bundle.clone(), bid),
+                }
+                bids.push((bundle.clone(), bid));
+            }
+            supplementary_bids.insert(agent.id, bids);
+            bidders.push(bidder);
+        }
+
+        let (mut allocation, _) = solve_exact(&bidders, goods);
+        let payments = core_selecting_payments(&bidders, goods, &allocation);
+        for agent in agents {
+            if allocation.get_bundle(agent.id).is_none() {
+                allocation.assign(agent.id, Bundle::new());
+            }
+        }
+
+        CcaResult {
+            allocation,
+            revenue: payments.values().sum(),
+            payments,
+            clock_prices: prices,
+            clock_bids,
+            supplementary_bids,
+            converged,
+        }
+    }
+}
+
+/// First bundle in the agent's demand set, if it is profitable
+fn clock_demand(agent: &Agent, prices: &PriceVector) -> Option<Bundle> {
+    prices
+        .demand_set(agent)
+        .into_iter()
+        .find(|bundle| !bundle.is_empty() && prices.net_utility(agent, bundle) > 1e-9)
+}
@@ -0,0 +1,126 @@
+use crate::ids::AgentId;
+use crate::pricing::PriceVector;
+use crate::types::{Agent, Allocation, Bundle, Good};
+use std::collections::HashMap;
+
+/// Deferred-acceptance descending clock auction for procurement (Milgrom
+/// and Segal). The auctioneer buys enough endowments to cover every good
+/// in `goods`; each seller's cost is its value for its own endowment.
+/// Every active seller faces a personal clock price that starts at
+/// `opening_price`. Sellers are visited in turn: one whose exit would leave
+/// some good uncovered is accepted at its current price; any other has its
+/// price lowered by `decrement` and drops out once the price is below its
+/// cost. The auction ends when every remaining seller is accepted.
+/// Staying in while the price covers one's cost is obviously dominant, so
+/// the auction is strategyproof (even weakly group-strategyproof) for
+/// sellers, though the set it buys need not be the cheapest.
+#[derive(Debug, Clone)]
+pub struct DeferredAcceptanceAuction {
+    /// Starting clock price for every seller
+    pub opening_price: f64,
+    /// Price cut per visit to an unaccepted seller
+    pub decrement: f64,
+    /// Safety cap on the number of rounds
+    pub max_rounds: usize,
+}
+
+/// Outcome of a deferred-acceptance auction
+#[derive(Debug, Clone)]
+pub struct DeferredAcceptanceResult {
+    /// Sellers bought from, in the order they were accepted
+    pub winners: Vec<AgentId>,
+    /// Clock price paid to each winner
+    pub payments: HashMap<AgentId, f64>,
+    /// Losing sellers keep their endowments; winners hand theirs over
+    pub allocation: Allocation,
+    /// Each winner's payment spread evenly over the goods it supplies
+    pub prices: PriceVector,
+    pub total_cost: f64,
+    pub rounds: usize,
+    /// False if the sellers together could not cover every good
+    pub covered: bool,
+}
+
+impl DeferredAcceptanceAuction {
+    pub fn new(opening_price: f64, decrement: f64) -> Self {
+        Self {
+            opening_price,
+            decrement,
+            max_rounds: 10_000,
+        }
+    }
+
+    pub fn with_max_rounds(mut self, max_rounds: usize) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    pub fn run(&self, sellers: &[Agent], goods: &[Good]) -> DeferredAcceptanceResult {
+        let mut active: Vec<usize> = (0..sellers.len())
+            .filter(|&i| !sellers[i].endowment.is_empty())
+            .filter(|&i| sellers[i].preference(&sellers[i].endowment) <= self.opening_price)
+            .collect();
+        let mut clock = vec![self.opening_price; sellers.len()];
+        let mut winners: Vec<AgentId> = Vec::new();
+        let covered = covers(sellers, &active, goods);
+
+        let mut rounds = 0;
+        while covered && rounds < self.max_rounds && winners.len() < active.len() {
+            rounds += 1;
+            for i in active.clone() {
+                let seller = &sellers[i];
+                if winners.contains(&seller.id) {
+                    continue;
+                }
+                let others: Vec<usize> = active.iter().copied().filter(|&j| j != i).collect();
+                if !covers(sellers, &others, goods) {
+                    winners.push(seller.id);
+                    continue;
+                }
+                clock[i] -= self.decrement;
+                if clock[i] < seller.preference(&seller.endowment) {
+                    active = others;
+                }
+            }
+        }
+
+        let mut allocation = Allocation::new();
+        let mut prices = PriceVector::new();
+        let mut payments = HashMap::new();
+        for (i, seller) in sellers.iter().enumerate() {
+            if covered && winners.contains(&seller.id) {
+                payments.insert(seller.id, clock[i]);
+                for good in &seller.endowment {
+                    prices.set_price(good.id, clock[i] / seller.endowment.len() as f64);
+                }
+                allocation.assign(seller.id, Bundle::new());
+            } else {
+                allocation.assign_units(
+                    seller.id,
+                    seller.endowment.clone(),
+                    seller.endowment_units.clone(),
+                );
+            }
+        }
+        if !covered {
+            winners.clear();
+        }
+
+        DeferredAcceptanceResult {
+            winners,
+            total_cost: payments.values().sum(),
+            payments,
+            allocation,
+            prices,
+            rounds,
+            covered,
+        }
+    }
+}
+
+/// Whether the endowments of `chosen` sellers include every good
+fn covers(sellers: &[Agent], chosen: &[usize], goods: &[Good]) -> bool {
+    goods
+        .iter()
+        .all(|good| chosen.iter().any(|&i| sellers[i].endowment.contains(good)))
+}
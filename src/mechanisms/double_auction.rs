@@ -0,0 +1,217 @@
+use crate::ids::{AgentId, GoodId};
+use crate::pricing::PriceVector;
+use crate::types::{sorted_ids, Agent, Allocation, Bundle};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// A seller's offer to give up `bundle` for at least `min_price`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ask {
+    pub seller: AgentId,
+    pub bundle: Bundle,
+    pub min_price: f64,
+}
+
+impl Ask {
+    pub fn new(seller: impl Into<AgentId>, bundle: Bundle, min_price: f64) -> Self {
+        Self {
+            seller: seller.into(),
+            bundle,
+            min_price,
+        }
+    }
+}
+
+/// A buy bundle matched to the asks that supply it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Match {
+    pub buyer: AgentId,
+    pub bundle: Bundle,
+    /// Indices into the asks passed to `DoubleAuction::run`
+    pub asks: Vec<usize>,
+    /// What the buyer pays, all of which goes to the sellers
+    pub price: f64,
+    /// Buyer's value minus the sellers' asks
+    pub surplus: f64,
+}
+
+/// Outcome of a double auction
+#[derive(Debug, Clone)]
+pub struct DoubleAuctionResult {
+    /// Buyers hold what they bought and sellers keep their unsold asks
+    pub allocation: Allocation,
+    /// Clearing price of every traded good
+    pub prices: PriceVector,
+    pub matches: Vec<Match>,
+    /// Net transfer per agent: positive for buyers, negative for sellers
+    pub payments: HashMap<AgentId, f64>,
+    /// Total gains from trade
+    pub surplus: f64,
+}
+
+impl DoubleAuctionResult {
+    /// Payments sum to zero: the auctioneer neither gains nor loses money
+    pub fn is_budget_balanced(&self) -> bool {
+        self.payments.values().sum::<f64>().abs() <= 1e-9
+    }
+}
+
+/// Combinatorial double auction for exchanges. Buyers bid through their
+/// preferences (each buys at most one bundle); sellers post `Ask`s. A buy
+/// bundle trades when disjoint unsold asks cover it exactly and its value
+/// exceeds their total; matches are made greedily by surplus. Each ask
+/// receives its minimum plus a share of the match surplus (`seller_share`,
+/// split in proportion to the asks), so buyers pay exactly what sellers receive.
+#[derive(Debug, Clone)]
+pub struct DoubleAuction {
+    /// Fraction of each match's surplus that goes to the sellers (0 to 1)
+    pub seller_share: f64,
+}
+
+impl Default for DoubleAuction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DoubleAuction {
+    /// Split the surplus evenly between buyer and sellers
+    pub fn new() -> Self {
+        Self { seller_share: 0.5 }
+    }
+
+    pub fn with_seller_share(mut self, share: f64) -> Self {
+        self.seller_share = share.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn run(&self, buyers: &[Agent], asks: &[Ask]) -> DoubleAuctionResult {
+        let mut sold = vec![false; asks.len()];
+        // Goods already traded, in case several sellers offer the same good
+        let mut traded: HashSet<GoodId> = HashSet::new();
+        let mut done: HashSet<AgentId> = HashSet::new();
+        let mut matches = Vec::new();
+
+        // Repeatedly take the best remaining match
+        loop {
+            let mut best: Option<(f64, &Agent, &Bundle, Vec<usize>)> = None;
+            for buyer in buyers.iter().filter(|b| !done.contains(&b.id)) {
+                for bundle in buyer.preference_bundles() {
+                    let Some((cost, cover)) = cheapest_cover(bundle, asks, &sold, &traded) else {
+                        continue;
+                    };
+                    let surplus = buyer.preference(bundle) - cost;
+                    if surplus > 1e-9 && best.as_ref().is_none_or(|(top, ..)| surplus > *top) {
+                        best = Some((surplus, buyer, bundle, cover));
+                    }
+                }
+            }
+            let Some((surplus, buyer, bundle, cover)) = best else {
+                break;
+            };
+            for &i in &cover {
+                sold[i] = true;
+            }
+            traded.extend(bundle.iter().map(|g| g.id));
+            done.insert(buyer.id);
+            let cost: f64 = cover.iter().map(|&i| asks[i].min_price).sum();
+            matches.push(Match {
+                buyer: buyer.id,
+                bundle: bundle.clone(),
+                asks: cover,
+                price: cost + self.seller_share * surplus,
+                surplus,
+            });
+        }
+
+        let mut allocation = Allocation::new();
+        let mut prices = PriceVector::new();
+        let mut payments: HashMap<AgentId, f64> = HashMap::new();
+        for m in &matches {
+            allocation.assign(m.buyer, m.bundle.clone());
+            *payments.entry(m.buyer).or_insert(0.0) += m.price;
+
+            let cost: f64 = m.asks.iter().map(|&i| asks[i].min_price).sum();
+            for &i in &m.asks {
+                let ask = &asks[i];
+                let weight = if cost > 0.0 {
+                    ask.min_price / cost
+                } else {
+                    1.0 / m.asks.len() as f64
+                };
+                let received = ask.min_price + self.seller_share * m.surplus * weight;
+                *payments.entry(ask.seller).or_insert(0.0) -= received;
+                for good in &ask.bundle {
+                    prices.set_price(good.id, received / ask.bundle.len() as f64);
+                }
+            }
+        }
+        for (ask, _) in asks.iter().zip(&sold).filter(|(_, &sold)| !sold) {
+            let mut kept = allocation.get_bundle(ask.seller).cloned().unwrap_or_default();
+            kept.extend(ask.bundle.iter().cloned());
+            allocation.assign(ask.seller, kept);
+        }
+
+        DoubleAuctionResult {
+            allocation,
+            prices,
+            surplus: matches.iter().map(|m| m.surplus).sum(),
+            matches,
+            payments,
+        }
+    }
+}
+
+/// Cheapest set of unsold, pairwise disjoint asks whose bundles make up
+/// exactly `bundle`, with its total ask
+fn cheapest_cover(
+    bundle: &Bundle,
+    asks: &[Ask],
+    sold: &[bool],
+    traded: &HashSet<GoodId>,
+) -> Option<(f64, Vec<usize>)> {
+    if bundle.iter().any(|g| traded.contains(&g.id)) {
+        return None;
+    }
+    let candidates: Vec<usize> = (0..asks.len())
+        .filter(|&i| !sold[i] && !asks[i].bundle.is_empty() && asks[i].bundle.is_subset(bundle))
+        .collect();
+    let remaining: Vec<GoodId> = sorted_ids(bundle);
+    let mut best = None;
+    cover_from(&remaining, asks, &candidates, &mut Vec::new(), 0.0, &mut best);
+    best
+}
+
+fn cover_from(
+    remaining: &[GoodId],
+    asks: &[Ask],
+    candidates: &[usize],
+    chosen: &mut Vec<usize>,
+    cost: f64,
+    best: &mut Option<(f64, Vec<usize>)>,
+) {
+    if best.as_ref().is_some_and(|(top, _)| cost >= *top) {
+        return;
+    }
+    // Cover the first remaining good with each ask that contains it
+    let Some(&first) = remaining.first() else {
+        *best = Some((cost, chosen.clone()));
+        return;
+    };
+    for &i in candidates {
+        let ask = &asks[i];
+        let fits = ask.bundle.iter().any(|g| g.id == first)
+            && ask.bundle.iter().all(|g| remaining.contains(&g.id));
+        if !fits {
+            continue;
+        }
+        let rest: Vec<GoodId> = remaining
+            .iter()
+            .copied()
+            .filter(|id| !ask.bundle.iter().any(|g| g.id == *id))
+            .collect();
+        chosen.push(i);
+        cover_from(&rest, asks, candidates, chosen, cost + ask.min_price, best);
+        chosen.pop();
+    }
+}
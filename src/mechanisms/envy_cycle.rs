@@ -0,0 +1,80 @@
+use crate::types::{Agent, Allocation, Bundle, Good};
+
+/// Envy-cycle elimination (Lipton et al.) for fair division without money.
+/// Goods are handed out one at a time to an agent nobody envies; when every
+/// agent is envied, bundles are rotated backwards along an envy cycle until
+/// one is not. Endowments are ignored and each good is treated as a single
+/// item. With monotone valuations the result is EF1 (see
+/// `BRACEMechanism::verify_ef1`).
+#[derive(Debug, Clone, Default)]
+pub struct EnvyCycleMechanism;
+
+impl EnvyCycleMechanism {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Allocate every good, keeping the allocation EF1 after each step
+    pub fn compute_allocation(&self, agents: &[Agent], goods: &[Good]) -> Allocation {
+        let mut bundles: Vec<Bundle> = vec![Bundle::new(); agents.len()];
+
+        if !agents.is_empty() {
+            for good in goods {
+                let receiver = loop {
+                    match unenvied(agents, &bundles) {
+                        Some(i) => break i,
+                        None => rotate_cycle(agents, &mut bundles),
+                    }
+                };
+                bundles[receiver].insert(good.clone());
+            }
+        }
+
+        let mut allocation = Allocation::new();
+        for (agent, bundle) in agents.iter().zip(bundles) {
+            allocation.assign(agent.id, bundle);
+        }
+        allocation
+    }
+}
+
+/// Whether agent `i` strictly prefers agent `j`'s bundle to its own
+fn envies(agents: &[Agent], bundles: &[Bundle], i: usize, j: usize) -> bool {
+    agents[i].preference(&bundles[j]) > agents[i].preference(&bundles[i]) + 1e-9
+}
+
+/// First agent that no one envies
+fn unenvied(agents: &[Agent], bundles: &[Bundle]) -> Option<usize> {
+    (0..agents.len()).find(|&j| (0..agents.len()).all(|i| i == j || !envies(agents, bundles, i, j)))
+}
+
+/// Every agent is envied, so walking back along envy edges must revisit
+/// an agent; give each agent on that cycle the bundle it envies
+fn rotate_cycle(agents: &[Agent], bundles: &mut [Bundle]) {
+    let envier = |j: usize| {
+        (0..agents.len())
+            .find(|&i| i != j && envies(agents, bundles, i, j))
+            .expect("every agent is envied")
+    };
+
+    let mut visited = vec![false; agents.len()];
+    let mut current = 0;
+    while !visited[current] {
+        visited[current] = true;
+        current = envier(current);
+    }
+
+    // cycle[k + 1] envies cycle[k], and cycle[0] envies the last one
+    let mut cycle = vec![current];
+    let mut node = envier(current);
+    while node != current {
+        cycle.push(node);
+        node = envier(node);
+    }
+
+    let n = cycle.len();
+    let taken: Vec<Bundle> = (0..n).map(|k| bundles[cycle[(k + n - 1) % n]].clone()).collect();
+    for (&i, bundle) in cycle.iter().zip(taken) {
+        bundles[i] = bundle;
+    }
+}
@@ -0,0 +1,41 @@
+use crate::types::{Agent, Allocation, Good};
+use crate::wdp::{GreedySolver, WdpSolver};
+use serde::{Deserialize, Serialize};
+
+/// Outcome of the greedy allocator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GreedyResult {
+    pub allocation: Allocation,
+    pub total_welfare: f64,
+    /// Worst-case ratio of optimal to greedy welfare: sqrt(number of goods)
+    pub approximation_ratio: f64,
+    /// Whether the ratio is guaranteed here: every agent bids on at most one
+    /// bundle (single-minded) and every good has a single unit
+    pub guarantee_holds: bool,
+}
+
+/// Greedy bid ranking (Lehmann, O'Callaghan and Shoham): bids are accepted in
+/// decreasing order of value / sqrt(bundle size) whenever their goods are
+/// still free. A fast baseline; it ignores endowments, so it is not
+/// individually rational.
+#[derive(Debug, Clone, Default)]
+pub struct GreedyMechanism;
+
+impl GreedyMechanism {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn run(&self, agents: &[Agent], goods: &[Good]) -> GreedyResult {
+        let (allocation, total_welfare) = GreedySolver.solve(agents, goods);
+        let single_minded = agents.iter().all(|agent| agent.preference_bundles().len() <= 1);
+        let unit_supply = goods.iter().all(|good| good.supply == 1);
+
+        GreedyResult {
+            allocation,
+            total_welfare,
+            approximation_ratio: (goods.len().max(1) as f64).sqrt(),
+            guarantee_holds: single_minded && unit_supply,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::ids::AgentId;
+use crate::pricing::PriceVector;
+use crate::types::{Agent, Allocation, Bundle, Good};
+use crate::wdp::solve_exact;
+use std::collections::{HashMap, HashSet};
+
+/// iBundle ascending-proxy auction.
+/// Each agent faces its own (non-anonymous) bundle prices. Every round, proxy
+/// bidders bid the current price on every bundle in their demand set, the
+/// auctioneer picks a revenue-maximizing provisional allocation, and losing
+/// agents see the prices of their demanded bundles rise by `increment`.
+/// The auction ends once every active agent is provisionally winning.
+#[derive(Debug, Clone)]
+pub struct IBundleAuction {
+    /// Minimum bid increment applied to losing agents' bundles
+    pub increment: f64,
+    /// Safety cap on the number of rounds
+    pub max_rounds: usize,
+}
+
+/// Outcome of an iBundle auction
+#[derive(Debug, Clone)]
+pub struct IBundleResult {
+    pub allocation: Allocation,
+    /// Each winner pays its final price for the bundle it wins
+    pub payments: HashMap<AgentId, f64>,
+    /// Final per-agent bundle prices
+    pub prices: HashMap<AgentId, PriceVector>,
+    pub revenue: f64,
+    pub rounds: usize,
+    /// False if the round cap was hit before every active agent was winning
+    pub converged: bool,
+}
+
+impl IBundleAuction {
+    pub fn new(increment: f64) -> Self {
+        Self {
+            increment,
+            max_rounds: 10_000,
+        }
+    }
+
+    pub fn with_max_rounds(mut self, max_rounds: usize) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    /// Run the auction. Goods start out with the seller, so endowments are ignored.
+    pub fn run(&self, agents: &[Agent], goods: &[Good]) -> IBundleResult {
+        let mut prices: HashMap<AgentId, PriceVector> = agents
+            .iter()
+            .map(|agent| {
+                let mut vector = PriceVector::new();
+                for good in goods {
+                    vector.set_price(good.id, good.reserve_price);
+                }
+                (agent.id, vector)
+            })
+            .collect();
+
+        let mut allocation = Allocation::new();
+        let mut revenue = 0.0;
+        let mut rounds = 0;
+        let mut converged = false;
+
+        while rounds < self.max_rounds {
+            rounds += 1;
+
+            // Proxy bids: the demand set at current personal prices, as an XOR bid
+            let mut demands: Vec<(&Agent, Vec<Bundle>)> = Vec::new();
+            let mut bidders = Vec::new();
+            for agent in agents {
+                let agent_prices = &prices[&agent.id];
+                let demand: Vec<Bundle> = agent_prices
+                    .demand_set(agent)
+                    .into_iter()
+                    .filter(|bundle| {
+                        !bundle.is_empty() && agent_prices.net_utility(agent, bundle) >= -1e-9
+                    })
+                    .collect();
+                if demand.is_empty() {
+                    continue;
+                }
+
+                let mut bidder = Agent::new(agent.id, HashSet::new());
+                for bundle in &demand {
+                    let bid = agent_prices.agent_bundle_price(agent, bundle);
+                    match agent.bundle_units(bundle) {
+                        Some(units) => {
+                            bidder.add_multi_unit_preference(bundle.clone(), units.clone(), bid)
+                        }
+                        None => bidder.add_preference(bundle.clone(), bid),
+                    }
+                }
+                bidders.push(bidder);
+                demands.push((agent, demand));
+            }
+
+            // Provisional allocation maximizes revenue over the submitted bids
+            let (provisional, provisional_revenue) = solve_exact(&bidders, goods);
+            allocation = provisional;
+            revenue = provisional_revenue;
+
+            let losers: Vec<&(&Agent, Vec<Bundle>)> = demands
+                .iter()
+                .filter(|(agent, _)| {
+                    allocation
+                        .get_bundle(agent.id)
+                        .is_none_or(|bundle| bundle.is_empty())
+                })
+                .collect();
+            if losers.is_empty() {
+                converged = true;
+                break;
+            }
+
+            for (agent, demand) in losers {
+                let agent_prices = prices.get_mut(&agent.id).expect("every agent has prices");
+                for bundle in demand {
+                    let price = agent_prices.agent_bundle_price(agent, bundle);
+                    agent_prices.set_bundle_price(bundle, price + self.increment);
+                }
+            }
+        }
+
+        let mut payments = HashMap::new();
+        for agent in agents {
+            match allocation.get_bundle(agent.id) {
+                Some(bundle) if !bundle.is_empty() => {
+                    payments.insert(agent.id, prices[&agent.id].agent_bundle_price(agent, bundle));
+                }
+                _ => {
+                    allocation.assign(agent.id, Bundle::new());
+                    payments.insert(agent.id, 0.0);
+                }
+            }
+        }
+
+        IBundleResult {
+            allocation,
+            payments,
+            prices,
+            revenue,
+            rounds,
+            converged,
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::ids::AgentId;
+use crate::types::{Agent, Allocation, Good};
+use crate::wdp::search_all;
+
+/// Outcome of the leximin mechanism
+#[derive(Debug, Clone)]
+pub struct LeximinResult {
+    pub allocation: Allocation,
+    /// Each agent's utility, worst-off first
+    pub utilities: Vec<(AgentId, f64)>,
+}
+
+impl LeximinResult {
+    /// Utility of the worst-off agent
+    pub fn min_utility(&self) -> f64 {
+        self.utilities.first().map_or(0.0, |&(_, u)| u)
+    }
+}
+
+/// Leximin (max-min fair) allocation.
+/// Maximizes the smallest agent utility, then the second smallest, and so
+/// on. Endowments are ignored: goods are allocated from scratch. Every
+/// feasible assignment of declared bundles is searched, so this is only
+/// suitable for small instances.
+#[derive(Debug, Clone, Default)]
+pub struct LeximinMechanism;
+
+impl LeximinMechanism {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Compute the leximin-optimal allocation
+    pub fn run(&self, agents: &[Agent], goods: &[Good]) -> LeximinResult {
+        let (allocation, values) = search_all(agents, goods, leximin_better);
+
+        let mut utilities: Vec<(AgentId, f64)> =
+            agents.iter().map(|a| a.id).zip(values).collect();
+        utilities.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        LeximinResult {
+            allocation,
+            utilities,
+        }
+    }
+}
+
+/// Whether `new` beats `old` in the leximin order: compare sorted utility
+/// vectors position by position, worst-off first
+fn leximin_better(new: &[f64], old: &[f64]) -> bool {
+    let sorted = |values: &[f64]| {
+        let mut values = values.to_vec();
+        values.sort_by(f64::total_cmp);
+        values
+    };
+    for (a, b) in sorted(new).iter().zip(&sorted(old)) {
+        if a > &(b + 1e-9) {
+            return true;
+        }
+        if b > &(a + 1e-9) {
+            return false;
+        }
+    }
+    false
+}
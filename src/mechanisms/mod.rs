@@ -0,0 +1,23 @@
+pub mod cca;
+pub mod deferred_acceptance;
+pub mod double_auction;
+pub mod envy_cycle;
+pub mod greedy;
+pub mod ibundle;
+pub mod leximin;
+pub mod probabilistic_serial;
+pub mod rsd;
+pub mod smra;
+pub mod ttc;
+
+pub use cca::{CcaResult, CombinatorialClockAuction};
+pub use deferred_acceptance::{DeferredAcceptanceAuction, DeferredAcceptanceResult};
+pub use double_auction::{Ask, DoubleAuction, DoubleAuctionResult, Match};
+pub use envy_cycle::EnvyCycleMechanism;
+pub use greedy::{GreedyMechanism, GreedyResult};
+pub use ibundle::{IBundleAuction, IBundleResult};
+pub use leximin::{LeximinMechanism, LeximinResult};
+pub use probabilistic_serial::ProbabilisticSerial;
+pub use rsd::RandomSerialDictatorship;
+pub use smra::{SmraAuction, SmraResult, SmraRound};
+pub use ttc::TtcMechanism;
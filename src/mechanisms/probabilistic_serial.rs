@@ -0,0 +1,166 @@
+use crate::ids::GoodId;
+use crate::types::{Agent, Bundle, FractionalAllocation, Good};
+use std::collections::{HashMap, HashSet};
+
+/// Probabilistic Serial (Bogomolnaia and Moulin) for the assignment case:
+/// each agent wants one good and ranks goods by its value for them alone
+/// (unvalued goods are unacceptable; ties go to the smaller ID). All agents
+/// "eat" their favorite remaining good at unit speed until they have eaten
+/// one unit in total or nothing acceptable is left. The amount eaten is the
+/// probability of receiving the good. Endowments are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct ProbabilisticSerial;
+
+impl ProbabilisticSerial {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Run the eating algorithm. Each agent's lottery lists single-good
+    /// bundles, plus the empty bundle for any probability left unassigned.
+    pub fn compute_allocation(&self, agents: &[Agent], goods: &[Good]) -> FractionalAllocation {
+        let rankings: Vec<Vec<GoodId>> = agents.iter().map(|agent| ranking(agent, goods)).collect();
+        let mut remaining: HashMap<GoodId, f64> =
+            goods.iter().map(|good| (good.id, good.supply as f64)).collect();
+        let mut eaten: Vec<HashMap<GoodId, f64>> = vec![HashMap::new(); agents.len()];
+        let mut total = vec![0.0; agents.len()];
+
+        loop {
+            // What each agent still eating is eating now
+            let targets: Vec<(usize, GoodId)> = (0..agents.len())
+                .filter(|&i| total[i] < 1.0 - 1e-12)
+                .filter_map(|i| {
+                    rankings[i]
+                        .iter()
+                        .find(|id| remaining[*id] > 1e-12)
+                        .map(|&id| (i, id))
+                })
+                .collect();
+            if targets.is_empty() {
+                break;
+            }
+
+            let mut eaters: HashMap<GoodId, f64> = HashMap::new();
+            for &(_, id) in &targets {
+                *eaters.entry(id).or_insert(0.0) += 1.0;
+            }
+            // Advance until some good runs out or some agent is full
+            let step = eaters
+                .iter()
+                .map(|(id, count)| remaining[id] / count)
+                .chain(targets.iter().map(|&(i, _)| 1.0 - total[i]))
+                .fold(f64::INFINITY, f64::min);
+
+            for &(i, id) in &targets {
+                *eaten[i].entry(id).or_insert(0.0) += step;
+                total[i] += step;
+                *remaining.get_mut(&id).expect("ranked goods exist") -= step;
+            }
+        }
+
+        let mut allocation = FractionalAllocation::new();
+        for (i, agent) in agents.iter().enumerate() {
+            let mut ids: Vec<GoodId> = eaten[i].keys().copied().collect();
+            ids.sort_by_key(|id| id.as_str());
+            for id in ids {
+                let good = goods.iter().find(|g| g.id == id).expect("ranked goods exist");
+                allocation.add_share(agent.id, Bundle::from([good.clone()]), eaten[i][&id]);
+            }
+            if total[i] < 1.0 - 1e-9 {
+                allocation.add_share(agent.id, Bundle::new(), 1.0 - total[i]);
+            }
+        }
+        allocation
+    }
+
+    /// Whether a random assignment is ordinally efficient, i.e. no other
+    /// random assignment stochastically dominates it for every agent.
+    /// Uses the Bogomolnaia-Moulin characterization: the assignment must be
+    /// non-wasteful (nobody has probability on a worse good, or on nothing,
+    /// while a good it prefers is not fully assigned) and the relation
+    /// "some agent prefers a to b but gets b with positive probability"
+    /// must be acyclic.
+    pub fn verify_ordinal_efficiency(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        allocation: &FractionalAllocation,
+    ) -> bool {
+        let assigned: HashMap<GoodId, f64> = goods
+            .iter()
+            .map(|good| {
+                let share = agents.iter().map(|a| allocation.good_share(a.id, good.id)).sum();
+                (good.id, share)
+            })
+            .collect();
+
+        let mut edges: HashMap<GoodId, HashSet<GoodId>> = HashMap::new();
+        for agent in agents {
+            let value = |id: GoodId| single_value(agent, goods, id);
+            let held: Vec<GoodId> = goods
+                .iter()
+                .map(|g| g.id)
+                .filter(|&id| allocation.good_share(agent.id, id) > 1e-9)
+                .collect();
+            let unassigned: f64 =
+                1.0 - held.iter().map(|&id| allocation.good_share(agent.id, id)).sum::<f64>();
+
+            for better in goods.iter().map(|g| g.id).filter(|&id| value(id) > 0.0) {
+                let has_worse = unassigned > 1e-9 || held.iter().any(|&b| value(better) > value(b));
+                let good = goods.iter().find(|g| g.id == better).expect("listed good");
+                if has_worse && assigned[&better] < good.supply as f64 - 1e-9 {
+                    return false;
+                }
+                for &worse in held.iter().filter(|&&b| value(better) > value(b)) {
+                    edges.entry(better).or_default().insert(worse);
+                }
+            }
+        }
+        !has_cycle(&edges)
+    }
+}
+
+/// Acceptable goods from best to worst
+fn ranking(agent: &Agent, goods: &[Good]) -> Vec<GoodId> {
+    let mut ids: Vec<GoodId> = goods
+        .iter()
+        .map(|good| good.id)
+        .filter(|&id| single_value(agent, goods, id) > 0.0)
+        .collect();
+    ids.sort_by(|&a, &b| {
+        single_value(agent, goods, b)
+            .total_cmp(&single_value(agent, goods, a))
+            .then_with(|| a.as_str().cmp(b.as_str()))
+    });
+    ids
+}
+
+fn single_value(agent: &Agent, goods: &[Good], id: GoodId) -> f64 {
+    goods
+        .iter()
+        .find(|good| good.id == id)
+        .map_or(0.0, |good| agent.preference(&Bundle::from([good.clone()])))
+}
+
+fn has_cycle(edges: &HashMap<GoodId, HashSet<GoodId>>) -> bool {
+    // 0 = unvisited, 1 = on the stack, 2 = done
+    fn visit(
+        node: GoodId,
+        edges: &HashMap<GoodId, HashSet<GoodId>>,
+        state: &mut HashMap<GoodId, u8>,
+    ) -> bool {
+        match state.get(&node) {
+            Some(1) => return true,
+            Some(2) => return false,
+            _ => {}
+        }
+        state.insert(node, 1);
+        let found = edges
+            .get(&node)
+            .is_some_and(|next| next.iter().any(|&n| visit(n, edges, state)));
+        state.insert(node, 2);
+        found
+    }
+    let mut state = HashMap::new();
+    edges.keys().any(|&node| visit(node, edges, &mut state))
+}
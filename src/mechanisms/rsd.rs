@@ -0,0 +1,81 @@
+use crate::auction::CombinatorialAuction;
+use crate::error::AuctionError;
+use crate::ids::GoodId;
+use crate::pricing::PriceVector;
+use crate::types::{unit_count, Agent, Allocation, AuctionResult, Bundle, Good};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::HashMap;
+
+/// Random Serial Dictatorship: agents are put in a random (seeded) order
+/// and each in turn takes its most valued bundle among those that still fit
+/// the remaining supply and its capacity. Endowments are pooled and there
+/// is no money, so the result reports zero prices. Strategyproof, and a
+/// simple baseline to compare BRACE against.
+#[derive(Debug, Clone)]
+pub struct RandomSerialDictatorship {
+    pub seed: u64,
+    /// Tolerance used when checking the allocation
+    pub epsilon: f64,
+}
+
+impl RandomSerialDictatorship {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, epsilon: 0.01 }
+    }
+
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Run the mechanism; fails only if the instance itself is invalid
+    pub fn run(&self, agents: &[Agent], goods: &[Good]) -> Result<AuctionResult, AuctionError> {
+        let auction = CombinatorialAuction::new(agents.to_vec(), goods.to_vec(), self.epsilon)?;
+        Ok(auction.evaluate(self.compute_allocation(agents, goods), &PriceVector::new()))
+    }
+
+    /// Let every agent pick in the seeded random order
+    pub fn compute_allocation(&self, agents: &[Agent], goods: &[Good]) -> Allocation {
+        let mut order: Vec<&Agent> = agents.iter().collect();
+        order.shuffle(&mut StdRng::seed_from_u64(self.seed));
+
+        let mut remaining: HashMap<GoodId, u32> =
+            goods.iter().map(|good| (good.id, good.supply)).collect();
+        let mut allocation = Allocation::new();
+        for agent in order {
+            let fits = |bundle: &Bundle| {
+                let units = agent.bundle_units(bundle);
+                agent.within_capacity(bundle, units)
+                    && bundle.iter().all(|good| {
+                        remaining
+                            .get(&good.id)
+                            .is_some_and(|&left| unit_count(bundle, units, good.id) <= left)
+                    })
+            };
+            let favorite = agent
+                .preference_bundles()
+                .iter()
+                .filter(|bundle| fits(bundle) && agent.preference(bundle) > 0.0)
+                .fold(None, |best: Option<&Bundle>, bundle| match best {
+                    Some(top) if agent.preference(top) >= agent.preference(bundle) => best,
+                    _ => Some(bundle),
+                });
+
+            match favorite {
+                Some(bundle) => {
+                    let units = agent.bundle_units(bundle).cloned().unwrap_or_default();
+                    for good in bundle {
+                        if let Some(left) = remaining.get_mut(&good.id) {
+                            *left -= unit_count(bundle, Some(&units), good.id);
+                        }
+                    }
+                    allocation.assign_units(agent.id, bundle.clone(), units);
+                }
+                None => allocation.assign(agent.id, Bundle::new()),
+            }
+        }
+        allocation
+    }
+}
@@ -0,0 +1,173 @@
+use crate::auction::CombinatorialAuction;
+use crate::error::AuctionError;
+use crate::ids::{AgentId, GoodId};
+use crate::pricing::PriceVector;
+use crate::types::{sorted_ids, Agent, Allocation, AuctionResult, Bundle, Good};
+use std::collections::HashMap;
+
+/// Simultaneous Multi-Round Auction (SMRA).
+/// All goods are open at once and sold item by item. Every round each agent
+/// bids straightforwardly: it picks its most profitable bundle, valuing goods
+/// it provisionally wins at their standing bid and the others at the minimum
+/// bid (standing bid plus `increment`, or the reserve), and bids on the goods
+/// it is not already winning. The highest bid becomes the new provisional
+/// winner (earlier agents win ties). An activity rule caps each agent's
+/// eligibility at the number of goods it was active on in the previous
+/// round. The auction ends when a round brings no new bids. Goods are sold
+/// as single items and endowments are ignored.
+#[derive(Debug, Clone)]
+pub struct SmraAuction {
+    /// Minimum raise over the standing bid
+    pub increment: f64,
+    /// Safety cap on the number of rounds
+    pub max_rounds: usize,
+    /// Tolerance used when checking the final allocation
+    pub epsilon: f64,
+}
+
+/// State of the auction at the end of one round
+#[derive(Debug, Clone)]
+pub struct SmraRound {
+    /// Standing bid on every good
+    pub prices: PriceVector,
+    /// Provisional winner of every good that has received a bid
+    pub winners: HashMap<GoodId, AgentId>,
+    /// Goods each agent bid on this round (sorted by ID)
+    pub bids: HashMap<AgentId, Vec<GoodId>>,
+    /// Most goods each agent may be active on next round
+    pub eligibility: HashMap<AgentId, usize>,
+}
+
+/// Outcome of an SMRA
+#[derive(Debug, Clone)]
+pub struct SmraResult {
+    pub rounds: Vec<SmraRound>,
+    /// Each winner pays the standing bids on the goods it wins
+    pub payments: HashMap<AgentId, f64>,
+    /// Final allocation and prices, checked like a BRACE outcome
+    pub result: AuctionResult,
+    /// False if the round cap was hit while agents were still bidding
+    pub converged: bool,
+}
+
+impl SmraAuction {
+    pub fn new(increment: f64) -> Self {
+        Self {
+            increment,
+            max_rounds: 10_000,
+            epsilon: 0.01,
+        }
+    }
+
+    pub fn with_max_rounds(mut self, max_rounds: usize) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Run the auction; fails only if the instance itself is invalid
+    pub fn run(&self, agents: &[Agent], goods: &[Good]) -> Result<SmraResult, AuctionError> {
+        let auction = CombinatorialAuction::new(agents.to_vec(), goods.to_vec(), self.epsilon)?;
+
+        let mut standing = PriceVector::new();
+        for good in goods {
+            standing.set_price(good.id, good.reserve_price);
+        }
+        let mut winners: HashMap<GoodId, AgentId> = HashMap::new();
+        let mut eligibility: HashMap<AgentId, usize> =
+            agents.iter().map(|agent| (agent.id, goods.len())).collect();
+        let mut rounds = Vec::new();
+        let mut converged = false;
+
+        while rounds.len() < self.max_rounds {
+            let mut bids: HashMap<AgentId, Vec<GoodId>> = HashMap::new();
+            let mut new_high: HashMap<GoodId, (f64, AgentId)> = HashMap::new();
+            let mut next_eligibility = HashMap::new();
+
+            for agent in agents {
+                // Prices this agent faces
+                let mut faced = PriceVector::new();
+                for good in goods {
+                    let price = match winners.get(&good.id) {
+                        Some(&winner) if winner == agent.id => standing.get_price(good.id),
+                        Some(_) => standing.get_price(good.id) + self.increment,
+                        None => good.reserve_price,
+                    };
+                    faced.set_price(good.id, price);
+                }
+
+                let limit = eligibility[&agent.id];
+                let wanted = agent
+                    .preference_bundles()
+                    .iter()
+                    .filter(|bundle| !bundle.is_empty() && bundle.len() <= limit)
+                    .map(|bundle| (faced.net_utility(agent, bundle), bundle))
+                    .filter(|(utility, _)| *utility > 1e-9)
+                    .fold(None, |best: Option<(f64, &Bundle)>, (utility, bundle)| match best {
+                        Some((top, _)) if top >= utility - 1e-9 => best,
+                        _ => Some((utility, bundle)),
+                    });
+
+                let mut placed = Vec::new();
+                if let Some((_, bundle)) = wanted {
+                    for id in sorted_ids(bundle) {
+                        if winners.get(&id) == Some(&agent.id) {
+                            continue;
+                        }
+                        let amount = faced.get_price(id);
+                        if new_high.get(&id).is_none_or(|(high, _)| amount > *high) {
+                            new_high.insert(id, (amount, agent.id));
+                        }
+                        placed.push(id);
+                    }
+                }
+
+                let winning = winners.values().filter(|&&w| w == agent.id).count();
+                next_eligibility.insert(agent.id, limit.min(winning + placed.len()));
+                if !placed.is_empty() {
+                    bids.insert(agent.id, placed);
+                }
+            }
+
+            let no_bids = bids.is_empty();
+            for (id, (amount, agent)) in new_high {
+                standing.set_price(id, amount);
+                winners.insert(id, agent);
+            }
+            eligibility = next_eligibility;
+            rounds.push(SmraRound {
+                prices: standing.clone(),
+                winners: winners.clone(),
+                bids,
+                eligibility: eligibility.clone(),
+            });
+            if no_bids {
+                converged = true;
+                break;
+            }
+        }
+
+        let mut allocation = Allocation::new();
+        let mut payments = HashMap::new();
+        for agent in agents {
+            let won: Bundle = goods
+                .iter()
+                .filter(|good| winners.get(&good.id) == Some(&agent.id))
+                .cloned()
+                .collect();
+            payments.insert(agent.id, standing.bundle_price(&won));
+            allocation.assign(agent.id, won);
+        }
+
+        Ok(SmraResult {
+            rounds,
+            payments,
+            result: auction.evaluate(allocation, &standing),
+            converged,
+        })
+    }
+}
@@ -0,0 +1,65 @@
+use crate::types::{Agent, Allocation};
+
+/// Top Trading Cycles for pure exchange (housing markets).
+/// Each agent's endowment is treated as an indivisible house. Every round,
+/// each remaining agent points at the owner of its favourite remaining
+/// endowment (its own on ties), and every resulting cycle trades and leaves.
+/// With strict preferences the outcome is the unique core allocation.
+#[derive(Debug, Clone, Default)]
+pub struct TtcMechanism;
+
+impl TtcMechanism {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Compute the TTC allocation from the agents' endowments
+    pub fn compute_allocation(&self, agents: &[Agent]) -> Allocation {
+        let mut allocation = Allocation::new();
+        let mut remaining = vec![true; agents.len()];
+
+        while let Some(start) = remaining.iter().position(|&r| r) {
+            let pointers: Vec<usize> = (0..agents.len())
+                .map(|i| {
+                    if !remaining[i] {
+                        return i;
+                    }
+                    let agent = &agents[i];
+                    let mut best = i;
+                    for (j, owner) in agents.iter().enumerate() {
+                        if remaining[j] && agent.prefers(&owner.endowment, &agents[best].endowment) {
+                            best = j;
+                        }
+                    }
+                    best
+                })
+                .collect();
+
+            // Follow pointers from any remaining agent until a node repeats
+            let mut visited = vec![false; agents.len()];
+            let mut current = start;
+            while !visited[current] {
+                visited[current] = true;
+                current = pointers[current];
+            }
+
+            // `current` lies on a cycle: trade along it
+            let cycle_start = current;
+            loop {
+                let owner = &agents[pointers[current]];
+                allocation.assign_units(
+                    agents[current].id,
+                    owner.endowment.clone(),
+                    owner.endowment_units.clone(),
+                );
+                remaining[current] = false;
+                current = pointers[current];
+                if current == cycle_start {
+                    break;
+                }
+            }
+        }
+
+        allocation
+    }
+}
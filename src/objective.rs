@@ -0,0 +1,45 @@
+use crate::types::{Agent, Allocation};
+use serde::{Deserialize, Serialize};
+
+/// Social objective an allocation is judged by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Objective {
+    /// Sum of values
+    #[default]
+    Utilitarian,
+    /// Product of values, reported as their geometric mean (0 if anyone gets nothing of value)
+    NashSocialWelfare,
+    /// Smallest value
+    Egalitarian,
+}
+
+impl Objective {
+    /// Score a list of agent values
+    pub fn score(&self, values: &[f64]) -> f64 {
+        match self {
+            Objective::Utilitarian => values.iter().sum(),
+            Objective::NashSocialWelfare => {
+                if values.is_empty() || values.iter().any(|&v| v <= 0.0) {
+                    return 0.0;
+                }
+                let mean_log = values.iter().map(|v| v.ln()).sum::<f64>() / values.len() as f64;
+                mean_log.exp()
+            }
+            Objective::Egalitarian if values.is_empty() => 0.0,
+            Objective::Egalitarian => values.iter().copied().fold(f64::INFINITY, f64::min),
+        }
+    }
+
+    /// Score an allocation by the agents' values for their bundles
+    pub fn evaluate(&self, agents: &[Agent], allocation: &Allocation) -> f64 {
+        let values: Vec<f64> = agents
+            .iter()
+            .map(|agent| {
+                allocation
+                    .get_bundle(agent.id)
+                    .map_or(0.0, |bundle| agent.preference(bundle))
+            })
+            .collect();
+        self.score(&values)
+    }
+}
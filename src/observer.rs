@@ -0,0 +1,78 @@
+use crate::ids::{AgentId, GoodId};
+use crate::pricing::PriceVector;
+use crate::types::Allocation;
+
+/// Whether the mechanism should keep going after a callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Flow {
+    #[default]
+    Continue,
+    Stop,
+}
+
+/// Callbacks invoked while the BRACE mechanism runs.
+/// All methods default to doing nothing, so implement only the ones you need.
+pub trait AuctionObserver {
+    /// A trade among `agents` was accepted; `allocation` is the result
+    fn on_trade(&mut self, _agents: &[AgentId], _allocation: &Allocation) {}
+
+    /// The price adjustment finished its `iteration`-th step.
+    /// Returning `Flow::Stop` keeps the current prices.
+    fn on_price_update(&mut self, _iteration: usize, _prices: &PriceVector) -> Flow {
+        Flow::Continue
+    }
+
+    /// The improvement loop finished its `iteration`-th pass.
+    /// Returning `Flow::Stop` keeps the current allocation.
+    fn on_iteration_end(&mut self, _iteration: usize, _allocation: &Allocation) -> Flow {
+        Flow::Continue
+    }
+}
+
+/// The unit observer ignores every event
+impl AuctionObserver for () {}
+
+/// Records every price vector produced by the price adjustment
+#[derive(Debug, Clone, Default)]
+pub struct PriceTrajectory {
+    /// Prices after each adjustment step, in order
+    pub steps: Vec<PriceVector>,
+}
+
+impl PriceTrajectory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One row per step: `iteration` followed by one column per good (sorted by ID)
+    pub fn to_csv(&self) -> String {
+        let mut goods: Vec<GoodId> = self
+            .steps
+            .iter()
+            .flat_map(|prices| prices.all_prices().keys().copied())
+            .collect();
+        goods.sort_by_key(|id| id.as_str());
+        goods.dedup();
+
+        let mut csv = String::from("iteration");
+        for good in &goods {
+            csv.push_str(&format!(",{}", good.as_str().replace(',', ";")));
+        }
+        csv.push('\n');
+        for (iteration, prices) in self.steps.iter().enumerate() {
+            csv.push_str(&iteration.to_string());
+            for &good in &goods {
+                csv.push_str(&format!(",{}", prices.get_price(good)));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+impl AuctionObserver for PriceTrajectory {
+    fn on_price_update(&mut self, _iteration: usize, prices: &PriceVector) -> Flow {
+        self.steps.push(prices.clone());
+        Flow::Continue
+    }
+}
@@ -0,0 +1,122 @@
+use crate::error::AuctionError;
+use crate::ids::GoodId;
+use crate::types::{unit_count, Agent, Allocation, Bundle, Good};
+use std::collections::HashMap;
+
+/// An auction whose agents arrive one at a time.
+/// Each arrival is served immediately from its own endowment plus the goods
+/// nobody has claimed yet; goods given to earlier agents are never taken back.
+pub struct OnlineAuction {
+    goods: Vec<Good>,
+    agents: Vec<Agent>,
+    allocation: Allocation,
+    /// Unclaimed units per good
+    free: HashMap<GoodId, u32>,
+}
+
+impl OnlineAuction {
+    pub fn new(goods: Vec<Good>) -> Result<Self, AuctionError> {
+        let mut free = HashMap::new();
+        for good in &goods {
+            if free.insert(good.id, good.supply).is_some() {
+                return Err(AuctionError::DuplicateGood(good.id));
+            }
+        }
+        Ok(Self {
+            goods,
+            agents: Vec::new(),
+            allocation: Allocation::new(),
+            free,
+        })
+    }
+
+    /// Admit an agent and commit its bundle: the most preferred of its
+    /// preference bundles that fits in its endowment plus the unclaimed goods,
+    /// or its endowment if none is better (so every arrival is individually rational).
+    /// Endowed goods must still be unclaimed.
+    pub fn arrive(&mut self, agent: Agent) -> Result<Bundle, AuctionError> {
+        if self.agents.iter().any(|a| a.id == agent.id) {
+            return Err(AuctionError::DuplicateAgent(agent.id));
+        }
+
+        // Goods the newcomer can draw on: its endowment plus unclaimed units
+        let mut pool = self.free.clone();
+        for good in &agent.endowment {
+            let count = unit_count(&agent.endowment, Some(&agent.endowment_units), good.id);
+            match pool.get(&good.id) {
+                None => {
+                    return Err(AuctionError::UnknownEndowedGood {
+                        agent: agent.id,
+                        good: good.id,
+                    })
+                }
+                Some(&free) if free < count => {
+                    return Err(AuctionError::CommittedGood {
+                        agent: agent.id,
+                        good: good.id,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        let fits = |bundle: &Bundle| {
+            let units = agent.bundle_units(bundle);
+            agent.within_capacity(bundle, units)
+                && bundle.iter().all(|good| {
+                    pool.get(&good.id)
+                        .is_some_and(|&free| unit_count(bundle, units, good.id) <= free)
+                })
+        };
+        let mut best = &agent.endowment;
+        for bundle in agent.preference_bundles() {
+            if fits(bundle) && agent.prefers(bundle, best) {
+                best = bundle;
+            }
+        }
+
+        // Claim the chosen units; the rest of the endowment becomes unclaimed
+        let bundle = best.clone();
+        let units = if best == &agent.endowment {
+            agent.endowment_units.clone()
+        } else {
+            agent.bundle_units(best).cloned().unwrap_or_default()
+        };
+        for good in &bundle {
+            if let Some(free) = pool.get_mut(&good.id) {
+                *free -= unit_count(&bundle, Some(&units), good.id);
+            }
+        }
+        self.free = pool;
+        self.allocation.assign_units(agent.id, bundle.clone(), units);
+        self.agents.push(agent);
+        Ok(bundle)
+    }
+
+    /// Committed bundles of all agents so far
+    pub fn allocation(&self) -> &Allocation {
+        &self.allocation
+    }
+
+    pub fn agents(&self) -> &[Agent] {
+        &self.agents
+    }
+
+    pub fn goods(&self) -> &[Good] {
+        &self.goods
+    }
+
+    /// Unclaimed units of a good
+    pub fn available(&self, good_id: impl Into<GoodId>) -> u32 {
+        self.free.get(&good_id.into()).copied().unwrap_or(0)
+    }
+
+    /// Goods with unclaimed units, in the order they were listed
+    pub fn unallocated_goods(&self) -> Vec<GoodId> {
+        self.goods
+            .iter()
+            .filter(|good| self.available(good.id) > 0)
+            .map(|good| good.id)
+            .collect()
+    }
+}
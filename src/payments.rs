@@ -0,0 +1,151 @@
+use crate::ids::AgentId;
+use crate::pricing::{core_selecting_payments, PriceVector};
+use crate::types::{Agent, Allocation, Good};
+use crate::wdp::solve_exact;
+use std::collections::HashMap;
+
+/// How much each agent pays for its final bundle (negative = receives).
+/// The sum of the payments is the seller's revenue.
+pub trait PaymentRule: Send + Sync {
+    fn payments(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        allocation: &Allocation,
+        prices: &PriceVector,
+    ) -> HashMap<AgentId, f64>;
+}
+
+/// Trade at the final prices: each agent pays for its bundle and is paid
+/// for its endowment (units included), so a pure exchange balances out
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PostedPrices;
+
+impl PaymentRule for PostedPrices {
+    fn payments(
+        &self,
+        agents: &[Agent],
+        _goods: &[Good],
+        allocation: &Allocation,
+        prices: &PriceVector,
+    ) -> HashMap<AgentId, f64> {
+        agents
+            .iter()
+            .map(|agent| {
+                let bought = allocation.get_bundle(agent.id).map_or(0.0, |bundle| {
+                    prices.bundle_price_units(bundle, allocation.get_units(agent.id))
+                });
+                let sold = prices.bundle_price_units(&agent.endowment, Some(&agent.endowment_units));
+                (agent.id, bought - sold)
+            })
+            .collect()
+    }
+}
+
+/// Nobody pays anything
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoPayments;
+
+impl PaymentRule for NoPayments {
+    fn payments(
+        &self,
+        agents: &[Agent],
+        _goods: &[Good],
+        _allocation: &Allocation,
+        _prices: &PriceVector,
+    ) -> HashMap<AgentId, f64> {
+        agents.iter().map(|agent| (agent.id, 0.0)).collect()
+    }
+}
+
+/// Pay as bid: each agent pays its reported value of its bundle minus
+/// that of its endowment, i.e. all of its reported gain
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FirstPrice;
+
+impl PaymentRule for FirstPrice {
+    fn payments(
+        &self,
+        agents: &[Agent],
+        _goods: &[Good],
+        allocation: &Allocation,
+        _prices: &PriceVector,
+    ) -> HashMap<AgentId, f64> {
+        agents
+            .iter()
+            .map(|agent| {
+                let value = allocation.get_bundle(agent.id).map_or(0.0, |b| agent.preference(b));
+                (agent.id, value - agent.preference(&agent.endowment))
+            })
+            .collect()
+    }
+}
+
+/// VCG (Clarke pivot) payments, see `vcg_payments`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Vcg;
+
+impl PaymentRule for Vcg {
+    fn payments(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        allocation: &Allocation,
+        _prices: &PriceVector,
+    ) -> HashMap<AgentId, f64> {
+        vcg_payments(agents, goods, allocation)
+    }
+}
+
+/// Core-selecting payments, see `core_selecting_payments`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CoreSelecting;
+
+impl PaymentRule for CoreSelecting {
+    fn payments(
+        &self,
+        agents: &[Agent],
+        goods: &[Good],
+        allocation: &Allocation,
+        _prices: &PriceVector,
+    ) -> HashMap<AgentId, f64> {
+        core_selecting_payments(agents, goods, allocation)
+    }
+}
+
+/// Compute VCG (Clarke pivot) payments for an allocation.
+/// Agent i pays the optimal welfare of the others without i minus the
+/// welfare the others obtain in `allocation`. Uses the exact winner
+/// determination solver, so it is only suitable for small instances.
+pub fn vcg_payments(
+    agents: &[Agent],
+    goods: &[Good],
+    allocation: &Allocation,
+) -> HashMap<AgentId, f64> {
+    let values: Vec<f64> = agents
+        .iter()
+        .map(|agent| {
+            allocation
+                .get_bundle(agent.id)
+                .map(|bundle| agent.preference(bundle))
+                .unwrap_or(0.0)
+        })
+        .collect();
+    let total: f64 = values.iter().sum();
+
+    agents
+        .iter()
+        .enumerate()
+        .map(|(i, agent)| {
+            let others: Vec<Agent> = agents
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, a)| a.clone())
+                .collect();
+            let (_, welfare_without_i) = solve_exact(&others, goods);
+            let others_welfare = total - values[i];
+            (agent.id, welfare_without_i - others_welfare)
+        })
+        .collect()
+}
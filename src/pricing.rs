@@ -1,47 +1,188 @@
-use crate::types::{Agent, Bundle, Good};
+use crate::ids::{AgentId, GoodId};
+use crate::payments::vcg_payments;
+use crate::observer::{AuctionObserver, Flow};
+use crate::types::{unit_count, Agent, Allocation, Bundle, BundleKey, Good, Units};
+use crate::wdp::solve_exact;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Source of price versions, so no two differently priced vectors share one
+static NEXT_VERSION: AtomicU64 = AtomicU64::new(0);
 
 /// Price vector for goods
-#[derive(Debug, Clone)]
+/// Optionally carries nonlinear prices for whole bundles, which take
+/// precedence over the sum of item prices
 pub struct PriceVector {
-    prices: HashMap<String, f64>,
+    prices: HashMap<GoodId, f64>,
+    bundle_prices: HashMap<BundleKey, f64>,
+    /// Changes with every price update
+    version: u64,
+    /// What each agent pays for each bundle at this version
+    agent_prices: Mutex<HashMap<(AgentId, BundleKey), f64>>,
 }
 
 impl PriceVector {
     pub fn new() -> Self {
+        Self::from_map(HashMap::new())
+    }
+
+    pub fn from_map(prices: HashMap<GoodId, f64>) -> Self {
+        Self::from_parts(prices, HashMap::new())
+    }
+
+    fn from_parts(prices: HashMap<GoodId, f64>, bundle_prices: HashMap<BundleKey, f64>) -> Self {
         Self {
-            prices: HashMap::new(),
+            prices,
+            bundle_prices,
+            version: NEXT_VERSION.fetch_add(1, Ordering::Relaxed),
+            agent_prices: Mutex::new(HashMap::new()),
         }
     }
 
-    pub fn from_map(prices: HashMap<String, f64>) -> Self {
-        Self { prices }
+    /// Identifies these exact prices: it changes whenever a price is set or
+    /// cleared and is never shared by price vectors built separately, so it
+    /// can key caches of anything computed from the prices
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Move to a new version, dropping memoized bundle prices
+    fn touch(&mut self) {
+        self.version = NEXT_VERSION.fetch_add(1, Ordering::Relaxed);
+        self.agent_prices
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
     }
 
-    pub fn set_price(&mut self, good_id: String, price: f64) {
-        self.prices.insert(good_id, price);
+    fn memo(&self) -> MutexGuard<'_, HashMap<(AgentId, BundleKey), f64>> {
+        self.agent_prices.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
-    pub fn get_price(&self, good_id: &str) -> f64 {
-        self.prices.get(good_id).copied().unwrap_or(0.0)
+    /// Set a price for a whole bundle
+    pub fn set_bundle_price(&mut self, bundle: &Bundle, price: f64) {
+        self.bundle_prices.insert(BundleKey::from_bundle(bundle), price);
+        self.touch();
     }
 
-    /// Calculate the price of a bundle
+    /// Explicit price of a whole bundle, if one is set
+    pub fn get_bundle_price(&self, bundle: &Bundle) -> Option<f64> {
+        if self.bundle_prices.is_empty() {
+            return None;
+        }
+        self.bundle_prices.get(&BundleKey::from_bundle(bundle)).copied()
+    }
+
+    /// Remove a bundle price, reverting to the sum of item prices
+    pub fn clear_bundle_price(&mut self, bundle: &Bundle) {
+        self.bundle_prices.remove(&BundleKey::from_bundle(bundle));
+        self.touch();
+    }
+
+    /// Whether any nonlinear bundle prices are set
+    pub fn has_bundle_prices(&self) -> bool {
+        !self.bundle_prices.is_empty()
+    }
+
+    pub fn set_price(&mut self, good_id: impl Into<GoodId>, price: f64) {
+        self.prices.insert(good_id.into(), price);
+        self.touch();
+    }
+
+    pub fn get_price(&self, good_id: impl Into<GoodId>) -> f64 {
+        self.prices.get(&good_id.into()).copied().unwrap_or(0.0)
+    }
+
+    /// Calculate the price of a bundle (bundle price if set, else item prices)
     pub fn bundle_price(&self, bundle: &Bundle) -> f64 {
-        bundle.iter().map(|good| self.get_price(&good.id)).sum()
+        if let Some(price) = self.get_bundle_price(bundle) {
+            return price;
+        }
+        bundle.iter().map(|good| self.get_price(good.id)).sum()
     }
 
-    /// Calculate net utility: preference value minus price
+    /// Calculate the price of a bundle with explicit unit counts
+    /// A bundle price, if set, covers the bundle as demanded
+    pub fn bundle_price_units(&self, bundle: &Bundle, units: Option<&Units>) -> f64 {
+        if let Some(price) = self.get_bundle_price(bundle) {
+            return price;
+        }
+        bundle
+            .iter()
+            .map(|good| {
+                let count = units.and_then(|u| u.get(&good.id)).copied().unwrap_or(1);
+                self.get_price(good.id) * count as f64
+            })
+            .sum()
+    }
+
+    /// Calculate net utility: preference value minus price (the price is
+    /// memoized, see `agent_bundle_price`)
     pub fn net_utility(&self, agent: &Agent, bundle: &Bundle) -> f64 {
-        agent.preference(bundle) - self.bundle_price(bundle)
+        agent.preference(bundle) - self.agent_bundle_price(agent, bundle)
+    }
+
+    /// How far the price of an agent's allocated bundle exceeds its budget
+    pub fn budget_relaxation(
+        &self,
+        agent: &Agent,
+        bundle: &Bundle,
+        units: Option<&Units>,
+    ) -> f64 {
+        match agent.budget {
+            Some(budget) => (self.bundle_price_units(bundle, units) - budget).max(0.0),
+            None => 0.0,
+        }
+    }
+
+    /// Price an agent pays for one of its bundles (respecting unit demands).
+    /// Memoized per agent and bundle until a price changes, so repeated
+    /// demand queries at the same prices skip the summation.
+    pub fn agent_bundle_price(&self, agent: &Agent, bundle: &Bundle) -> f64 {
+        let key = (agent.id, BundleKey::from_bundle(bundle));
+        if let Some(&price) = self.memo().get(&key) {
+            return price;
+        }
+        let price = self.bundle_price_units(bundle, agent.bundle_units(bundle));
+        self.memo().insert(key, price);
+        price
     }
 
-    /// Find the demand set: bundles that maximize net utility
+    /// Find the demand set: affordable bundles that maximize net utility.
+    /// Tied bundles are listed in the agent's preference order, so results are reproducible
     pub fn demand_set(&self, agent: &Agent) -> Vec<Bundle> {
+        self.demand_set_with_budget(agent, agent.budget)
+    }
+
+    /// Find the demand set under an explicit budget (None = unlimited).
+    /// Agents with a demand oracle are asked directly; unaffordable answers are dropped.
+    /// Bundles beyond the agent's capacity are never demanded.
+    pub fn demand_set_with_budget(&self, agent: &Agent, budget: Option<f64>) -> Vec<Bundle> {
+        if let Some(oracle) = agent.demand_oracle() {
+            return oracle
+                .demand(self)
+                .into_iter()
+                .filter(|bundle| {
+                    budget.is_none_or(|budget| self.agent_bundle_price(agent, bundle) <= budget + 1e-9)
+                        && agent.within_capacity(bundle, agent.bundle_units(bundle))
+                })
+                .collect();
+        }
+
         let mut best_utility = f64::NEG_INFINITY;
         let mut demand = Vec::new();
 
         for bundle in agent.preference_bundles() {
+            if !agent.within_capacity(bundle, agent.bundle_units(bundle)) {
+                continue;
+            }
+            if let Some(budget) = budget {
+                if self.agent_bundle_price(agent, bundle) > budget + 1e-9 {
+                    continue;
+                }
+            }
             let utility = self.net_utility(agent, bundle);
             if utility > best_utility {
                 best_utility = utility;
@@ -55,9 +196,66 @@ impl PriceVector {
         demand
     }
 
-    pub fn all_prices(&self) -> &HashMap<String, f64> {
+    pub fn all_prices(&self) -> &HashMap<GoodId, f64> {
         &self.prices
     }
+
+    /// Item prices added good by good (a missing price counts as 0).
+    /// Bundle prices are dropped.
+    pub fn add(&self, other: &PriceVector) -> PriceVector {
+        self.combine(other, |a, b| a + b)
+    }
+
+    /// All prices, item and bundle, multiplied by `factor`
+    pub fn scale(&self, factor: f64) -> PriceVector {
+        PriceVector::from_parts(
+            self.prices.iter().map(|(id, p)| (*id, p * factor)).collect(),
+            self.bundle_prices
+                .iter()
+                .map(|(key, p)| (key.clone(), p * factor))
+                .collect(),
+        )
+    }
+
+    /// Item prices moved the fraction `t` of the way towards `other`
+    /// (0 = these prices, 1 = `other`). Bundle prices are dropped.
+    pub fn lerp(&self, other: &PriceVector, t: f64) -> PriceVector {
+        self.combine(other, |a, b| a + t * (b - a))
+    }
+
+    /// Sum of absolute item price differences
+    pub fn l1_distance(&self, other: &PriceVector) -> f64 {
+        self.combine(other, |a, b| (a - b).abs()).prices.values().sum()
+    }
+
+    /// Largest absolute item price difference
+    pub fn linf_distance(&self, other: &PriceVector) -> f64 {
+        self.combine(other, |a, b| (a - b).abs())
+            .prices
+            .values()
+            .copied()
+            .fold(0.0, f64::max)
+    }
+
+    /// Closest nonnegative prices: negative item and bundle prices become 0
+    pub fn project_nonnegative(&self) -> PriceVector {
+        PriceVector::from_parts(
+            self.prices.iter().map(|(id, p)| (*id, p.max(0.0))).collect(),
+            self.bundle_prices
+                .iter()
+                .map(|(key, p)| (key.clone(), p.max(0.0)))
+                .collect(),
+        )
+    }
+
+    /// Apply `op` to the item prices of every good priced in either vector
+    fn combine(&self, other: &PriceVector, op: impl Fn(f64, f64) -> f64) -> PriceVector {
+        let mut combined = PriceVector::new();
+        for id in self.prices.keys().chain(other.prices.keys()) {
+            combined.set_price(*id, op(self.get_price(*id), other.get_price(*id)));
+        }
+        combined
+    }
 }
 
 impl Default for PriceVector {
@@ -66,63 +264,811 @@ impl Default for PriceVector {
     }
 }
 
+impl Clone for PriceVector {
+    /// Same prices, same version, and the memoized bundle prices carried over
+    fn clone(&self) -> Self {
+        Self {
+            prices: self.prices.clone(),
+            bundle_prices: self.bundle_prices.clone(),
+            version: self.version,
+            agent_prices: Mutex::new(self.memo().clone()),
+        }
+    }
+}
+
+impl std::fmt::Debug for PriceVector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PriceVector")
+            .field("prices", &self.prices)
+            .field("bundle_prices", &self.bundle_prices)
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+/// Non-anonymous linear prices: each agent faces its own price per good
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PersonalizedPriceVector {
+    #[serde(serialize_with = "crate::sorted::serialize")]
+    prices: HashMap<AgentId, HashMap<GoodId, f64>>,
+}
+
+impl PersonalizedPriceVector {
+    pub fn new() -> Self {
+        Self {
+            prices: HashMap::new(),
+        }
+    }
+
+    pub fn set_price(&mut self, agent_id: impl Into<AgentId>, good_id: impl Into<GoodId>, price: f64) {
+        self.prices
+            .entry(agent_id.into())
+            .or_default()
+            .insert(good_id.into(), price);
+    }
+
+    pub fn get_price(&self, agent_id: impl Into<AgentId>, good_id: impl Into<GoodId>) -> f64 {
+        self.prices
+            .get(&agent_id.into())
+            .and_then(|prices| prices.get(&good_id.into()))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Prices faced by one agent as an ordinary price vector
+    pub fn agent_prices(&self, agent_id: impl Into<AgentId>) -> PriceVector {
+        PriceVector::from_map(self.prices.get(&agent_id.into()).cloned().unwrap_or_default())
+    }
+
+    pub fn all_prices(&self) -> &HashMap<AgentId, HashMap<GoodId, f64>> {
+        &self.prices
+    }
+}
+
+/// Compute anonymous nonlinear bundle prices for an allocation.
+/// Every declared bundle that is not allocated is priced at the highest
+/// value any agent has for it, and each allocated bundle at the highest
+/// value any other agent has for it (capped at its holder's value). When
+/// the allocation is efficient enough for these prices to exist, they
+/// support it even where no linear prices do.
+pub fn compute_bundle_prices(agents: &[Agent], allocation: &Allocation) -> PriceVector {
+    let mut prices = PriceVector::new();
+    let allocated: HashMap<BundleKey, AgentId> = agents
+        .iter()
+        .filter_map(|agent| {
+            let bundle = allocation.get_bundle(agent.id)?;
+            Some((BundleKey::from_bundle(bundle), agent.id))
+        })
+        .collect();
+
+    for agent in agents {
+        for bundle in agent.preference_bundles() {
+            let key = BundleKey::from_bundle(bundle);
+            let holder = allocated.get(&key).copied();
+            let competing = agents
+                .iter()
+                .filter(|other| Some(other.id) != holder)
+                .map(|other| other.preference(bundle))
+                .fold(0.0, f64::max);
+            let price = match holder.and_then(|id| agents.iter().find(|a| a.id == id)) {
+                Some(owner) => competing.min(owner.preference(bundle)),
+                None => competing,
+            };
+            prices.set_bundle_price(bundle, price);
+        }
+    }
+
+    for agent in agents {
+        if let Some(bundle) = allocation.get_bundle(agent.id) {
+            if prices.get_bundle_price(bundle).is_none() {
+                let competing = agents
+                    .iter()
+                    .filter(|other| other.id != agent.id)
+                    .map(|other| other.preference(bundle))
+                    .fold(0.0, f64::max);
+                prices.set_bundle_price(bundle, competing.min(agent.preference(bundle)));
+            }
+        }
+    }
+
+    prices
+}
+
+/// Prices that support an allocation
+#[derive(Debug, Clone)]
+pub enum SupportingPrices {
+    Anonymous(PriceVector),
+    Personalized(PersonalizedPriceVector),
+}
+
+/// Check whether every agent's allocated bundle is (weakly) optimal at the prices
+pub fn supports_allocation(
+    prices: &PriceVector,
+    agents: &[Agent],
+    allocation: &Allocation,
+    tolerance: f64,
+) -> bool {
+    max_ce_violation(prices, agents, allocation) <= tolerance
+}
+
+/// Per-agent competitive-equilibrium violation: how much more net utility
+/// the agent's demand set offers than its allocated bundle at `prices`.
+/// Zero for agents holding a demanded bundle or no bundle at all.
+pub fn ce_violations(
+    prices: &PriceVector,
+    agents: &[Agent],
+    allocation: &Allocation,
+) -> HashMap<AgentId, f64> {
+    agents
+        .iter()
+        .map(|agent| {
+            let violation = allocation
+                .get_bundle(agent.id)
+                .and_then(|bundle| {
+                    let allocated = agent.preference(bundle)
+                        - prices.bundle_price_units(bundle, allocation.get_units(agent.id));
+                    prices
+                        .demand_set(agent)
+                        .first()
+                        .map(|best| (prices.net_utility(agent, best) - allocated).max(0.0))
+                })
+                .unwrap_or(0.0);
+            (agent.id, violation)
+        })
+        .collect()
+}
+
+/// Largest competitive-equilibrium violation over all agents (0 = prices support the allocation)
+pub fn max_ce_violation(prices: &PriceVector, agents: &[Agent], allocation: &Allocation) -> f64 {
+    ce_violations(prices, agents, allocation)
+        .into_values()
+        .fold(0.0, f64::max)
+}
+
+/// Aggregate demand minus supply per good at some prices
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExcessDemand {
+    /// Units demanded minus units supplied (negative = excess supply)
+    pub per_good: HashMap<GoodId, f64>,
+    /// Goods priced at their reserve, which may stay unsold at a clearing price
+    #[serde(default)]
+    pub at_reserve: Vec<GoodId>,
+}
+
+impl ExcessDemand {
+    /// Excess demand for one good (0 for unknown goods)
+    pub fn get(&self, good_id: impl Into<GoodId>) -> f64 {
+        self.per_good.get(&good_id.into()).copied().unwrap_or(0.0)
+    }
+
+    /// Goods demanded beyond their supply, sorted by ID
+    pub fn over_demanded(&self) -> Vec<GoodId> {
+        self.select(|z| z > 1e-9)
+    }
+
+    /// Goods with unsold units, sorted by ID
+    pub fn under_demanded(&self) -> Vec<GoodId> {
+        self.select(|z| z < -1e-9)
+    }
+
+    /// Market-clearing residual: the largest excess demand, or excess supply
+    /// of a good priced above its reserve. Zero at a Walrasian equilibrium.
+    pub fn residual(&self) -> f64 {
+        self.per_good
+            .iter()
+            .map(|(good_id, &z)| {
+                if z < 0.0 && self.at_reserve.contains(good_id) {
+                    0.0
+                } else {
+                    z.abs()
+                }
+            })
+            .fold(0.0, f64::max)
+    }
+
+    /// Whether every market clears within `tolerance`
+    pub fn clears(&self, tolerance: f64) -> bool {
+        self.residual() <= tolerance
+    }
+
+    fn select(&self, keep: impl Fn(f64) -> bool) -> Vec<GoodId> {
+        let mut ids: Vec<GoodId> = self
+            .per_good
+            .iter()
+            .filter(|(_, &z)| keep(z))
+            .map(|(&id, _)| id)
+            .collect();
+        ids.sort_by_key(|id| id.as_str());
+        ids
+    }
+}
+
+/// Aggregate the agents' demand at `prices` and compare it with supply.
+/// Each agent demands the first bundle of its demand set, or nothing when
+/// no affordable bundle has positive net utility.
+pub fn excess_demand(agents: &[Agent], goods: &[Good], prices: &PriceVector) -> ExcessDemand {
+    let mut per_good: HashMap<GoodId, f64> =
+        goods.iter().map(|g| (g.id, -(g.supply as f64))).collect();
+
+    for agent in agents {
+        let Some(bundle) = prices.demand_set(agent).into_iter().next() else {
+            continue;
+        };
+        if prices.net_utility(agent, &bundle) <= 0.0 {
+            continue;
+        }
+        let units = agent.bundle_units(&bundle);
+        for good in &bundle {
+            *per_good.entry(good.id).or_insert(0.0) += unit_count(&bundle, units, good.id) as f64;
+        }
+    }
+
+    let mut at_reserve: Vec<GoodId> = goods
+        .iter()
+        .filter(|g| prices.get_price(g.id) <= g.reserve_price + 1e-9)
+        .map(|g| g.id)
+        .collect();
+    at_reserve.sort_by_key(|id| id.as_str());
+
+    ExcessDemand {
+        per_good,
+        at_reserve,
+    }
+}
+
+/// Compute personalized prices supporting an allocation.
+/// For each agent, goods outside its allocated bundle are priced up until no
+/// other declared bundle beats the allocated one. Bundles that are subsets of
+/// the allocated bundle cannot be priced out this way, so the result may
+/// still fail to support non-monotone preferences.
+pub fn compute_personalized_prices(
+    agents: &[Agent],
+    goods: &[Good],
+    allocation: &Allocation,
+    epsilon: f64,
+) -> PersonalizedPriceVector {
+    let mut personalized = PersonalizedPriceVector::new();
+    let max_iterations = 1000;
+
+    for agent in agents {
+        let mut prices = PriceVector::new();
+        for good in goods {
+            prices.set_price(good.id, good.reserve_price);
+        }
+        let Some(allocated) = allocation.get_bundle(agent.id) else {
+            continue;
+        };
+
+        for _ in 0..max_iterations {
+            let allocated_utility = agent.preference(allocated)
+                - prices.bundle_price_units(allocated, allocation.get_units(agent.id));
+            let best = prices
+                .demand_set(agent)
+                .into_iter()
+                .next()
+                .filter(|best| prices.net_utility(agent, best) > allocated_utility + epsilon);
+            let Some(best) = best else {
+                break;
+            };
+
+            let gap = prices.net_utility(agent, &best) - allocated_utility;
+            let outside: Vec<GoodId> = best
+                .iter()
+                .filter(|good| !allocated.contains(*good))
+                .map(|good| good.id)
+                .collect();
+            if outside.is_empty() {
+                break;
+            }
+            for good_id in outside {
+                prices.set_price(good_id, prices.get_price(good_id) + gap);
+            }
+        }
+
+        for (good_id, price) in prices.all_prices() {
+            personalized.set_price(agent.id, *good_id, *price);
+        }
+    }
+
+    personalized
+}
+
+/// Compute prices supporting an allocation, preferring linear anonymous prices,
+/// then anonymous bundle prices, and falling back to personalized prices
+pub fn compute_supporting_prices(
+    agents: &[Agent],
+    goods: &[Good],
+    allocation: &Allocation,
+    epsilon: f64,
+) -> SupportingPrices {
+    let config = PriceAdjustmentConfig::default();
+    let anonymous = compute_equilibrium_prices(agents, goods, allocation, epsilon, &config);
+    if supports_allocation(&anonymous, agents, allocation, epsilon) {
+        return SupportingPrices::Anonymous(anonymous);
+    }
+    let nonlinear = compute_bundle_prices(agents, allocation);
+    if supports_allocation(&nonlinear, agents, allocation, epsilon) {
+        SupportingPrices::Anonymous(nonlinear)
+    } else {
+        SupportingPrices::Personalized(compute_personalized_prices(
+            agents, goods, allocation, epsilon,
+        ))
+    }
+}
+
+/// Parameters of the tâtonnement price adjustment
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PriceAdjustmentConfig {
+    /// Initial price increment per iteration
+    pub step_size: f64,
+    pub max_iterations: usize,
+    /// Factor in (0, 1] applied to the step after every iteration (1 = no damping)
+    pub damping: f64,
+    /// Good whose price is normalized to 1 after every iteration
+    pub numeraire: Option<GoodId>,
+    /// How the (damped) step size turns into each iteration's step
+    pub step_rule: StepRule,
+    /// Which price computation to run
+    pub algorithm: PricingAlgorithm,
+}
+
+impl Default for PriceAdjustmentConfig {
+    fn default() -> Self {
+        Self {
+            step_size: 0.1,
+            max_iterations: 1000,
+            damping: 1.0,
+            numeraire: None,
+            step_rule: StepRule::default(),
+            algorithm: PricingAlgorithm::default(),
+        }
+    }
+}
+
+/// Backend of the equilibrium price computation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PricingAlgorithm {
+    /// Tâtonnement on excess demand, biased towards the given allocation
+    #[default]
+    Tatonnement,
+    /// Subgradient descent on the Lagrangian dual of winner determination
+    /// (supply constraints relaxed). Stops once the dual value is within
+    /// epsilon of the allocation's welfare, which certifies that the
+    /// prices support it; ignores budgets and the numeraire.
+    Subgradient,
+}
+
+/// Step size schedule of the price adjustment. The adaptive rules stop
+/// only once every allocated bundle is demanded, since their steps may
+/// shrink below epsilon long before that.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum StepRule {
+    /// `step_size` every iteration; stops once the largest change is below epsilon
+    #[default]
+    Constant,
+    /// `step_size / sqrt(t + 1)` in iteration `t`
+    Diminishing,
+    /// Armijo-style backtracking: try `step_size`, then keep multiplying by
+    /// `shrink` until the total CE gap drops (at most `max_tries` steps tried)
+    Backtracking { shrink: f64, max_tries: usize },
+    /// Polyak step: total CE gap divided by the squared norm of the update direction
+    Polyak,
+}
+
 /// Compute approximate competitive equilibrium prices
-/// This implements a price adjustment algorithm to find prices
-/// that support the BRACE allocation
+/// This implements tâtonnement: every iteration each agent demands its
+/// allocated bundle if that is still optimal (otherwise its favorite
+/// bundle), and prices move with the excess demand, up for over-demanded
+/// goods and down to the reserve for under-demanded ones
 pub fn compute_equilibrium_prices(
     agents: &[Agent],
     goods: &[Good],
     allocation: &crate::types::Allocation,
     epsilon: f64,
+    config: &PriceAdjustmentConfig,
 ) -> PriceVector {
-    let mut prices = PriceVector::new();
-    
-    // Initialize prices to zero
-    for good in goods {
-        prices.set_price(good.id.clone(), 0.0);
+    compute_equilibrium_prices_observed(agents, goods, allocation, epsilon, config, &mut ())
+}
+
+/// `compute_equilibrium_prices`, reporting each price update to `observer`
+pub fn compute_equilibrium_prices_observed(
+    agents: &[Agent],
+    goods: &[Good],
+    allocation: &crate::types::Allocation,
+    epsilon: f64,
+    config: &PriceAdjustmentConfig,
+    observer: &mut dyn AuctionObserver,
+) -> PriceVector {
+    adjust_prices(agents, goods, allocation, epsilon, config, None, observer).0
+}
+
+/// Run the price adjustment from `start` (default: the reserve prices);
+/// also reports whether it settled (or the observer stopped it) before
+/// `config.max_iterations`
+pub(crate) fn adjust_prices(
+    agents: &[Agent],
+    goods: &[Good],
+    allocation: &crate::types::Allocation,
+    epsilon: f64,
+    config: &PriceAdjustmentConfig,
+    start: Option<&PriceVector>,
+    observer: &mut dyn AuctionObserver,
+) -> (PriceVector, bool) {
+    // Initialize prices to the reserve (zero if none) unless given
+    let mut prices = match start {
+        Some(start) => start.clone(),
+        None => {
+            let mut prices = PriceVector::new();
+            for good in goods {
+                prices.set_price(good.id, good.reserve_price);
+            }
+            prices
+        }
+    };
+    if config.algorithm == PricingAlgorithm::Subgradient {
+        return subgradient_prices(agents, goods, allocation, epsilon, config, prices, observer);
+    }
+    if let Some(numeraire) = config.numeraire {
+        prices.set_price(numeraire, 1.0);
     }
 
     // Iterative price adjustment
-    let max_iterations = 1000;
-    let step_size = 0.1;
+    let mut step_size = config.step_size;
+    let mut converged = false;
 
-    for _ in 0..max_iterations {
-        let mut price_changes = HashMap::new();
-        
-        // For each agent, check if their allocation is in their demand set
+    for iteration in 0..config.max_iterations {
+        // Excess demand: units demanded at these prices minus supply
+        let mut direction: HashMap<GoodId, f64> =
+            goods.iter().map(|g| (g.id, -(g.supply as f64))).collect();
         for agent in agents {
-            if let Some(allocated_bundle) = allocation.get_bundle(&agent.id) {
-                let demand = prices.demand_set(agent);
-                
-                // Check if allocated bundle is in demand
-                let in_demand = demand.iter().any(|b| {
-                    b.len() == allocated_bundle.len() && 
-                    b.iter().all(|g| allocated_bundle.contains(g))
-                });
-                
-                // If allocated bundle is not in demand, adjust prices
-                if !in_demand {
-                    // Increase prices of goods in allocated bundle
-                    for good in allocated_bundle {
-                        *price_changes.entry(good.id.clone()).or_insert(0.0) += step_size;
+            let allocated_bundle = allocation.get_bundle(agent.id);
+            // Relax the budget just enough to afford the allocated bundle
+            let budget = agent.budget.map(|budget| {
+                allocated_bundle.map_or(budget, |bundle| {
+                    let units = allocation.get_units(agent.id);
+                    budget.max(prices.bundle_price_units(bundle, units))
+                })
+            });
+            let demand = prices.demand_set_with_budget(agent, budget);
+
+            // An agent whose allocated bundle is among its demanded ones (and
+            // not worse than nothing) keeps it
+            let kept = allocated_bundle.filter(|allocated| {
+                prices.net_utility(agent, allocated) >= -1e-9
+                    && demand.iter().any(|b| b.len() == allocated.len() && b.iter().all(|g| allocated.contains(g)))
+            });
+            let (bundle, units) = match kept {
+                Some(bundle) => (bundle, allocation.get_units(agent.id)),
+                None => match demand.first() {
+                    Some(best) if prices.net_utility(agent, best) > 0.0 => {
+                        (best, agent.bundle_units(best))
                     }
-                }
+                    _ => continue,
+                },
+            };
+            for good in bundle {
+                *direction.entry(good.id).or_insert(0.0) += unit_count(bundle, units, good.id) as f64;
             }
         }
+        // Over-demanded goods get dearer, under-demanded ones cheaper down
+        // to their reserve; balanced goods, unsold goods at the reserve and
+        // the numeraire stay put
+        direction.retain(|good_id, excess| {
+            let floored = goods
+                .iter()
+                .find(|g| g.id == *good_id)
+                .is_some_and(|g| prices.get_price(g.id) <= g.reserve_price + 1e-9);
+            excess.abs() > 1e-9 && !(*excess < 0.0 && floored) && config.numeraire != Some(*good_id)
+        });
+
+        let step = step_length(
+            config.step_rule,
+            step_size,
+            iteration,
+            &prices,
+            &direction,
+            goods,
+            |trial| ce_gap(trial, agents, allocation),
+        );
 
         // Check convergence before applying changes
-        let max_change = price_changes.values().map(|&v: &f64| v.abs()).fold(0.0, f64::max);
-        if max_change < epsilon {
+        let max_change = direction.values().map(|&v: &f64| v.abs()).fold(0.0, f64::max) * step;
+        let settled = match config.step_rule {
+            StepRule::Constant => max_change < epsilon,
+            _ => direction.is_empty(),
+        };
+        if settled {
+            converged = true;
             break;
         }
 
         // Apply price changes
-        for (good_id, change) in &price_changes {
-            let current = prices.get_price(good_id);
-            prices.set_price(good_id.clone(), current + change);
+        prices = shifted(&prices, &direction, step, goods);
+
+        // Rescale so the numeraire stays at 1
+        if let Some(numeraire) = config.numeraire {
+            let scale = prices.get_price(numeraire);
+            if scale > 0.0 {
+                let ids: Vec<GoodId> = prices.all_prices().keys().copied().collect();
+                for good_id in ids {
+                    prices.set_price(good_id, prices.get_price(good_id) / scale);
+                }
+            }
+        }
+
+        step_size *= config.damping;
+
+        if observer.on_price_update(iteration, &prices) == Flow::Stop {
+            converged = true;
+            break;
         }
     }
 
-    prices
+    (prices, converged)
 }
 
+/// Lagrangian dual prices by subgradient descent (`PricingAlgorithm::Subgradient`).
+/// Returns the prices with the lowest dual value seen.
+fn subgradient_prices(
+    agents: &[Agent],
+    goods: &[Good],
+    allocation: &Allocation,
+    epsilon: f64,
+    config: &PriceAdjustmentConfig,
+    mut prices: PriceVector,
+    observer: &mut dyn AuctionObserver,
+) -> (PriceVector, bool) {
+    // Welfare of the allocation, counting unsold units at their reserve
+    let primal: f64 = agents
+        .iter()
+        .filter_map(|agent| allocation.get_bundle(agent.id).map(|b| agent.preference(b)))
+        .sum::<f64>()
+        + goods
+            .iter()
+            .map(|g| g.reserve_price * g.supply.saturating_sub(allocation.allocated_units(g.id)) as f64)
+            .sum::<f64>();
+
+    let mut best = (f64::INFINITY, prices.clone());
+    let mut step_size = config.step_size;
+    let mut converged = false;
+
+    for iteration in 0..config.max_iterations {
+        let (dual, mut direction) = lagrangian(agents, goods, &prices);
+        if dual < best.0 {
+            best = (dual, prices.clone());
+        }
+        // Excess demand is a subgradient (with the sign flipped); goods at
+        // their reserve cannot get cheaper
+        direction.retain(|good_id, excess| {
+            let floored = goods
+                .iter()
+                .find(|g| g.id == *good_id)
+                .is_some_and(|g| prices.get_price(g.id) <= g.reserve_price + 1e-9);
+            excess.abs() > 1e-9 && !(*excess < 0.0 && floored)
+        });
+        if dual - primal <= epsilon || direction.is_empty() {
+            converged = true;
+            break;
+        }
+
+        let step = step_length(
+            config.step_rule,
+            step_size,
+            iteration,
+            &prices,
+            &direction,
+            goods,
+            |trial| lagrangian(agents, goods, trial).0 - primal,
+        );
+        prices = shifted(&prices, &direction, step, goods);
+        step_size *= config.damping;
+
+        if observer.on_price_update(iteration, &prices) == Flow::Stop {
+            converged = true;
+            break;
+        }
+    }
+
+    // The prices after the last update may not have been evaluated yet
+    if lagrangian(agents, goods, &prices).0 <= best.0 {
+        best.1 = prices;
+    }
+    (best.1, converged)
+}
+
+/// Lagrangian dual value at `prices` (revenue from the full supply plus
+/// every agent's best net utility, ignoring budgets) and the excess demand
+/// of the agents' favorite bundles
+fn lagrangian(agents: &[Agent], goods: &[Good], prices: &PriceVector) -> (f64, HashMap<GoodId, f64>) {
+    let mut value: f64 = goods
+        .iter()
+        .map(|g| prices.get_price(g.id) * g.supply as f64)
+        .sum();
+    let mut excess: HashMap<GoodId, f64> =
+        goods.iter().map(|g| (g.id, -(g.supply as f64))).collect();
+    for agent in agents {
+        let Some(best) = prices.demand_set_with_budget(agent, None).into_iter().next() else {
+            continue;
+        };
+        let utility = prices.net_utility(agent, &best);
+        if utility <= 0.0 {
+            continue;
+        }
+        value += utility;
+        let units = agent.bundle_units(&best);
+        for good in &best {
+            *excess.entry(good.id).or_insert(0.0) += unit_count(&best, units, good.id) as f64;
+        }
+    }
+    (value, excess)
+}
+
+/// Sum of all agents' CE violations
+fn ce_gap(prices: &PriceVector, agents: &[Agent], allocation: &Allocation) -> f64 {
+    ce_violations(prices, agents, allocation).values().sum()
+}
+
+/// `prices` moved `step` along `direction`, floored at the reserves
+fn shifted(
+    prices: &PriceVector,
+    direction: &HashMap<GoodId, f64>,
+    step: f64,
+    goods: &[Good],
+) -> PriceVector {
+    let mut moved = prices.clone();
+    for good in goods {
+        if let Some(change) = direction.get(&good.id) {
+            let price = prices.get_price(good.id) + step * change;
+            moved.set_price(good.id, price.max(good.reserve_price));
+        }
+    }
+    moved
+}
+
+/// Step taken along `direction` in `iteration` under `rule`; `gap` is the
+/// quantity the adaptive rules try to drive to zero
+fn step_length(
+    rule: StepRule,
+    step_size: f64,
+    iteration: usize,
+    prices: &PriceVector,
+    direction: &HashMap<GoodId, f64>,
+    goods: &[Good],
+    gap: impl Fn(&PriceVector) -> f64,
+) -> f64 {
+    match rule {
+        StepRule::Constant => step_size,
+        StepRule::Diminishing => step_size / ((iteration + 1) as f64).sqrt(),
+        StepRule::Backtracking { shrink, max_tries } => {
+            backtracking_step(prices, direction, goods, gap, (step_size, shrink, max_tries))
+        }
+        StepRule::Polyak => {
+            let norm: f64 = direction.values().map(|d| d * d).sum();
+            if norm > 0.0 {
+                gap(prices) / norm
+            } else {
+                0.0
+            }
+        }
+    }
+}
+
+/// First of `start`, `start * shrink`, ... (at most `max_tries`) that
+/// lowers `gap`, or the last one tried
+fn backtracking_step(
+    prices: &PriceVector,
+    direction: &HashMap<GoodId, f64>,
+    goods: &[Good],
+    gap: impl Fn(&PriceVector) -> f64,
+    (start, shrink, max_tries): (f64, f64, usize),
+) -> f64 {
+    let current = gap(prices);
+    let mut step = start;
+    for attempt in 1..=max_tries {
+        if gap(&shifted(prices, direction, step, goods)) < current || attempt == max_tries {
+            break;
+        }
+        step *= shrink;
+    }
+    step
+}
+
+/// Most agents `core_selecting_payments` enumerates coalitions for
+/// (2^12 winner determination problems)
+pub const MAX_CORE_AGENTS: usize = 12;
+
+/// Compute core-selecting payments for an allocation.
+/// Starts from VCG payments and repeatedly raises the payments of the
+/// winners outside the most violated blocking coalition (equal increments,
+/// capped at each winner's value) until no coalition blocks. This is the
+/// constraint-generation heuristic behind nearest-VCG core prices.
+/// Enumerates all coalitions, solving winner determination for each, so
+/// above `MAX_CORE_AGENTS` agents it stops at the (capped) VCG payments,
+/// which need not be in the core.
+pub fn core_selecting_payments(
+    agents: &[Agent],
+    goods: &[Good],
+    allocation: &Allocation,
+) -> HashMap<AgentId, f64> {
+    let values: Vec<f64> = agents
+        .iter()
+        .map(|agent| {
+            allocation
+                .get_bundle(agent.id)
+                .map(|bundle| agent.preference(bundle))
+                .unwrap_or(0.0)
+        })
+        .collect();
+
+    let vcg = vcg_payments(agents, goods, allocation);
+    let mut payments: Vec<f64> = agents
+        .iter()
+        .zip(&values)
+        .map(|(agent, value)| vcg[&agent.id].clamp(0.0, value.max(0.0)))
+        .collect();
+    let n = agents.len();
+    if n > MAX_CORE_AGENTS {
+        return agents.iter().map(|agent| agent.id).zip(payments).collect();
+    }
+
+    // Optimal welfare of every coalition, indexed by membership bitmask
+    let coalition_welfare: Vec<f64> = (0..1usize << n)
+        .map(|mask| {
+            let members: Vec<Agent> = (0..n)
+                .filter(|i| mask & (1 << i) != 0)
+                .map(|i| agents[i].clone())
+                .collect();
+            solve_exact(&members, goods).1
+        })
+        .collect();
+
+    let max_iterations = 1000;
+    for _ in 0..max_iterations {
+        // Find the most violated core constraint:
+        // payments of winners outside K must cover W(K) - value of K's winners
+        let mut worst: Option<(usize, f64)> = None;
+        for (mask, welfare) in coalition_welfare.iter().enumerate() {
+            let inside_value: f64 = (0..n)
+                .filter(|i| mask & (1 << i) != 0)
+                .map(|i| values[i])
+                .sum();
+            let outside_payments: f64 = (0..n)
+                .filter(|i| mask & (1 << i) == 0)
+                .map(|i| payments[i])
+                .sum();
+            let violation = welfare - inside_value - outside_payments;
+            if violation > 1e-9 && worst.is_none_or(|(_, v)| violation > v) {
+                worst = Some((mask, violation));
+            }
+        }
+
+        let Some((mask, mut violation)) = worst else {
+            break;
+        };
+
+        // Water-fill the violation over outside winners with remaining slack
+        let mut raisable: Vec<usize> = (0..n)
+            .filter(|i| mask & (1 << i) == 0 && values[*i] - payments[*i] > 1e-12)
+            .collect();
+        while violation > 1e-12 && !raisable.is_empty() {
+            let share = violation / raisable.len() as f64;
+            for &i in &raisable {
+                let increase = share.min(values[i] - payments[i]);
+                payments[i] += increase;
+                violation -= increase;
+            }
+            raisable.retain(|&i| values[i] - payments[i] > 1e-12);
+        }
+        if violation > 1e-9 {
+            // Remaining violation cannot be covered without breaking IR
+            break;
+        }
+    }
+
+    agents
+        .iter()
+        .zip(payments)
+        .map(|(agent, payment)| (agent.id, payment))
+        .collect()
+}
@@ -0,0 +1,135 @@
+use crate::ids::{AgentId, GoodId};
+use crate::pricing::PriceVector;
+use crate::types::{Agent, Allocation, Bundle, Good};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+/// A participant answering demand queries asynchronously, e.g. over the
+/// network. Implementations can write `async fn demand`.
+pub trait Bidder: Send + Sync {
+    fn id(&self) -> AgentId;
+
+    /// A most profitable bundle at `prices` (empty to drop out)
+    fn demand(&self, prices: &PriceVector) -> impl Future<Output = Bundle> + Send;
+}
+
+/// A local agent bids truthfully: its first profitable demanded bundle
+impl Bidder for Agent {
+    fn id(&self) -> AgentId {
+        self.id
+    }
+
+    async fn demand(&self, prices: &PriceVector) -> Bundle {
+        prices
+            .demand_set(self)
+            .into_iter()
+            .find(|bundle| prices.net_utility(self, bundle) > 1e-9)
+            .unwrap_or_default()
+    }
+}
+
+/// Ascending clock auction over async bidders. Every round all bidders are
+/// queried concurrently at the current prices and the price of every
+/// over-demanded good rises by `increment`, until demand fits supply. Each
+/// bidder then wins its last demanded bundle at the final clock prices.
+/// Goods start out with the seller.
+#[derive(Debug, Clone)]
+pub struct AsyncClockAuction {
+    /// Price increase per clock round for over-demanded goods
+    pub increment: f64,
+    /// Safety cap on the number of clock rounds
+    pub max_rounds: usize,
+}
+
+/// Outcome of an `AsyncClockAuction`
+#[derive(Debug, Clone)]
+pub struct AsyncClockResult {
+    pub allocation: Allocation,
+    /// What each winner pays: its bundle at the final clock prices
+    pub payments: HashMap<AgentId, f64>,
+    pub prices: PriceVector,
+    /// Bundle each bidder demanded in each round
+    pub rounds: Vec<HashMap<AgentId, Bundle>>,
+    /// False if the round cap was hit while goods were still over-demanded
+    pub converged: bool,
+}
+
+impl AsyncClockAuction {
+    pub fn new(increment: f64) -> Self {
+        Self {
+            increment,
+            max_rounds: 10_000,
+        }
+    }
+
+    pub fn with_max_rounds(mut self, max_rounds: usize) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    /// Run the clock, querying each round's bidders on the tokio runtime
+    pub async fn run<B: Bidder + 'static>(
+        &self,
+        bidders: &[Arc<B>],
+        goods: &[Good],
+    ) -> AsyncClockResult {
+        let mut prices = PriceVector::new();
+        for good in goods {
+            prices.set_price(good.id, good.reserve_price);
+        }
+
+        let mut rounds: Vec<HashMap<AgentId, Bundle>> = Vec::new();
+        let mut converged = false;
+        while rounds.len() < self.max_rounds {
+            let mut queries = JoinSet::new();
+            for bidder in bidders {
+                let bidder = Arc::clone(bidder);
+                let prices = prices.clone();
+                queries.spawn(async move { (bidder.id(), bidder.demand(&prices).await) });
+            }
+            let mut bids = HashMap::new();
+            while let Some(answer) = queries.join_next().await {
+                let (id, bundle) = answer.expect("demand query panicked");
+                bids.insert(id, bundle);
+            }
+
+            let mut demand: HashMap<GoodId, u32> = HashMap::new();
+            for bundle in bids.values() {
+                for good in bundle {
+                    *demand.entry(good.id).or_insert(0) += 1;
+                }
+            }
+            rounds.push(bids);
+
+            let over: Vec<&Good> = goods
+                .iter()
+                .filter(|good| demand.get(&good.id).copied().unwrap_or(0) > good.supply)
+                .collect();
+            if over.is_empty() {
+                converged = true;
+                break;
+            }
+            for good in over {
+                prices.set_price(good.id, prices.get_price(good.id) + self.increment);
+            }
+        }
+
+        let mut allocation = Allocation::new();
+        let mut payments = HashMap::new();
+        for (id, bundle) in rounds.last().cloned().unwrap_or_default() {
+            if !bundle.is_empty() {
+                payments.insert(id, prices.bundle_price(&bundle));
+            }
+            allocation.assign(id, bundle);
+        }
+        AsyncClockResult {
+            allocation,
+            payments,
+            prices,
+            rounds,
+            converged,
+        }
+    }
+}
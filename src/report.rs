@@ -0,0 +1,212 @@
+use crate::ids::{AgentId, GoodId};
+use crate::pricing::PriceVector;
+use crate::types::{sorted_ids, Agent, AuctionResult, Bundle};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Why an agent received its bundle, evaluated at the final prices
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentExplanation {
+    pub agent: AgentId,
+    /// Allocated goods (sorted by ID)
+    pub bundle: Vec<GoodId>,
+    pub value: f64,
+    /// Value of the agent's endowment
+    pub endowment_value: f64,
+    /// Price of the allocated bundle (personalized if the result has them)
+    pub price: f64,
+    pub net_utility: f64,
+    /// Best affordable alternative bundle and its net utility
+    pub best_alternative: Option<(Vec<GoodId>, f64)>,
+    /// Bundles with higher net utility that exceed the agent's budget
+    pub unaffordable_better: usize,
+    /// Whether the allocated bundle is (weakly) optimal at the prices
+    pub in_demand: bool,
+    pub reason: String,
+}
+
+/// Per-agent explanation of an auction outcome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub agents: Vec<AgentExplanation>,
+}
+
+/// Explain the outcome for each agent
+pub fn explain(result: &AuctionResult, agents: &[Agent]) -> Report {
+    let tolerance = 1e-9;
+    let anonymous = PriceVector::from_map(result.prices.clone());
+
+    let agents = agents
+        .iter()
+        .map(|agent| {
+            let prices = match &result.personalized_prices {
+                Some(personalized) => personalized.agent_prices(agent.id),
+                None => anonymous.clone(),
+            };
+            let empty = Bundle::new();
+            let bundle = result.allocation.get_bundle(agent.id).unwrap_or(&empty);
+            let value = agent.preference(bundle);
+            let price = prices.bundle_price_units(bundle, result.allocation.get_units(agent.id));
+            let net_utility = value - price;
+
+            let mut best_alternative: Option<(&Bundle, f64)> = None;
+            let mut unaffordable_better = 0;
+            for candidate in agent.preference_bundles() {
+                if candidate == bundle {
+                    continue;
+                }
+                let utility = prices.net_utility(agent, candidate);
+                let affordable = agent
+                    .budget
+                    .is_none_or(|budget| prices.agent_bundle_price(agent, candidate) <= budget + tolerance);
+                if !affordable {
+                    if utility > net_utility + tolerance {
+                        unaffordable_better += 1;
+                    }
+                    continue;
+                }
+                if best_alternative.is_none_or(|(_, best)| utility > best) {
+                    best_alternative = Some((candidate, utility));
+                }
+            }
+
+            let in_demand = best_alternative.is_none_or(|(_, u)| u <= net_utility + tolerance);
+            let mut reason = match best_alternative {
+                None => "no other affordable bundle was requested".to_string(),
+                Some((alternative, utility)) if in_demand => format!(
+                    "best affordable alternative {} yields {:.2} <= {:.2}",
+                    format_bundle(alternative),
+                    utility,
+                    net_utility
+                ),
+                Some((alternative, utility)) => format!(
+                    "{} would yield {:.2} > {:.2}; the prices do not support this allocation",
+                    format_bundle(alternative),
+                    utility,
+                    net_utility
+                ),
+            };
+            if unaffordable_better > 0 {
+                reason.push_str(&format!(
+                    "; {} better bundle(s) exceed the budget",
+                    unaffordable_better
+                ));
+            }
+
+            AgentExplanation {
+                agent: agent.id,
+                bundle: sorted_ids(bundle),
+                value,
+                endowment_value: agent.preference(&agent.endowment),
+                price,
+                net_utility,
+                best_alternative: best_alternative
+                    .map(|(alternative, utility)| (sorted_ids(alternative), utility)),
+                unaffordable_better,
+                in_demand,
+                reason,
+            }
+        })
+        .collect();
+
+    Report { agents }
+}
+
+/// Write the allocation, price and per-agent outcome tables as CSV
+pub(crate) fn write_csv(
+    result: &AuctionResult,
+    agents: &[Agent],
+    mut allocation: impl Write,
+    mut prices: impl Write,
+    mut outcomes: impl Write,
+) -> io::Result<()> {
+    writeln!(allocation, "agent,good,units")?;
+    for agent in agents {
+        let Some(bundle) = result.allocation.get_bundle(agent.id) else {
+            continue;
+        };
+        for good in sorted_ids(bundle) {
+            writeln!(
+                allocation,
+                "{},{},{}",
+                csv_field(agent.id.as_str()),
+                csv_field(good.as_str()),
+                result.allocation.unit_count(agent.id, good)
+            )?;
+        }
+    }
+
+    writeln!(prices, "good,price")?;
+    let mut goods: Vec<(&GoodId, &f64)> = result.prices.iter().collect();
+    goods.sort_by_key(|(id, _)| id.as_str());
+    for (good, price) in goods {
+        writeln!(prices, "{},{}", csv_field(good.as_str()), price)?;
+    }
+
+    writeln!(
+        outcomes,
+        "agent,value,endowment_value,price,net_utility,in_demand,ce_violation,budget_relaxation,vcg_payment,core_payment,payment"
+    )?;
+    let payment = |payments: &Option<HashMap<AgentId, f64>>, agent: AgentId| {
+        payments
+            .as_ref()
+            .and_then(|p| p.get(&agent))
+            .map(|p| p.to_string())
+            .unwrap_or_default()
+    };
+    for entry in explain(result, agents).agents {
+        let lookup = |map: &HashMap<AgentId, f64>| map.get(&entry.agent).copied().unwrap_or(0.0);
+        writeln!(
+            outcomes,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(entry.agent.as_str()),
+            entry.value,
+            entry.endowment_value,
+            entry.price,
+            entry.net_utility,
+            entry.in_demand,
+            lookup(&result.ce_violations),
+            lookup(&result.budget_relaxation),
+            payment(&result.vcg_payments, entry.agent),
+            payment(&result.core_payments, entry.agent),
+            lookup(&result.payments),
+        )?;
+    }
+    Ok(())
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or newline
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn format_bundle(bundle: &Bundle) -> String {
+    let names: Vec<&str> = sorted_ids(bundle).iter().map(|id| id.as_str()).collect();
+    format!("{{{}}}", names.join(", "))
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.agents {
+            let names: Vec<&str> = entry.bundle.iter().map(|id| id.as_str()).collect();
+            writeln!(
+                f,
+                "{}: got {{{}}} (value {:.2}, endowment {:.2}, price {:.2}, net {:.2})",
+                entry.agent.as_str(),
+                names.join(", "),
+                entry.value,
+                entry.endowment_value,
+                entry.price,
+                entry.net_utility
+            )?;
+            writeln!(f, "  {}", entry.reason)?;
+        }
+        Ok(())
+    }
+}
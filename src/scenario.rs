@@ -0,0 +1,214 @@
+use crate::auction::{CombinatorialAuction, MechanismKind};
+use crate::brace::{Improvement, TieBreaker};
+use crate::constraints::{CategoryRule, Constraints};
+use crate::error::AuctionError;
+use crate::ids::{AgentId, GoodId};
+use crate::io::{parse_bid, ParseError};
+use crate::objective::Objective;
+use crate::types::{Agent, AuctionInstance, Category, Good};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A good in a scenario file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoodSpec {
+    pub id: GoodId,
+    /// Display name (defaults to the ID)
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default = "default_supply")]
+    pub supply: u32,
+    #[serde(default)]
+    pub reserve_price: f64,
+    #[serde(default)]
+    pub category: Option<Category>,
+}
+
+fn default_supply() -> u32 {
+    1
+}
+
+/// A valued bundle, given by good IDs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreferenceSpec {
+    pub goods: Vec<GoodId>,
+    pub value: f64,
+}
+
+/// An agent in a scenario file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentSpec {
+    pub id: AgentId,
+    #[serde(default)]
+    pub endowment: Vec<GoodId>,
+    #[serde(default)]
+    pub budget: Option<f64>,
+    /// Most units the agent can receive
+    #[serde(default)]
+    pub max_goods: Option<u32>,
+    /// Value undeclared bundles at their best declared subset
+    #[serde(default)]
+    pub free_disposal: bool,
+    /// Money held besides goods
+    #[serde(default)]
+    pub money: f64,
+    #[serde(default)]
+    pub preferences: Vec<PreferenceSpec>,
+    /// Bid expression valuing bundles without a listed preference,
+    /// e.g. `"(A & B):10 XOR C:5"` (see `io::parse_bid`)
+    #[serde(default)]
+    pub bid: Option<String>,
+}
+
+/// Mechanism settings in a scenario file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MechanismSpec {
+    /// Mechanism to run (default: BRACE)
+    pub kind: MechanismKind,
+    pub objective: Objective,
+    pub tie_breaker: TieBreaker,
+    /// Strict or weak improvement for swaps and cycles
+    pub improvement: Improvement,
+    /// Also compute VCG payments
+    pub vcg: bool,
+    /// Also compute core-selecting payments
+    pub core: bool,
+    /// Pure exchange without money
+    pub housing_market: bool,
+}
+
+impl MechanismSpec {
+    /// Apply these settings to an auction
+    pub fn configure(&self, mut auction: CombinatorialAuction) -> CombinatorialAuction {
+        auction = auction
+            .with_mechanism_kind(self.kind)
+            .with_objective(self.objective)
+            .with_tie_breaker(self.tie_breaker)
+            .with_improvement(self.improvement);
+        if self.vcg {
+            auction = auction.with_vcg_payments();
+        }
+        if self.core {
+            auction = auction.with_core_payments();
+        }
+        if self.housing_market {
+            auction = auction.with_housing_market();
+        }
+        auction
+    }
+}
+
+/// Declarative auction description: goods and agents referenced by ID,
+/// plus the mechanism settings. Read from TOML or YAML.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub epsilon: f64,
+    pub goods: Vec<GoodSpec>,
+    pub agents: Vec<AgentSpec>,
+    /// Groups of goods no agent may hold two of
+    #[serde(default)]
+    pub conflicts: Vec<Vec<GoodId>>,
+    /// Per-category allocation rules
+    #[serde(default)]
+    pub categories: Vec<CategoryRule>,
+    #[serde(default)]
+    pub mechanism: MechanismSpec,
+}
+
+impl Scenario {
+    /// Parse a TOML scenario
+    #[cfg(feature = "toml")]
+    pub fn from_toml(input: &str) -> Result<Self, ParseError> {
+        Ok(toml::from_str(input)?)
+    }
+
+    /// Parse a YAML scenario
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(input: &str) -> Result<Self, ParseError> {
+        Ok(serde_yaml::from_str(input)?)
+    }
+
+    /// Read a scenario, choosing the format by file extension
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let path = path.as_ref();
+        match extension(path).as_str() {
+            #[cfg(feature = "toml")]
+            "toml" => Self::from_toml(&std::fs::read_to_string(path)?),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Self::from_yaml(&std::fs::read_to_string(path)?),
+            other => Err(ParseError::UnsupportedFormat(other.to_string())),
+        }
+    }
+
+    /// Resolve the good IDs and build the instance
+    pub fn to_instance(&self) -> Result<AuctionInstance, AuctionError> {
+        let goods: Vec<Good> = self
+            .goods
+            .iter()
+            .map(|spec| {
+                let name = spec.name.clone().unwrap_or_else(|| spec.id.as_str().to_string());
+                let good = Good::new(spec.id, name)
+                    .with_supply(spec.supply)
+                    .with_reserve_price(spec.reserve_price);
+                match &spec.category {
+                    Some(category) => good.with_category(category.clone()),
+                    None => good,
+                }
+            })
+            .collect();
+
+        let agents = self
+            .agents
+            .iter()
+            .map(|spec| {
+                let mut builder = Agent::builder(spec.id).endow(spec.endowment.iter().copied());
+                if let Some(budget) = spec.budget {
+                    builder = builder.budget(budget);
+                }
+                if let Some(max_goods) = spec.max_goods {
+                    builder = builder.max_goods(max_goods);
+                }
+                if spec.free_disposal {
+                    builder = builder.free_disposal();
+                }
+                builder = builder.money(spec.money);
+                for preference in &spec.preferences {
+                    builder = builder.prefer(preference.goods.iter().copied(), preference.value);
+                }
+                let mut agent = builder.build(&goods)?;
+                if let Some(bid) = &spec.bid {
+                    agent.set_valuation(parse_bid(bid, &goods)?);
+                }
+                Ok(agent)
+            })
+            .collect::<Result<Vec<Agent>, AuctionError>>()?;
+
+        let constraints = Constraints {
+            conflicts: self.conflicts.clone(),
+            categories: self.categories.clone(),
+        };
+        Ok(AuctionInstance::new(agents, goods, self.epsilon).with_constraints(constraints))
+    }
+
+    /// Build the auction with the scenario's mechanism settings, rejecting
+    /// settings the chosen mechanism kind cannot honour
+    pub fn into_auction(self) -> Result<CombinatorialAuction, AuctionError> {
+        let auction = CombinatorialAuction::from_instance(self.to_instance()?)?;
+        let auction = self.mechanism.configure(auction);
+        auction.check_mechanism_kind()?;
+        Ok(auction)
+    }
+}
+
+/// Whether `path` names a scenario file (TOML or YAML) rather than a JSON instance
+pub fn is_scenario_path(path: &Path) -> bool {
+    matches!(extension(path).as_str(), "toml" | "yaml" | "yml")
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
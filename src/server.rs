@@ -0,0 +1,204 @@
+use crate::auction::CombinatorialAuction;
+use crate::error::AuctionError;
+use crate::types::{Agent, AuctionInstance, AuctionResult, Good};
+use axum::extract::{DefaultBodyLimit, Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+/// Largest request body accepted, in bytes
+pub const MAX_BODY_BYTES: usize = 1 << 20;
+/// Most goods one auction may have
+pub const MAX_GOODS: usize = 1000;
+/// Most agents one auction accepts
+pub const MAX_AGENTS: usize = 1000;
+
+/// Computes the result of a submitted auction
+pub type Solver = fn(AuctionInstance) -> Result<AuctionResult, AuctionError>;
+
+/// Body of `POST /auctions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAuction {
+    pub goods: Vec<Good>,
+    pub epsilon: f64,
+}
+
+/// Response of `POST /auctions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Created {
+    pub id: u64,
+}
+
+/// Errors returned to HTTP clients as `{"error": "..."}`
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("no auction with ID {0}")]
+    NotFound(u64),
+    #[error("auction {0} has not been solved yet")]
+    NotSolved(u64),
+    #[error("an auction has at most {MAX_GOODS} goods")]
+    TooManyGoods,
+    #[error("auction {0} already has {MAX_AGENTS} agents")]
+    TooManyAgents(u64),
+    #[error("solver failed: {0}")]
+    Solver(String),
+    #[error(transparent)]
+    Auction(#[from] AuctionError),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ApiError::NotFound(_) | ApiError::NotSolved(_) => StatusCode::NOT_FOUND,
+            ApiError::TooManyGoods | ApiError::TooManyAgents(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::Solver(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::Auction(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        let body = serde_json::json!({ "error": self.to_string() });
+        (status, Json(body)).into_response()
+    }
+}
+
+/// An auction assembled over HTTP, with its latest result
+struct Session {
+    instance: AuctionInstance,
+    result: Option<AuctionResult>,
+}
+
+#[derive(Default)]
+struct Store {
+    next_id: u64,
+    sessions: HashMap<u64, Session>,
+}
+
+#[derive(Clone)]
+struct Shared {
+    store: Arc<Mutex<Store>>,
+    solver: Solver,
+}
+
+/// The HTTP API:
+/// - `POST /auctions` creates an auction from goods and epsilon
+/// - `GET /auctions/{id}` returns its instance
+/// - `POST /auctions/{id}/agents` submits an agent with its bids
+/// - `POST /auctions/{id}/solve` runs BRACE and returns the result
+/// - `GET /auctions/{id}/result` fetches the latest result
+///
+/// Bodies over `MAX_BODY_BYTES` are rejected, as are auctions past
+/// `MAX_GOODS` goods or `MAX_AGENTS` agents.
+pub fn router() -> Router {
+    router_with_solver(|instance| CombinatorialAuction::from_instance(instance)?.run())
+}
+
+/// The HTTP API with `solver` computing results in place of BRACE
+pub fn router_with_solver(solver: Solver) -> Router {
+    Router::new()
+        .route("/auctions", post(create))
+        .route("/auctions/{id}", get(instance))
+        .route("/auctions/{id}/agents", post(submit_agent))
+        .route("/auctions/{id}/solve", post(solve))
+        .route("/auctions/{id}/result", get(result))
+        .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
+        .with_state(Shared {
+            store: Arc::default(),
+            solver,
+        })
+}
+
+/// Serve the API on `addr` until the process exits
+pub async fn serve(addr: SocketAddr) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router()).await
+}
+
+async fn create(
+    State(shared): State<Shared>,
+    Json(request): Json<CreateAuction>,
+) -> Result<(StatusCode, Json<Created>), ApiError> {
+    if request.goods.len() > MAX_GOODS {
+        return Err(ApiError::TooManyGoods);
+    }
+    // Reject duplicate goods up front
+    CombinatorialAuction::new(Vec::new(), request.goods.clone(), request.epsilon)?;
+
+    let mut store = shared.store.lock().unwrap();
+    let id = store.next_id;
+    store.next_id += 1;
+    store.sessions.insert(
+        id,
+        Session {
+            instance: AuctionInstance::new(Vec::new(), request.goods, request.epsilon),
+            result: None,
+        },
+    );
+    Ok((StatusCode::CREATED, Json(Created { id })))
+}
+
+async fn instance(
+    State(shared): State<Shared>,
+    Path(id): Path<u64>,
+) -> Result<Json<AuctionInstance>, ApiError> {
+    let store = shared.store.lock().unwrap();
+    let session = store.sessions.get(&id).ok_or(ApiError::NotFound(id))?;
+    Ok(Json(session.instance.clone()))
+}
+
+async fn submit_agent(
+    State(shared): State<Shared>,
+    Path(id): Path<u64>,
+    Json(agent): Json<Agent>,
+) -> Result<StatusCode, ApiError> {
+    let mut store = shared.store.lock().unwrap();
+    let session = store.sessions.get_mut(&id).ok_or(ApiError::NotFound(id))?;
+    if session.instance.agents.len() >= MAX_AGENTS {
+        return Err(ApiError::TooManyAgents(id));
+    }
+    if session.instance.agents.iter().any(|a| a.id == agent.id) {
+        return Err(AuctionError::DuplicateAgent(agent.id).into());
+    }
+    // Reject unknown goods and non-finite values
+    CombinatorialAuction::new(
+        vec![agent.clone()],
+        session.instance.goods.clone(),
+        session.instance.epsilon,
+    )?;
+
+    session.instance.agents.push(agent);
+    session.result = None;
+    Ok(StatusCode::CREATED)
+}
+
+async fn solve(
+    State(shared): State<Shared>,
+    Path(id): Path<u64>,
+) -> Result<Json<AuctionResult>, ApiError> {
+    let instance = {
+        let store = shared.store.lock().unwrap();
+        store.sessions.get(&id).ok_or(ApiError::NotFound(id))?.instance.clone()
+    };
+
+    let solver = shared.solver;
+    let result = tokio::task::spawn_blocking(move || solver(instance))
+        .await
+        .map_err(|error| ApiError::Solver(error.to_string()))??;
+
+    let mut store = shared.store.lock().unwrap();
+    let session = store.sessions.get_mut(&id).ok_or(ApiError::NotFound(id))?;
+    session.result = Some(result.clone());
+    Ok(Json(result))
+}
+
+async fn result(
+    State(shared): State<Shared>,
+    Path(id): Path<u64>,
+) -> Result<Json<AuctionResult>, ApiError> {
+    let store = shared.store.lock().unwrap();
+    let session = store.sessions.get(&id).ok_or(ApiError::NotFound(id))?;
+    session.result.clone().map(Json).ok_or(ApiError::NotSolved(id))
+}
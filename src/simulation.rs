@@ -0,0 +1,108 @@
+use crate::auction::CombinatorialAuction;
+use crate::brace::EpsilonPolicy;
+use crate::error::AuctionError;
+use crate::ids::GoodId;
+use crate::types::{Agent, Allocation, Good};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Outcome of one round of a repeated auction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundStats {
+    pub round: usize,
+    pub allocation: Allocation,
+    pub prices: HashMap<GoodId, f64>,
+    pub total_welfare: f64,
+    /// Agents whose bundle differs from the endowment they entered the round with
+    pub agents_traded: usize,
+    pub is_feasible: bool,
+    pub is_individually_rational: bool,
+}
+
+/// Welfare and price dynamics of a repeated auction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub rounds: Vec<RoundStats>,
+    /// First round in which no agent traded (later rounds would repeat it)
+    pub stable_from: Option<usize>,
+    /// Agents endowed with their final bundles
+    pub final_agents: Vec<Agent>,
+}
+
+impl SimulationReport {
+    /// Total welfare per round
+    pub fn welfare(&self) -> Vec<f64> {
+        self.rounds.iter().map(|round| round.total_welfare).collect()
+    }
+
+    /// Price of one good per round
+    pub fn price_path(&self, good_id: impl Into<GoodId>) -> Vec<f64> {
+        let good_id = good_id.into();
+        self.rounds
+            .iter()
+            .map(|round| round.prices.get(&good_id).copied().unwrap_or(0.0))
+            .collect()
+    }
+}
+
+/// Runs the same agents through repeated BRACE auctions, each round's
+/// allocation becoming the next round's endowment
+pub struct Simulation {
+    agents: Vec<Agent>,
+    goods: Vec<Good>,
+    epsilon: EpsilonPolicy,
+}
+
+impl Simulation {
+    pub fn new(agents: Vec<Agent>, goods: Vec<Good>, epsilon: impl Into<EpsilonPolicy>) -> Self {
+        Self {
+            agents,
+            goods,
+            epsilon: epsilon.into(),
+        }
+    }
+
+    /// Run up to `rounds` rounds, stopping early once a round has no trades
+    pub fn run(&self, rounds: usize) -> Result<SimulationReport, AuctionError> {
+        let mut agents = self.agents.clone();
+        let mut stats = Vec::with_capacity(rounds);
+        let mut stable_from = None;
+
+        for round in 0..rounds {
+            let auction =
+                CombinatorialAuction::new(agents.clone(), self.goods.clone(), self.epsilon.clone())?;
+            let result = auction.run()?;
+
+            let mut agents_traded = 0;
+            for agent in &mut agents {
+                let bundle = result.allocation.get_bundle(agent.id).cloned().unwrap_or_default();
+                let units = result.allocation.get_units(agent.id).cloned().unwrap_or_default();
+                if bundle != agent.endowment || units != agent.endowment_units {
+                    agents_traded += 1;
+                }
+                agent.endowment = bundle;
+                agent.endowment_units = units;
+            }
+
+            stats.push(RoundStats {
+                round,
+                allocation: result.allocation,
+                prices: result.prices,
+                total_welfare: result.total_welfare,
+                agents_traded,
+                is_feasible: result.is_feasible,
+                is_individually_rational: result.is_individually_rational,
+            });
+            if agents_traded == 0 {
+                stable_from = Some(round);
+                break;
+            }
+        }
+
+        Ok(SimulationReport {
+            rounds: stats,
+            stable_from,
+            final_agents: agents,
+        })
+    }
+}
@@ -0,0 +1,82 @@
+use crate::types::{Bundle, Good};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Serialize `value` with every map ordered by key name and every bundle by
+/// good ID, so the same value always serializes to the same bytes. For use
+/// with `#[serde(serialize_with = "crate::sorted::serialize")]`.
+pub(crate) fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Canonical,
+    S: Serializer,
+{
+    value.serialize_sorted(serializer)
+}
+
+/// Values with a canonical serialization order
+pub(crate) trait Canonical {
+    fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+/// A borrowed value serialized in canonical order
+struct Sorted<'a, T: ?Sized>(&'a T);
+
+impl<T: Canonical + ?Sized> Serialize for Sorted<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_sorted(serializer)
+    }
+}
+
+impl<K: Display + Serialize, V: Canonical> Canonical for HashMap<K, V> {
+    fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut entries: Vec<(String, &K, &V)> =
+            self.iter().map(|(key, value)| (key.to_string(), key, value)).collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        serializer.collect_map(entries.into_iter().map(|(_, key, value)| (key, Sorted(value))))
+    }
+}
+
+impl Canonical for Bundle {
+    fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut goods: Vec<&Good> = self.iter().collect();
+        goods.sort_by_key(|good| good.id.as_str());
+        serializer.collect_seq(goods)
+    }
+}
+
+impl<T: Canonical> Canonical for Option<T> {
+    fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Some(value) => serializer.serialize_some(&Sorted(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<T: Canonical> Canonical for Vec<T> {
+    fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Sorted))
+    }
+}
+
+impl<A: Canonical, B: Canonical> Canonical for (A, B) {
+    fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&Sorted(&self.0))?;
+        tuple.serialize_element(&Sorted(&self.1))?;
+        tuple.end()
+    }
+}
+
+macro_rules! as_is {
+    ($($ty:ty),*) => {
+        $(impl Canonical for $ty {
+            fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.serialize(serializer)
+            }
+        })*
+    };
+}
+
+as_is!(f64, u32);
@@ -0,0 +1,184 @@
+use crate::brace::BRACEMechanism;
+use crate::ids::{AgentId, GoodId};
+use crate::types::{sorted_ids, Agent, Allocation, Bundle, Good};
+use serde::{Deserialize, Serialize};
+
+/// How to search for false-name splits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SybilConfig {
+    /// Number of identities an agent splits into
+    pub identities: usize,
+    /// Cap on the number of splits tried per agent
+    pub max_splits: usize,
+}
+
+impl Default for SybilConfig {
+    fn default() -> Self {
+        Self {
+            identities: 2,
+            max_splits: 256,
+        }
+    }
+}
+
+/// One false identity: its share of the endowment and declared bundles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SybilIdentity {
+    pub id: AgentId,
+    pub endowment: Vec<GoodId>,
+    pub bundles: Vec<Vec<GoodId>>,
+}
+
+/// Best split found for one agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SybilReport {
+    pub agent: AgentId,
+    /// True value of the bundle received under a single identity
+    pub honest_utility: f64,
+    /// True value of the union of bundles received by the identities
+    pub best_split_utility: f64,
+    /// The most profitable split, if any split helps
+    pub best_split: Option<Vec<SybilIdentity>>,
+    pub gain: f64,
+}
+
+/// Split an agent into identities. `labels` gives the identity of each
+/// endowed good (sorted by ID) followed by each declared bundle.
+pub fn split_agent(agent: &Agent, identities: usize, labels: &[usize]) -> Vec<Agent> {
+    let endowment = sorted_ids(&agent.endowment);
+    let bundles = agent.preference_bundles();
+    assert_eq!(labels.len(), endowment.len() + bundles.len());
+
+    let mut split: Vec<Agent> = (0..identities)
+        .map(|k| {
+            let mut identity = Agent::new(format!("{}#{}", agent.id, k + 1), Bundle::new());
+            identity.budget = agent.budget;
+            identity.free_disposal = agent.free_disposal;
+            // The money is not duplicated: the first identity holds it
+            identity.money = if k == 0 { agent.money } else { 0.0 };
+            identity
+        })
+        .collect();
+
+    for (good_id, &label) in endowment.iter().zip(labels) {
+        let good = agent.endowment.iter().find(|g| g.id == *good_id).unwrap();
+        split[label].endowment.insert(good.clone());
+        if let Some(&units) = agent.endowment_units.get(good_id) {
+            split[label].endowment_units.insert(*good_id, units);
+        }
+    }
+    for (bundle, &label) in bundles.iter().zip(&labels[endowment.len()..]) {
+        let value = agent.preference(bundle);
+        match agent.bundle_units(bundle) {
+            Some(units) => split[label].add_multi_unit_preference(bundle.clone(), units.clone(), value),
+            None => split[label].add_preference(bundle.clone(), value),
+        }
+    }
+    split
+}
+
+/// Run the mechanism with `agents[index]` replaced by `identities` and
+/// return the agent's true value for everything its identities receive
+pub fn split_utility(
+    mechanism: &BRACEMechanism,
+    agents: &[Agent],
+    goods: &[Good],
+    index: usize,
+    identities: &[Agent],
+) -> f64 {
+    let mut reported: Vec<Agent> = agents
+        .iter()
+        .enumerate()
+        .filter(|(j, _)| *j != index)
+        .map(|(_, agent)| agent.clone())
+        .collect();
+    reported.extend(identities.iter().cloned());
+
+    let (allocation, _) = mechanism.compute_allocation(&reported, goods);
+    let combined: Bundle = identities
+        .iter()
+        .filter_map(|identity| allocation.get_bundle(identity.id))
+        .flat_map(|bundle| bundle.iter().cloned())
+        .collect();
+    agents[index].preference(&combined)
+}
+
+/// Search splits of `agents[index]` for the one that most improves its outcome.
+/// Splits are enumerated exhaustively up to `config.max_splits`.
+pub fn best_split(
+    mechanism: &BRACEMechanism,
+    agents: &[Agent],
+    goods: &[Good],
+    index: usize,
+    config: &SybilConfig,
+) -> SybilReport {
+    let agent = &agents[index];
+    let (honest, _) = mechanism.compute_allocation(agents, goods);
+    let honest_utility = utility(agent, &honest);
+
+    let mut report = SybilReport {
+        agent: agent.id,
+        honest_utility,
+        best_split_utility: honest_utility,
+        best_split: None,
+        gain: 0.0,
+    };
+    if config.identities < 2 {
+        return report;
+    }
+
+    let items = agent.endowment.len() + agent.preference_bundles().len();
+    let mut labels = vec![0; items];
+    let mut tried = 0;
+    // The first item always goes to the first identity (identities are interchangeable)
+    while tried < config.max_splits && next_labels(&mut labels, config.identities) {
+        tried += 1;
+        let split = split_agent(agent, config.identities, &labels);
+        let value = split_utility(mechanism, agents, goods, index, &split);
+        if value > report.best_split_utility + 1e-9 {
+            report.best_split_utility = value;
+            report.gain = value - honest_utility;
+            report.best_split = Some(split.iter().map(describe).collect());
+        }
+    }
+    report
+}
+
+/// Search every agent for a profitable split
+pub fn audit_false_names(
+    mechanism: &BRACEMechanism,
+    agents: &[Agent],
+    goods: &[Good],
+    config: &SybilConfig,
+) -> Vec<SybilReport> {
+    (0..agents.len())
+        .map(|index| best_split(mechanism, agents, goods, index, config))
+        .collect()
+}
+
+/// Advance to the next labelling with item 0 fixed to identity 0; false when exhausted
+fn next_labels(labels: &mut [usize], identities: usize) -> bool {
+    for label in labels.iter_mut().skip(1).rev() {
+        *label += 1;
+        if *label < identities {
+            return true;
+        }
+        *label = 0;
+    }
+    false
+}
+
+fn utility(agent: &Agent, allocation: &Allocation) -> f64 {
+    allocation
+        .get_bundle(agent.id)
+        .map(|bundle| agent.preference(bundle))
+        .unwrap_or(0.0)
+}
+
+fn describe(identity: &Agent) -> SybilIdentity {
+    SybilIdentity {
+        id: identity.id,
+        endowment: sorted_ids(&identity.endowment),
+        bundles: identity.preference_bundles().iter().map(sorted_ids).collect(),
+    }
+}
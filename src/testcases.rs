@@ -0,0 +1,100 @@
+use crate::types::{Agent, AgentBuilder, AuctionInstance, Good};
+
+/// Names accepted by `by_name`
+pub const NAMES: &[&str] = &["no-linear-prices", "greedy-trap", "housing-cycle", "sybil-split"];
+
+/// A curated instance by name (see `NAMES`)
+pub fn by_name(name: &str) -> Option<AuctionInstance> {
+    match name {
+        "no-linear-prices" => Some(no_linear_prices()),
+        "greedy-trap" => Some(greedy_trap()),
+        "housing-cycle" => Some(housing_cycle()),
+        "sybil-split" => Some(sybil_split()),
+        _ => None,
+    }
+}
+
+/// Every curated instance with its name
+pub fn all() -> Vec<(&'static str, AuctionInstance)> {
+    NAMES
+        .iter()
+        .map(|name| (*name, by_name(name).expect("listed testcase")))
+        .collect()
+}
+
+/// Agent1 values the pair {A, B} at 3; Agent2 wants either good alone at 2.
+/// Giving Agent1 the pair is optimal, but keeping Agent2 from demanding a
+/// good needs both prices at 2 or more, which Agent1 will not pay: no
+/// linear (per-good) prices support any efficient allocation.
+pub fn no_linear_prices() -> AuctionInstance {
+    let goods = goods(&["A", "B"]);
+    let agents = vec![
+        agent(Agent::builder("Agent1").prefer(["A", "B"], 3.0), &goods),
+        agent(Agent::builder("Agent2").prefer(["A"], 2.0).prefer(["B"], 2.0), &goods),
+    ];
+    AuctionInstance::new(agents, goods, 0.01)
+}
+
+/// Agent1 values {A, B} at 10 while Agent2 and Agent3 value A and B alone
+/// at 6 each. Ranking bids by value per good (10 / sqrt 2 > 6) awards the
+/// pair and reaches 10 instead of the optimal 12.
+pub fn greedy_trap() -> AuctionInstance {
+    let goods = goods(&["A", "B"]);
+    let agents = vec![
+        agent(Agent::builder("Agent1").prefer(["A", "B"], 10.0), &goods),
+        agent(Agent::builder("Agent2").prefer(["A"], 6.0), &goods),
+        agent(Agent::builder("Agent3").prefer(["B"], 6.0), &goods),
+    ];
+    AuctionInstance::new(agents, goods, 0.01)
+}
+
+/// Each agent owns one house and wants the next agent's. No two agents gain
+/// by swapping, but the three-way trade makes everyone better off.
+pub fn housing_cycle() -> AuctionInstance {
+    let goods = goods(&["A", "B", "C"]);
+    let agents = [("Agent1", "A", "B"), ("Agent2", "B", "C"), ("Agent3", "C", "A")]
+        .iter()
+        .map(|(id, own, wanted)| {
+            let builder = Agent::builder(*id)
+                .endow([*own])
+                .prefer([*wanted], 10.0)
+                .prefer([*own], 1.0);
+            agent(builder, &goods)
+        })
+        .collect();
+    AuctionInstance::new(agents, goods, 0.01)
+}
+
+/// Agent1 owns {A, C} and most wants {A, B}; Agent2 owns B and wants
+/// {A, C}. Under its own name Agent1 ends up with B alone (5), but bidding
+/// for B through a second identity with no endowment gets it {A, B} (10).
+pub fn sybil_split() -> AuctionInstance {
+    let goods = goods(&["A", "B", "C"]);
+    let agents = vec![
+        agent(
+            Agent::builder("Agent1")
+                .endow(["A", "C"])
+                .prefer(["A", "C"], 4.0)
+                .prefer(["A", "B"], 10.0)
+                .prefer(["B"], 5.0),
+            &goods,
+        ),
+        agent(
+            Agent::builder("Agent2")
+                .endow(["B"])
+                .prefer(["B"], 2.0)
+                .prefer(["A", "C"], 9.0)
+                .prefer(["C"], 5.0),
+            &goods,
+        ),
+    ];
+    AuctionInstance::new(agents, goods, 0.01)
+}
+
+fn goods(ids: &[&str]) -> Vec<Good> {
+    ids.iter().map(|id| Good::new(*id, format!("Good {}", id))).collect()
+}
+
+fn agent(builder: AgentBuilder, goods: &[Good]) -> Agent {
+    builder.build(goods).expect("testcase agents only use listed goods")
+}
@@ -1,97 +1,1077 @@
-use serde::{Deserialize, Serialize};
+use crate::constraints::Constraints;
+use crate::error::{AuctionError, NonConvergence, Violation};
+use crate::ids::{AgentId, GoodId};
+use crate::pricing::PersonalizedPriceVector;
+use crate::objective::Objective;
+use crate::report::{self, Report};
+use crate::scenario::{is_scenario_path, Scenario};
+use crate::io::read_instance;
+use crate::valuation::{DemandOracle, ExplicitValuation, KAdditiveValuation};
+use serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::Arc;
 
 /// Represents a good/item in the auction
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Goods are identified by their ID for equality and hashing
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Good {
-    pub id: String,
+    pub id: GoodId,
     pub name: String,
+    /// Number of identical units available
+    #[serde(default = "default_supply")]
+    pub supply: u32,
+    /// Seller's minimum price per unit
+    #[serde(default)]
+    pub reserve_price: f64,
+    /// Category the good belongs to, for `CategoryRule`s
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+}
+
+impl PartialEq for Good {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Good {}
+
+impl Hash for Good {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+fn default_supply() -> u32 {
+    1
+}
+
+fn default_converged() -> bool {
+    true
+}
+
+impl Good {
+    /// Create a single-unit good
+    pub fn new(id: impl Into<GoodId>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            supply: 1,
+            reserve_price: 0.0,
+            category: None,
+        }
+    }
+
+    /// Set the seller's reserve price
+    pub fn with_reserve_price(mut self, reserve_price: f64) -> Self {
+        self.reserve_price = reserve_price;
+        self
+    }
+
+    /// Set the number of identical units
+    pub fn with_supply(mut self, supply: u32) -> Self {
+        self.supply = supply;
+        self
+    }
+
+    /// Place the good in a category
+    pub fn with_category(mut self, category: impl Into<Category>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+}
+
+/// Name of a group of goods (e.g. a spectrum band or a course section)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Category(pub String);
+
+impl Category {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Category {
+    fn from(name: &str) -> Self {
+        Self(name.to_string())
+    }
+}
+
+impl From<String> for Category {
+    fn from(name: String) -> Self {
+        Self(name)
+    }
 }
 
 /// A bundle is a set of goods
 pub type Bundle = HashSet<Good>;
 
-/// A hashable key for bundles (sorted good IDs)
+/// Units per good ID inside a bundle; goods not listed count as one unit
+pub type Units = HashMap<GoodId, u32>;
+
+/// Number of units of a good within a bundle
+pub fn unit_count(bundle: &Bundle, units: Option<&Units>, good_id: GoodId) -> u32 {
+    if !bundle.iter().any(|g| g.id == good_id) {
+        return 0;
+    }
+    units.and_then(|u| u.get(&good_id)).copied().unwrap_or(1)
+}
+
+/// Good IDs of a bundle, sorted by name
+pub(crate) fn sorted_ids(bundle: &Bundle) -> Vec<GoodId> {
+    let mut ids: Vec<GoodId> = bundle.iter().map(|good| good.id).collect();
+    ids.sort_by_key(|id| id.as_str());
+    ids
+}
+
+/// Bundles up to this size get a key without heap allocation
+const INLINE_KEY_GOODS: usize = 8;
+
+/// A hashable key for bundles: the sorted interned good indices, stored
+/// inline for small bundles so building a key on every lookup does not allocate
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct BundleKey {
-    good_ids: Vec<String>,
+pub(crate) enum BundleKey {
+    /// Unused slots are zero
+    Inline {
+        len: u8,
+        indices: [u32; INLINE_KEY_GOODS],
+    },
+    Heap(Box<[u32]>),
 }
 
 impl BundleKey {
-    fn from_bundle(bundle: &Bundle) -> Self {
-        let mut good_ids: Vec<String> = bundle.iter().map(|g| g.id.clone()).collect();
-        good_ids.sort();
-        Self { good_ids }
+    pub(crate) fn from_bundle(bundle: &Bundle) -> Self {
+        if bundle.len() <= INLINE_KEY_GOODS {
+            let mut indices = [0; INLINE_KEY_GOODS];
+            for (slot, good) in indices.iter_mut().zip(bundle) {
+                *slot = good.id.index();
+            }
+            indices[..bundle.len()].sort_unstable();
+            return BundleKey::Inline {
+                len: bundle.len() as u8,
+                indices,
+            };
+        }
+        let mut indices: Vec<u32> = bundle.iter().map(|g| g.id.index()).collect();
+        indices.sort_unstable();
+        BundleKey::Heap(indices.into_boxed_slice())
     }
 }
 
 /// Represents an agent (bidder) in the auction
 #[derive(Debug, Clone)]
 pub struct Agent {
-    pub id: String,
+    pub id: AgentId,
     /// Initial endowment of goods
     pub endowment: Bundle,
-    /// Preference ranking: higher value = more preferred
-    /// Maps bundles to preference values
-    preferences: HashMap<BundleKey, f64>,
-    /// Store bundles for iteration
+    /// Units of each endowed good (one if not listed)
+    pub endowment_units: Units,
+    /// Budget available for buying bundles (None = unlimited)
+    pub budget: Option<f64>,
+    /// Most units the agent can receive in total (None = unlimited)
+    pub max_goods: Option<u32>,
+    /// An undeclared bundle is worth as much as its best declared subset
+    /// (free disposal) instead of 0.0
+    pub free_disposal: bool,
+    /// Money held besides goods; utility is quasi-linear in it, see `utility`
+    pub money: f64,
+    /// Explicit bundle values (higher = more preferred); take precedence over `valuation`
+    explicit: ExplicitValuation,
+    /// Candidate bundles: explicit ones, then the valuation's own
     bundles: Vec<Bundle>,
+    /// Units demanded per preference bundle (one of each good if absent)
+    units: HashMap<BundleKey, Units>,
+    /// Pluggable valuation for bundles without an explicit value
+    valuation: Option<Arc<dyn Valuation>>,
+    /// Strict ordinal ranking (0 = best); when present, comparisons use ranks
+    ranks: HashMap<BundleKey, usize>,
+    /// Computes demand in place of scanning `bundles` (not serialized)
+    oracle: Option<Arc<dyn DemandOracle>>,
 }
 
 impl Agent {
-    pub fn new(id: String, endowment: Bundle) -> Self {
+    /// Start building an agent from good IDs
+    pub fn builder(id: impl Into<AgentId>) -> AgentBuilder {
+        AgentBuilder {
+            id: id.into(),
+            endowment: Vec::new(),
+            budget: None,
+            max_goods: None,
+            free_disposal: false,
+            money: 0.0,
+            preferences: Vec::new(),
+        }
+    }
+
+    pub fn new(id: impl Into<AgentId>, endowment: Bundle) -> Self {
         Self {
-            id,
+            id: id.into(),
             endowment,
-            preferences: HashMap::new(),
+            endowment_units: Units::new(),
+            budget: None,
+            max_goods: None,
+            free_disposal: false,
+            money: 0.0,
+            explicit: ExplicitValuation::new(),
             bundles: Vec::new(),
+            units: HashMap::new(),
+            valuation: None,
+            oracle: None,
+            ranks: HashMap::new(),
         }
     }
 
+    /// Create an agent whose preferences come from a valuation
+    pub fn with_valuation<V: Valuation + 'static>(
+        id: impl Into<AgentId>,
+        endowment: Bundle,
+        valuation: V,
+    ) -> Self {
+        let mut agent = Self::new(id, endowment);
+        agent.set_valuation(valuation);
+        agent
+    }
+
     /// Add a preference for a bundle
     pub fn add_preference(&mut self, bundle: Bundle, value: f64) {
-        let key = BundleKey::from_bundle(&bundle);
-        self.preferences.insert(key, value);
+        self.explicit.insert(bundle.clone(), value);
         self.bundles.push(bundle);
     }
 
-    /// Get preference value for a bundle, defaulting to 0.0
-    pub fn preference(&self, bundle: &Bundle) -> f64 {
+    /// Explicit preferences (bundle, value), in the order they were added
+    pub fn preferences(&self) -> impl Iterator<Item = (&Bundle, f64)> {
+        self.explicit.iter()
+    }
+
+    /// Set the value of a bundle, adding it if it has no explicit preference yet
+    pub fn set_preference(&mut self, bundle: Bundle, value: f64) {
+        if self.explicit.contains(&bundle) {
+            self.explicit.insert(bundle, value);
+        } else {
+            self.add_preference(bundle, value);
+        }
+    }
+
+    /// Remove an explicit preference (with its unit demand and rank), returning its value.
+    /// A bundle the valuation also lists stays among the preference bundles.
+    pub fn remove_preference(&mut self, bundle: &Bundle) -> Option<f64> {
+        let value = self.explicit.remove(bundle)?;
         let key = BundleKey::from_bundle(bundle);
-        self.preferences.get(&key).copied().unwrap_or(0.0)
+        self.units.remove(&key);
+        self.ranks.remove(&key);
+        let listed = self
+            .valuation
+            .as_ref()
+            .is_some_and(|valuation| valuation.bundles().contains(bundle));
+        if !listed {
+            self.bundles.retain(|b| b != bundle);
+        }
+        Some(value)
+    }
+
+    /// Remove every explicit preference and ranking, keeping the valuation
+    pub fn clear_preferences(&mut self) {
+        self.explicit.clear();
+        self.units.clear();
+        self.ranks.clear();
+        self.bundles = self
+            .valuation
+            .as_ref()
+            .map(|valuation| valuation.bundles())
+            .unwrap_or_default();
+    }
+
+    /// Add a preference for specific quantities of the goods in a bundle
+    pub fn add_multi_unit_preference(&mut self, bundle: Bundle, units: Units, value: f64) {
+        let key = BundleKey::from_bundle(&bundle);
+        self.units.insert(key, units);
+        self.add_preference(bundle, value);
+    }
+
+    /// Units demanded with a preference bundle, if not one of each good
+    pub fn bundle_units(&self, bundle: &Bundle) -> Option<&Units> {
+        if self.units.is_empty() {
+            return None;
+        }
+        self.units.get(&BundleKey::from_bundle(bundle))
+    }
+
+    /// Whether a bundle (with the given units) is within the agent's capacity
+    pub fn within_capacity(&self, bundle: &Bundle, units: Option<&Units>) -> bool {
+        self.max_goods.is_none_or(|cap| {
+            bundle.iter().map(|good| unit_count(bundle, units, good.id)).sum::<u32>() <= cap
+        })
+    }
+
+    /// Answer demand queries with an oracle instead of scanning preference bundles
+    pub fn set_demand_oracle<O: DemandOracle + 'static>(&mut self, oracle: O) {
+        self.oracle = Some(Arc::new(oracle));
+    }
+
+    pub fn demand_oracle(&self) -> Option<&dyn DemandOracle> {
+        self.oracle.as_deref()
+    }
+
+    /// Use a valuation to value bundles without an explicit preference.
+    /// The valuation's own bundles become part of the agent's preference bundles.
+    pub fn set_valuation<V: Valuation + 'static>(&mut self, valuation: V) {
+        for bundle in valuation.bundles() {
+            if !self.explicit.contains(&bundle) && !self.bundles.iter().any(|b| b == &bundle) {
+                self.bundles.push(bundle);
+            }
+        }
+        self.valuation = Some(Arc::new(valuation));
+    }
+
+    /// Whether every bundle is worth at most some preference bundle it
+    /// contains, so that winner determination over the preference bundles
+    /// bounds the welfare of any allocation. Free disposal keeps this;
+    /// OR-like valuations, whose unions are worth more, do not.
+    pub(crate) fn bounded_by_preference_bundles(&self) -> bool {
+        match &self.valuation {
+            None => true,
+            // Explicit values could undercut the valuation's own bundles
+            Some(valuation) => valuation.bounded_by_bundles() && self.explicit.is_empty(),
+        }
+    }
+
+    /// Use an XOR bid to value bundles without an explicit preference
+    pub fn set_xor_bid(&mut self, bid: XorBid) {
+        self.set_valuation(bid);
+    }
+
+    /// Get preference value for a bundle.
+    /// Explicit preferences take precedence, then the valuation, then 0.0.
+    /// Under free disposal an undeclared bundle is also worth at least its
+    /// most valuable explicitly declared subset.
+    pub fn preference(&self, bundle: &Bundle) -> f64 {
+        if let Some(value) = self.explicit.get(bundle) {
+            return value;
+        }
+        let value = self
+            .valuation
+            .as_ref()
+            .map(|valuation| valuation.value(bundle))
+            .unwrap_or(0.0);
+        if !self.free_disposal {
+            return value;
+        }
+        self.explicit
+            .iter()
+            .filter(|(declared, _)| declared.is_subset(bundle))
+            .fold(value, |best, (_, declared)| best.max(declared))
+    }
+
+    /// Define a strict ranking of bundles, best first, replacing any earlier ranking
+    /// and explicit preferences (see `clear_preferences`).
+    /// Comparisons then use ranks, with unranked bundles below every ranked one.
+    /// Ranked bundles also get a surrogate value (count minus rank) so that
+    /// prices and welfare stay defined.
+    pub fn add_ranking(&mut self, ranking: Vec<Bundle>) {
+        self.clear_preferences();
+        let count = ranking.len();
+        for (rank, bundle) in ranking.into_iter().enumerate() {
+            self.ranks.insert(BundleKey::from_bundle(&bundle), rank);
+            self.add_preference(bundle, (count - rank) as f64);
+        }
+    }
+
+    /// Rank of a bundle in the ordinal ranking (0 = best)
+    pub fn rank(&self, bundle: &Bundle) -> Option<usize> {
+        if self.ranks.is_empty() {
+            return None;
+        }
+        self.ranks.get(&BundleKey::from_bundle(bundle)).copied()
+    }
+
+    /// Whether preferences were given as an ordinal ranking
+    pub fn has_ranking(&self) -> bool {
+        !self.ranks.is_empty()
     }
 
     /// Check if agent prefers bundle1 over bundle2
     pub fn prefers(&self, bundle1: &Bundle, bundle2: &Bundle) -> bool {
+        if self.has_ranking() {
+            return match (self.rank(bundle1), self.rank(bundle2)) {
+                (Some(rank1), Some(rank2)) => rank1 < rank2,
+                (Some(_), None) => true,
+                _ => false,
+            };
+        }
         self.preference(bundle1) > self.preference(bundle2)
     }
 
+    /// Check if agent likes bundle1 at least as much as bundle2
+    pub fn prefers_or_indifferent(&self, bundle1: &Bundle, bundle2: &Bundle) -> bool {
+        !self.prefers(bundle2, bundle1)
+    }
+
+    /// Quasi-linear utility of holding `bundle` after paying `payment`:
+    /// its value plus the money left over (negative payments are received)
+    pub fn utility(&self, bundle: &Bundle, payment: f64) -> f64 {
+        self.preference(bundle) + self.money - payment
+    }
+
     /// Get all bundles the agent has preferences for
     pub fn preference_bundles(&self) -> &[Bundle] {
         &self.bundles
     }
+
+    /// Ranked bundles, best first
+    fn ranking(&self) -> Vec<Bundle> {
+        let mut ranked: Vec<(usize, &Bundle)> = self
+            .bundles
+            .iter()
+            .filter_map(|bundle| Some((self.rank(bundle)?, bundle)))
+            .collect();
+        ranked.sort_by_key(|(rank, _)| *rank);
+        ranked.dedup_by_key(|(rank, _)| *rank);
+        ranked.into_iter().map(|(_, bundle)| bundle.clone()).collect()
+    }
+
+    /// Explicit (non-ranked) preferences in insertion order
+    fn explicit_preferences(&self) -> Vec<PreferenceRepr> {
+        let mut seen = HashSet::new();
+        self.bundles
+            .iter()
+            .filter_map(|bundle| {
+                let key = BundleKey::from_bundle(bundle);
+                if self.ranks.contains_key(&key) {
+                    return None;
+                }
+                let value = self.explicit.get_key(&key)?;
+                let units = self.units.get(&key).cloned().unwrap_or_default();
+                seen.insert(key).then(|| PreferenceRepr {
+                    bundle: bundle.clone(),
+                    value,
+                    units,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Fluent builder for agents, resolving good IDs against a goods list
+#[derive(Debug, Clone)]
+pub struct AgentBuilder {
+    id: AgentId,
+    endowment: Vec<GoodId>,
+    budget: Option<f64>,
+    max_goods: Option<u32>,
+    free_disposal: bool,
+    money: f64,
+    preferences: Vec<(Vec<GoodId>, f64)>,
+}
+
+impl AgentBuilder {
+    /// Add goods to the endowment
+    pub fn endow<G: Into<GoodId>>(mut self, goods: impl IntoIterator<Item = G>) -> Self {
+        self.endowment.extend(goods.into_iter().map(Into::into));
+        self
+    }
+
+    /// Value a bundle of goods
+    pub fn prefer<G: Into<GoodId>>(mut self, goods: impl IntoIterator<Item = G>, value: f64) -> Self {
+        self.preferences
+            .push((goods.into_iter().map(Into::into).collect(), value));
+        self
+    }
+
+    pub fn budget(mut self, budget: f64) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Cap the number of units the agent can receive
+    pub fn max_goods(mut self, max_goods: u32) -> Self {
+        self.max_goods = Some(max_goods);
+        self
+    }
+
+    /// Value undeclared bundles at their best declared subset
+    pub fn free_disposal(mut self) -> Self {
+        self.free_disposal = true;
+        self
+    }
+
+    /// Money the agent holds besides its goods
+    pub fn money(mut self, money: f64) -> Self {
+        self.money = money;
+        self
+    }
+
+    /// Resolve good IDs against `goods` and build the agent
+    pub fn build(self, goods: &[Good]) -> Result<Agent, AuctionError> {
+        let known: HashMap<GoodId, &Good> = goods.iter().map(|g| (g.id, g)).collect();
+
+        let mut endowment = Bundle::new();
+        for good_id in &self.endowment {
+            let good = known.get(good_id).ok_or(AuctionError::UnknownEndowedGood {
+                agent: self.id,
+                good: *good_id,
+            })?;
+            endowment.insert((*good).clone());
+        }
+
+        let mut agent = Agent::new(self.id, endowment);
+        agent.budget = self.budget;
+        agent.max_goods = self.max_goods;
+        agent.free_disposal = self.free_disposal;
+        if !self.money.is_finite() {
+            return Err(AuctionError::NonFiniteValue {
+                agent: self.id,
+                value: self.money,
+            });
+        }
+        agent.money = self.money;
+        for (good_ids, value) in self.preferences {
+            if !value.is_finite() {
+                return Err(AuctionError::NonFiniteValue {
+                    agent: self.id,
+                    value,
+                });
+            }
+            let mut bundle = Bundle::new();
+            for good_id in good_ids {
+                let good = known.get(&good_id).ok_or(AuctionError::UnknownPreferenceGood {
+                    agent: self.id,
+                    good: good_id,
+                })?;
+                bundle.insert((*good).clone());
+            }
+            agent.add_preference(bundle, value);
+        }
+        Ok(agent)
+    }
+}
+
+/// Serialized form of an explicit preference
+#[derive(Serialize, Deserialize)]
+struct PreferenceRepr {
+    #[serde(serialize_with = "crate::sorted::serialize")]
+    bundle: Bundle,
+    value: f64,
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "crate::sorted::serialize"
+    )]
+    units: Units,
 }
 
-/// An allocation maps agents to their assigned bundles
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
+}
+
+/// Serialized form of an agent
+#[derive(Serialize, Deserialize)]
+struct AgentRepr {
+    id: AgentId,
+    #[serde(serialize_with = "crate::sorted::serialize")]
+    endowment: Bundle,
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "crate::sorted::serialize"
+    )]
+    endowment_units: Units,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    budget: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_goods: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    free_disposal: bool,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    money: f64,
+    #[serde(default)]
+    preferences: Vec<PreferenceRepr>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "crate::sorted::serialize"
+    )]
+    ranking: Vec<Bundle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bid: Option<BidLanguage>,
+}
+
+impl Serialize for Agent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bid = match &self.valuation {
+            Some(valuation) => Some(valuation.to_bid_language().ok_or_else(|| {
+                ser::Error::custom(format!(
+                    "valuation of agent {} has no bidding-language representation",
+                    self.id.as_str()
+                ))
+            })?),
+            None => None,
+        };
+        AgentRepr {
+            id: self.id,
+            endowment: self.endowment.clone(),
+            endowment_units: self.endowment_units.clone(),
+            budget: self.budget,
+            max_goods: self.max_goods,
+            free_disposal: self.free_disposal,
+            money: self.money,
+            preferences: self.explicit_preferences(),
+            ranking: self.ranking(),
+            bid,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Agent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = AgentRepr::deserialize(deserializer)?;
+        let mut agent = Agent::new(repr.id, repr.endowment);
+        agent.endowment_units = repr.endowment_units;
+        agent.budget = repr.budget;
+        agent.max_goods = repr.max_goods;
+        agent.free_disposal = repr.free_disposal;
+        agent.money = repr.money;
+        for pref in repr.preferences {
+            if pref.units.is_empty() {
+                agent.add_preference(pref.bundle, pref.value);
+            } else {
+                agent.add_multi_unit_preference(pref.bundle, pref.units, pref.value);
+            }
+        }
+        if !repr.ranking.is_empty() {
+            agent.add_ranking(repr.ranking);
+        }
+        if let Some(bid) = repr.bid {
+            agent.set_valuation(bid);
+        }
+        Ok(agent)
+    }
+}
+
+/// Evaluates arbitrary bundles, e.g. from a compact bidding language
+pub trait Valuation: fmt::Debug + Send + Sync {
+    /// Value of a bundle
+    fn value(&self, bundle: &Bundle) -> f64;
+
+    /// Bundles worth considering when searching for demand
+    fn bundles(&self) -> Vec<Bundle> {
+        Vec::new()
+    }
+
+    /// Serializable form, if this valuation is expressed in a bidding language
+    fn to_bid_language(&self) -> Option<BidLanguage> {
+        None
+    }
+
+    /// Whether no bundle is worth more than its best subset among `bundles`
+    /// (XOR semantics), so that bounds computed over those bundles hold
+    /// for every bundle
+    fn bounded_by_bundles(&self) -> bool {
+        false
+    }
+}
+
+/// A single bundle/value pair inside a bid
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtomicBid {
+    pub bundle: Bundle,
+    pub value: f64,
+}
+
+/// XOR bid: mutually exclusive atomic bids.
+/// A bundle is worth the highest atomic bid it contains, so at most
+/// one atomic bid is ever "won" (assumes free disposal)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct XorBid {
+    pub atoms: Vec<AtomicBid>,
+}
+
+impl XorBid {
+    pub fn new() -> Self {
+        Self { atoms: Vec::new() }
+    }
+
+    /// Add an atomic bid
+    pub fn add(&mut self, bundle: Bundle, value: f64) {
+        self.atoms.push(AtomicBid { bundle, value });
+    }
+
+    /// Builder-style variant of `add`
+    pub fn with(mut self, bundle: Bundle, value: f64) -> Self {
+        self.add(bundle, value);
+        self
+    }
+
+    /// Value of an arbitrary bundle under XOR semantics
+    pub fn value(&self, bundle: &Bundle) -> f64 {
+        self.atoms
+            .iter()
+            .filter(|atom| atom.bundle.is_subset(bundle))
+            .map(|atom| atom.value)
+            .fold(0.0, f64::max)
+    }
+}
+
+impl Valuation for XorBid {
+    fn value(&self, bundle: &Bundle) -> f64 {
+        XorBid::value(self, bundle)
+    }
+
+    fn bundles(&self) -> Vec<Bundle> {
+        self.atoms.iter().map(|atom| atom.bundle.clone()).collect()
+    }
+
+    fn to_bid_language(&self) -> Option<BidLanguage> {
+        Some(BidLanguage::Xor(self.clone()))
+    }
+
+    fn bounded_by_bundles(&self) -> bool {
+        true
+    }
+}
+
+/// OR bid: any collection of disjoint atomic bids can be won together.
+/// A bid of singleton bundles expresses an additive valuation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrBid {
+    pub atoms: Vec<AtomicBid>,
+}
+
+impl OrBid {
+    pub fn new() -> Self {
+        Self { atoms: Vec::new() }
+    }
+
+    /// Add an atomic bid
+    pub fn add(&mut self, bundle: Bundle, value: f64) {
+        self.atoms.push(AtomicBid { bundle, value });
+    }
+
+    /// Builder-style variant of `add`
+    pub fn with(mut self, bundle: Bundle, value: f64) -> Self {
+        self.add(bundle, value);
+        self
+    }
+
+    /// Value of an arbitrary bundle under OR semantics
+    pub fn value(&self, bundle: &Bundle) -> f64 {
+        best_disjoint_combination(self.atoms.iter().map(std::slice::from_ref), bundle)
+    }
+}
+
+impl Valuation for OrBid {
+    fn value(&self, bundle: &Bundle) -> f64 {
+        OrBid::value(self, bundle)
+    }
+
+    fn bundles(&self) -> Vec<Bundle> {
+        self.atoms.iter().map(|atom| atom.bundle.clone()).collect()
+    }
+
+    fn to_bid_language(&self) -> Option<BidLanguage> {
+        Some(BidLanguage::Or(self.clone()))
+    }
+}
+
+/// OR-of-XOR bid: at most one atomic bid per XOR clause is won,
+/// and atoms won across clauses must be disjoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrOfXorBid {
+    pub clauses: Vec<XorBid>,
+}
+
+impl OrOfXorBid {
+    pub fn new() -> Self {
+        Self {
+            clauses: Vec::new(),
+        }
+    }
+
+    /// Add an XOR clause
+    pub fn add(&mut self, clause: XorBid) {
+        self.clauses.push(clause);
+    }
+
+    /// Builder-style variant of `add`
+    pub fn with(mut self, clause: XorBid) -> Self {
+        self.add(clause);
+        self
+    }
+
+    /// Value of an arbitrary bundle under OR-of-XOR semantics
+    pub fn value(&self, bundle: &Bundle) -> f64 {
+        best_disjoint_combination(self.clauses.iter().map(|clause| &clause.atoms[..]), bundle)
+    }
+}
+
+impl Valuation for OrOfXorBid {
+    fn value(&self, bundle: &Bundle) -> f64 {
+        OrOfXorBid::value(self, bundle)
+    }
+
+    fn bundles(&self) -> Vec<Bundle> {
+        self.clauses
+            .iter()
+            .flat_map(|clause| clause.atoms.iter().map(|atom| atom.bundle.clone()))
+            .collect()
+    }
+
+    fn to_bid_language(&self) -> Option<BidLanguage> {
+        Some(BidLanguage::OrOfXor(self.clone()))
+    }
+}
+
+/// Any of the supported bidding languages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "language", rename_all = "snake_case")]
+pub enum BidLanguage {
+    Xor(XorBid),
+    Or(OrBid),
+    OrOfXor(OrOfXorBid),
+    KAdditive(KAdditiveValuation),
+}
+
+impl Valuation for BidLanguage {
+    fn value(&self, bundle: &Bundle) -> f64 {
+        match self {
+            BidLanguage::Xor(bid) => bid.value(bundle),
+            BidLanguage::Or(bid) => bid.value(bundle),
+            BidLanguage::OrOfXor(bid) => bid.value(bundle),
+            BidLanguage::KAdditive(valuation) => valuation.value(bundle),
+        }
+    }
+
+    fn bundles(&self) -> Vec<Bundle> {
+        match self {
+            BidLanguage::Xor(bid) => Valuation::bundles(bid),
+            BidLanguage::Or(bid) => Valuation::bundles(bid),
+            BidLanguage::OrOfXor(bid) => Valuation::bundles(bid),
+            BidLanguage::KAdditive(valuation) => valuation.bundles(),
+        }
+    }
+
+    fn to_bid_language(&self) -> Option<BidLanguage> {
+        Some(self.clone())
+    }
+
+    fn bounded_by_bundles(&self) -> bool {
+        matches!(self, BidLanguage::Xor(_))
+    }
+}
+
+/// Best total value of pairwise-disjoint atoms inside `bundle`,
+/// taking at most one atom from each clause. Clauses sharing no good with
+/// another clause (e.g. additive bids) are summed directly; the rest are
+/// searched with branch and bound.
+fn best_disjoint_combination<'a>(
+    clauses: impl Iterator<Item = &'a [AtomicBid]>,
+    bundle: &Bundle,
+) -> f64 {
+    /// Atoms of one clause, as indices of their goods and values
+    type Clause = Vec<(Vec<usize>, f64)>;
+
+    fn best_atom(clause: &Clause) -> f64 {
+        clause.iter().map(|(_, value)| *value).fold(0.0, f64::max)
+    }
+
+    fn search(clauses: &[Clause], bounds: &[f64], used: &mut [bool], value: f64, best: &mut f64) {
+        *best = best.max(value);
+        let Some((clause, rest)) = clauses.split_first() else {
+            return;
+        };
+        if value + bounds[0] <= *best {
+            return;
+        }
+        for (goods, atom_value) in clause {
+            if goods.iter().all(|&good| !used[good]) {
+                goods.iter().for_each(|&good| used[good] = true);
+                search(rest, &bounds[1..], used, value + atom_value, best);
+                goods.iter().for_each(|&good| used[good] = false);
+            }
+        }
+        search(rest, &bounds[1..], used, value, best);
+    }
+
+    // Only positive atoms inside the bundle can be won
+    let mut index: HashMap<GoodId, usize> = HashMap::new();
+    let clauses: Vec<Clause> = clauses
+        .map(|atoms| {
+            atoms
+                .iter()
+                .filter(|atom| atom.value > 0.0 && atom.bundle.is_subset(bundle))
+                .map(|atom| {
+                    let goods = atom.bundle.iter().map(|good| {
+                        let next = index.len();
+                        *index.entry(good.id).or_insert(next)
+                    });
+                    (goods.collect(), atom.value)
+                })
+                .collect()
+        })
+        .filter(|clause: &Clause| !clause.is_empty())
+        .collect();
+
+    let mut touching = vec![0usize; index.len()];
+    for clause in &clauses {
+        let goods: HashSet<usize> = clause.iter().flat_map(|(goods, _)| goods.clone()).collect();
+        goods.into_iter().for_each(|good| touching[good] += 1);
+    }
+    let (independent, mut shared): (Vec<Clause>, Vec<Clause>) =
+        clauses.into_iter().partition(|clause| {
+            clause.iter().all(|(goods, _)| goods.iter().all(|&good| touching[good] == 1))
+        });
+
+    // Most valuable clauses first, so good combinations prune early
+    shared.sort_by(|a, b| best_atom(b).total_cmp(&best_atom(a)));
+    let mut bounds = vec![0.0; shared.len() + 1];
+    for i in (0..shared.len()).rev() {
+        bounds[i] = bounds[i + 1] + best_atom(&shared[i]);
+    }
+    let mut best = 0.0;
+    search(&shared, &bounds, &mut vec![false; index.len()], 0.0, &mut best);
+    independent.iter().map(best_atom).sum::<f64>() + best
+}
+
+/// An allocation maps agents to their assigned bundles. Fields are private
+/// so that `assign`/`assign_units` keep the per-good totals current.
+/// Serializes with agents and goods sorted by ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "AllocationRepr")]
 pub struct Allocation {
-    pub assignments: HashMap<String, Bundle>,
+    #[serde(serialize_with = "crate::sorted::serialize")]
+    assignments: HashMap<AgentId, Bundle>,
+    /// Units per agent for multi-unit goods (one of each good if absent)
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "crate::sorted::serialize"
+    )]
+    units: HashMap<AgentId, Units>,
+    /// Units of each good allocated across all agents
+    #[serde(skip)]
+    counts: HashMap<GoodId, u32>,
+}
+
+/// Serialized form of an allocation (totals are rebuilt on load)
+#[derive(Deserialize)]
+struct AllocationRepr {
+    assignments: HashMap<AgentId, Bundle>,
+    #[serde(default)]
+    units: HashMap<AgentId, Units>,
+}
+
+impl From<AllocationRepr> for Allocation {
+    fn from(repr: AllocationRepr) -> Self {
+        let mut allocation = Allocation::new();
+        for (agent_id, bundle) in repr.assignments {
+            let units = repr.units.get(&agent_id).cloned().unwrap_or_default();
+            allocation.assign_units(agent_id, bundle, units);
+        }
+        allocation
+    }
 }
 
 impl Allocation {
     pub fn new() -> Self {
         Self {
             assignments: HashMap::new(),
+            units: HashMap::new(),
+            counts: HashMap::new(),
         }
     }
 
-    pub fn assign(&mut self, agent_id: String, bundle: Bundle) {
+    /// Assign a bundle with one unit of each good
+    pub fn assign(&mut self, agent_id: impl Into<AgentId>, bundle: Bundle) {
+        self.assign_units(agent_id, bundle, Units::new());
+    }
+
+    /// Assign a bundle with explicit unit counts
+    pub fn assign_units(&mut self, agent_id: impl Into<AgentId>, bundle: Bundle, units: Units) {
+        let agent_id = agent_id.into();
+        self.update_counts(agent_id, false);
+        if units.is_empty() {
+            self.units.remove(&agent_id);
+        } else {
+            self.units.insert(agent_id, units);
+        }
         self.assignments.insert(agent_id, bundle);
+        self.update_counts(agent_id, true);
+    }
+
+    /// Add (or remove) an agent's holding to the per-good totals
+    fn update_counts(&mut self, agent_id: AgentId, add: bool) {
+        let Some(bundle) = self.assignments.get(&agent_id) else {
+            return;
+        };
+        let units = self.units.get(&agent_id);
+        for good in bundle {
+            let count = unit_count(bundle, units, good.id);
+            let total = self.counts.entry(good.id).or_insert(0);
+            *total = if add { *total + count } else { total.saturating_sub(count) };
+        }
+    }
+
+    pub fn get_bundle(&self, agent_id: impl Into<AgentId>) -> Option<&Bundle> {
+        self.assignments.get(&agent_id.into())
     }
 
-    pub fn get_bundle(&self, agent_id: &str) -> Option<&Bundle> {
-        self.assignments.get(agent_id)
+    /// Every agent's bundle
+    pub fn assignments(&self) -> &HashMap<AgentId, Bundle> {
+        &self.assignments
+    }
+
+    /// Agents and their bundles, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&AgentId, &Bundle)> {
+        self.assignments.iter()
+    }
+
+    /// Assignments sorted by agent ID, each with its good IDs sorted
+    pub fn sorted_assignments(&self) -> Vec<(AgentId, Vec<GoodId>)> {
+        let mut sorted: Vec<(AgentId, Vec<GoodId>)> = self
+            .assignments
+            .iter()
+            .map(|(agent_id, bundle)| (*agent_id, sorted_ids(bundle)))
+            .collect();
+        sorted.sort_by_key(|(agent_id, _)| agent_id.as_str());
+        sorted
+    }
+
+    /// Unit counts assigned to an agent, if not one of each good
+    pub fn get_units(&self, agent_id: impl Into<AgentId>) -> Option<&Units> {
+        self.units.get(&agent_id.into())
+    }
+
+    /// Number of units of a good assigned to an agent
+    pub fn unit_count(&self, agent_id: impl Into<AgentId>, good_id: impl Into<GoodId>) -> u32 {
+        let agent_id = agent_id.into();
+        self.get_bundle(agent_id)
+            .map(|bundle| unit_count(bundle, self.get_units(agent_id), good_id.into()))
+            .unwrap_or(0)
+    }
+
+    /// Units of a good allocated across all agents (O(1))
+    pub fn allocated_units(&self, good_id: impl Into<GoodId>) -> u32 {
+        self.counts.get(&good_id.into()).copied().unwrap_or(0)
+    }
+
+    /// Whether giving `agent_id` the bundle instead of its current holding
+    /// keeps every good within `supply` (e.g. to reject a move mid-run)
+    pub fn fits_after(
+        &self,
+        agent_id: impl Into<AgentId>,
+        bundle: &Bundle,
+        units: Option<&Units>,
+        supply: impl Fn(GoodId) -> u32,
+    ) -> bool {
+        let agent_id = agent_id.into();
+        bundle.iter().all(|good| {
+            let others = self.allocated_units(good.id).saturating_sub(self.unit_count(agent_id, good.id));
+            others + unit_count(bundle, units, good.id) <= supply(good.id)
+        })
     }
 }
 
@@ -101,14 +1081,324 @@ impl Default for Allocation {
     }
 }
 
-/// Result of an auction run
+/// A random allocation: each agent receives a lottery over bundles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FractionalAllocation {
+    /// Agent ID -> (bundle, probability) pairs
+    #[serde(serialize_with = "crate::sorted::serialize")]
+    pub shares: HashMap<AgentId, Vec<(Bundle, f64)>>,
+}
+
+impl FractionalAllocation {
+    pub fn new() -> Self {
+        Self {
+            shares: HashMap::new(),
+        }
+    }
+
+    /// Add probability mass for an agent receiving a bundle
+    pub fn add_share(&mut self, agent_id: impl Into<AgentId>, bundle: Bundle, probability: f64) {
+        let shares = self.shares.entry(agent_id.into()).or_default();
+        match shares.iter_mut().find(|(b, _)| *b == bundle) {
+            Some((_, p)) => *p += probability,
+            None => shares.push((bundle, probability)),
+        }
+    }
+
+    /// Build from a lottery over deterministic allocations
+    pub fn from_lottery(lottery: &[(Allocation, f64)]) -> Self {
+        let mut fractional = Self::new();
+        for (allocation, probability) in lottery {
+            for (agent_id, bundle) in allocation.iter() {
+                fractional.add_share(*agent_id, bundle.clone(), *probability);
+            }
+        }
+        fractional
+    }
+
+    /// Decompose a random assignment of single goods into a lottery over
+    /// deterministic allocations (see `lottery::decompose`)
+    pub fn to_lottery(&self, goods: &[Good]) -> Result<Vec<(Allocation, f64)>, AuctionError> {
+        crate::lottery::decompose(self, goods)
+    }
+
+    /// Lottery of an agent
+    pub fn get_shares(&self, agent_id: impl Into<AgentId>) -> &[(Bundle, f64)] {
+        self.shares.get(&agent_id.into()).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Probability that an agent receives a good
+    pub fn good_share(&self, agent_id: impl Into<AgentId>, good_id: impl Into<GoodId>) -> f64 {
+        let good_id = good_id.into();
+        self.get_shares(agent_id)
+            .iter()
+            .filter(|(bundle, _)| bundle.iter().any(|g| g.id == good_id))
+            .map(|(_, p)| p)
+            .sum()
+    }
+
+    /// Expected preference value of an agent's lottery
+    pub fn expected_value(&self, agent: &Agent) -> f64 {
+        self.get_shares(agent.id)
+            .iter()
+            .map(|(bundle, p)| p * agent.preference(bundle))
+            .sum()
+    }
+}
+
+/// Result of an auction run. Serializes with agents and goods sorted by
+/// ID, so equal results give byte-identical output.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuctionResult {
     pub allocation: Allocation,
-    pub prices: HashMap<String, f64>, // Price per good
+    #[serde(serialize_with = "crate::sorted::serialize")]
+    pub prices: HashMap<GoodId, f64>, // Price per good
     pub total_welfare: f64,
     pub is_feasible: bool,
     pub is_individually_rational: bool,
     pub is_ordinal_efficient: bool,
+    /// No agent envies another's bundle at the final prices
+    #[serde(default)]
+    pub is_envy_free: bool,
+    /// No agent's envy exceeds epsilon at the final prices
+    #[serde(default)]
+    pub is_epsilon_envy_free: bool,
+    /// VCG payments per agent, if requested
+    #[serde(serialize_with = "crate::sorted::serialize")]
+    pub vcg_payments: Option<HashMap<AgentId, f64>>,
+    /// Core-selecting payments per agent, if requested
+    #[serde(serialize_with = "crate::sorted::serialize")]
+    pub core_payments: Option<HashMap<AgentId, f64>>,
+    /// What each agent pays under the auction's payment rule (negative = receives)
+    #[serde(default, serialize_with = "crate::sorted::serialize")]
+    pub payments: HashMap<AgentId, f64>,
+    /// Sum of the payments, collected by the seller
+    #[serde(default)]
+    pub revenue: f64,
+    /// Each agent's value for its bundle minus its payment
+    #[serde(default, serialize_with = "crate::sorted::serialize")]
+    pub net_utility: HashMap<AgentId, f64>,
+    /// Money each agent holds after paying
+    #[serde(default, serialize_with = "crate::sorted::serialize")]
+    pub money: HashMap<AgentId, f64>,
+    /// Agents' quasi-linear utilities (value plus leftover money) plus the
+    /// seller's revenue
+    #[serde(default)]
+    pub quasi_linear_welfare: f64,
+    /// Shapley value of each agent's share of the optimal welfare, if requested
+    #[serde(default, serialize_with = "crate::sorted::serialize")]
+    pub shapley_values: Option<HashMap<AgentId, f64>>,
+    /// Amount by which each agent's bundle price exceeds its budget
+    #[serde(serialize_with = "crate::sorted::serialize")]
+    pub budget_relaxation: HashMap<AgentId, f64>,
+    /// Every agent's budget relaxation is within its epsilon
+    #[serde(default)]
+    pub is_budget_feasible: bool,
+    /// Goods that remain with the seller (sorted by ID)
+    pub unallocated_goods: Vec<GoodId>,
+    /// Agent-specific prices, present when the anonymous prices fail to
+    /// support the allocation
+    pub personalized_prices: Option<PersonalizedPriceVector>,
+    /// Welfare found by the benchmark WDP solver, if one was configured
+    #[serde(default)]
+    pub benchmark_welfare: Option<f64>,
+    /// Upper bound on the optimal welfare (from the benchmark solver if set);
+    /// None if some agent's valuation is worth more on bundles it does not
+    /// list (e.g. an OR bid), where no bound is computed
+    #[serde(default)]
+    pub welfare_upper_bound: Option<f64>,
+    /// How far `total_welfare` may be below the optimum, if bounded
+    #[serde(default)]
+    pub welfare_gap: Option<f64>,
+    /// Objective the mechanism optimized
+    #[serde(default)]
+    pub objective: Objective,
+    /// Value of the allocation under `objective`
+    #[serde(default)]
+    pub objective_value: f64,
+    /// Net utility each agent forgoes relative to its demand set at the final prices
+    #[serde(default, serialize_with = "crate::sorted::serialize")]
+    pub ce_violations: HashMap<AgentId, f64>,
+    /// Largest entry of `ce_violations` (0 = competitive equilibrium)
+    #[serde(default)]
+    pub max_ce_violation: f64,
+    /// Agents of every executed trade, in order; reported instead of prices
+    /// in housing market mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trade_cycles: Option<Vec<Vec<AgentId>>>,
+    /// False if an iteration cap cut the computation short (the prices may
+    /// then not support the allocation)
+    #[serde(default = "default_converged")]
+    pub converged: bool,
+    /// Every iteration cap that was hit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub non_convergence: Vec<NonConvergence>,
+    /// Global epsilon chosen by automatic selection, if enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epsilon_used: Option<f64>,
 }
 
+/// BRACE's outcome next to a welfare-maximizing one on the same instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkRun {
+    pub brace: AuctionResult,
+    /// Winner determination outcome, checked like BRACE's (at zero prices)
+    pub optimal: AuctionResult,
+    /// `brace.approximation_ratio(&optimal)`
+    pub approximation_ratio: f64,
+}
+
+impl AuctionResult {
+    /// Prices sorted by good ID
+    pub fn sorted_prices(&self) -> Vec<(GoodId, f64)> {
+        let mut sorted: Vec<(GoodId, f64)> =
+            self.prices.iter().map(|(id, p)| (*id, *p)).collect();
+        sorted.sort_by_key(|(id, _)| id.as_str());
+        sorted
+    }
+
+    /// This result's welfare as a fraction of `optimal`'s (1 when both are 0)
+    pub fn approximation_ratio(&self, optimal: &AuctionResult) -> f64 {
+        if optimal.total_welfare.abs() < 1e-12 {
+            return 1.0;
+        }
+        self.total_welfare / optimal.total_welfare
+    }
+
+    /// Explain, per agent, why it received its bundle at the final prices
+    pub fn explain(&self, agents: &[Agent]) -> Report {
+        report::explain(self, agents)
+    }
+
+    /// Write flat CSV tables: the allocation (agent, good, units), the final
+    /// prices (good, price) and per-agent outcomes (value, price, utility, payments)
+    pub fn to_csv_writers(
+        &self,
+        agents: &[Agent],
+        allocation: impl std::io::Write,
+        prices: impl std::io::Write,
+        outcomes: impl std::io::Write,
+    ) -> std::io::Result<()> {
+        report::write_csv(self, agents, allocation, prices, outcomes)
+    }
+}
+
+/// A complete auction instance that can be stored and replayed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuctionInstance {
+    pub agents: Vec<Agent>,
+    pub goods: Vec<Good>,
+    pub epsilon: f64,
+    /// Rules on which goods may be allocated together
+    #[serde(default, skip_serializing_if = "Constraints::is_empty")]
+    pub constraints: Constraints,
+}
+
+impl AuctionInstance {
+    pub fn new(agents: Vec<Agent>, goods: Vec<Good>, epsilon: f64) -> Self {
+        Self {
+            agents,
+            goods,
+            epsilon,
+            constraints: Constraints::default(),
+        }
+    }
+
+    /// Attach allocation constraints
+    pub fn with_constraints(mut self, constraints: Constraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    /// Serialize the instance to pretty-printed JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserialize an instance from JSON
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Read a JSON instance, or build one from a TOML/YAML scenario file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, AuctionError> {
+        let path = path.as_ref();
+        if is_scenario_path(path) {
+            Scenario::from_path(path)?.to_instance()
+        } else {
+            Ok(read_instance(path)?)
+        }
+    }
+
+    /// Check the instance for malformed input: unique good and agent IDs,
+    /// known goods in endowments, preferences and constraints, finite values,
+    /// and endowments that do not exceed supply (disjoint for single-unit goods)
+    pub fn validate(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        let mut supply: HashMap<GoodId, u32> = HashMap::new();
+        for good in &self.goods {
+            if supply.insert(good.id, good.supply).is_some() {
+                violations.push(Violation::DuplicateGood(good.id));
+            }
+        }
+
+        let mut agent_ids = HashSet::new();
+        let mut endowed: HashMap<GoodId, u32> = HashMap::new();
+        for agent in &self.agents {
+            if !agent_ids.insert(agent.id) {
+                violations.push(Violation::DuplicateAgent(agent.id));
+            }
+            for good_id in sorted_ids(&agent.endowment) {
+                if !supply.contains_key(&good_id) {
+                    violations.push(Violation::UnknownEndowedGood {
+                        agent: agent.id,
+                        good: good_id,
+                    });
+                }
+                *endowed.entry(good_id).or_insert(0) +=
+                    unit_count(&agent.endowment, Some(&agent.endowment_units), good_id);
+            }
+            for bundle in agent.preference_bundles() {
+                for good_id in sorted_ids(bundle) {
+                    if !supply.contains_key(&good_id) {
+                        violations.push(Violation::UnknownPreferenceGood {
+                            agent: agent.id,
+                            good: good_id,
+                        });
+                    }
+                }
+                let value = agent.preference(bundle);
+                if !value.is_finite() {
+                    violations.push(Violation::NonFiniteValue {
+                        agent: agent.id,
+                        value,
+                    });
+                }
+            }
+        }
+
+        for good_id in self.constraints.goods() {
+            if !supply.contains_key(&good_id) {
+                violations.push(Violation::UnknownConstrainedGood(good_id));
+            }
+        }
+
+        for good in &self.goods {
+            let count = endowed.get(&good.id).copied().unwrap_or(0);
+            if count > good.supply {
+                violations.push(Violation::OverEndowed {
+                    good: good.id,
+                    endowed: count,
+                    supply: good.supply,
+                });
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
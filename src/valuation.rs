@@ -0,0 +1,391 @@
+use crate::pricing::PriceVector;
+use crate::types::{
+    AtomicBid, BidLanguage, Bundle, BundleKey, Category, Good, OrBid, Valuation, XorBid,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Computes an agent's demand directly instead of scanning listed bundles
+pub trait DemandOracle: fmt::Debug + Send + Sync {
+    /// Bundles maximizing value minus price at `prices`
+    fn demand(&self, prices: &PriceVector) -> Vec<Bundle>;
+}
+
+/// An explicit bundle-value map: listed bundles have their value, all others 0
+#[derive(Debug, Clone, Default)]
+pub struct ExplicitValuation {
+    values: HashMap<BundleKey, f64>,
+    bundles: Vec<Bundle>,
+}
+
+impl ExplicitValuation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the value of a bundle
+    pub fn insert(&mut self, bundle: Bundle, value: f64) {
+        if self.values.insert(BundleKey::from_bundle(&bundle), value).is_none() {
+            self.bundles.push(bundle);
+        }
+    }
+
+    /// Remove a bundle, returning its value
+    pub fn remove(&mut self, bundle: &Bundle) -> Option<f64> {
+        let value = self.values.remove(&BundleKey::from_bundle(bundle))?;
+        self.bundles.retain(|b| b != bundle);
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.bundles.clear();
+    }
+
+    /// Listed bundles and their values, in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (&Bundle, f64)> {
+        self.bundles.iter().map(|bundle| (bundle, self.values[&BundleKey::from_bundle(bundle)]))
+    }
+
+    /// Builder-style variant of `insert`
+    pub fn with(mut self, bundle: Bundle, value: f64) -> Self {
+        self.insert(bundle, value);
+        self
+    }
+
+    /// Value of a listed bundle
+    pub fn get(&self, bundle: &Bundle) -> Option<f64> {
+        if self.values.is_empty() {
+            return None;
+        }
+        self.get_key(&BundleKey::from_bundle(bundle))
+    }
+
+    pub(crate) fn get_key(&self, key: &BundleKey) -> Option<f64> {
+        self.values.get(key).copied()
+    }
+
+    pub fn contains(&self, bundle: &Bundle) -> bool {
+        self.values.contains_key(&BundleKey::from_bundle(bundle))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl Valuation for ExplicitValuation {
+    fn value(&self, bundle: &Bundle) -> f64 {
+        self.get(bundle).unwrap_or(0.0)
+    }
+
+    fn bundles(&self) -> Vec<Bundle> {
+        self.bundles.clone()
+    }
+}
+
+/// Additive valuation: a bundle is worth the sum of its goods' values
+#[derive(Debug, Clone, Default)]
+pub struct AdditiveValuation {
+    values: Vec<(Good, f64)>,
+}
+
+impl AdditiveValuation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Value a single good
+    pub fn with(mut self, good: &Good, value: f64) -> Self {
+        self.values.push((good.clone(), value));
+        self
+    }
+}
+
+impl Valuation for AdditiveValuation {
+    fn value(&self, bundle: &Bundle) -> f64 {
+        self.values
+            .iter()
+            .filter(|(good, _)| bundle.contains(good))
+            .map(|(_, value)| value)
+            .sum()
+    }
+
+    /// Each good alone plus all goods together
+    fn bundles(&self) -> Vec<Bundle> {
+        let mut bundles: Vec<Bundle> = self
+            .values
+            .iter()
+            .map(|(good, _)| [good.clone()].into_iter().collect())
+            .collect();
+        if self.values.len() > 1 {
+            bundles.push(self.values.iter().map(|(good, _)| good.clone()).collect());
+        }
+        bundles
+    }
+
+    /// Additive values are an OR bid over single goods
+    fn to_bid_language(&self) -> Option<BidLanguage> {
+        let bid = self.values.iter().fold(OrBid::new(), |bid, (good, value)| {
+            bid.with([good.clone()].into_iter().collect(), *value)
+        });
+        Some(BidLanguage::Or(bid))
+    }
+}
+
+/// Every good worth more than its price
+impl DemandOracle for AdditiveValuation {
+    fn demand(&self, prices: &PriceVector) -> Vec<Bundle> {
+        let bundle = self
+            .values
+            .iter()
+            .filter(|(good, value)| *value > prices.get_price(good.id))
+            .map(|(good, _)| good.clone())
+            .collect();
+        vec![bundle]
+    }
+}
+
+/// Unit-demand valuation: a bundle is worth its single most valuable good
+#[derive(Debug, Clone, Default)]
+pub struct UnitDemandValuation {
+    values: Vec<(Good, f64)>,
+}
+
+impl UnitDemandValuation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Value a single good
+    pub fn with(mut self, good: &Good, value: f64) -> Self {
+        self.values.push((good.clone(), value));
+        self
+    }
+}
+
+impl Valuation for UnitDemandValuation {
+    fn value(&self, bundle: &Bundle) -> f64 {
+        self.values
+            .iter()
+            .filter(|(good, _)| bundle.contains(good))
+            .map(|(_, value)| *value)
+            .fold(0.0, f64::max)
+    }
+
+    fn bundles(&self) -> Vec<Bundle> {
+        self.values
+            .iter()
+            .map(|(good, _)| [good.clone()].into_iter().collect())
+            .collect()
+    }
+
+    /// Unit demand is an XOR bid over single goods
+    fn to_bid_language(&self) -> Option<BidLanguage> {
+        let bid = self.values.iter().fold(XorBid::new(), |bid, (good, value)| {
+            bid.with([good.clone()].into_iter().collect(), *value)
+        });
+        Some(BidLanguage::Xor(bid))
+    }
+
+    fn bounded_by_bundles(&self) -> bool {
+        true
+    }
+}
+
+/// The goods with the highest positive surplus, or nothing if none has any
+impl DemandOracle for UnitDemandValuation {
+    fn demand(&self, prices: &PriceVector) -> Vec<Bundle> {
+        let surplus = |(good, value): &(Good, f64)| value - prices.get_price(good.id);
+        let best = self.values.iter().map(surplus).fold(0.0, f64::max);
+        if best <= 1e-9 {
+            return vec![Bundle::new()];
+        }
+        self.values
+            .iter()
+            .filter(|entry| (surplus(entry) - best).abs() < 1e-9)
+            .map(|(good, _)| [good.clone()].into_iter().collect())
+            .collect()
+    }
+}
+
+/// Lexicographic preferences over a priority order of goods or categories:
+/// a bundle beats every bundle that falls behind it at the first priority
+/// level where the two differ, whatever the lower levels hold. Encoded as
+/// additive values that fall geometrically with priority, so the order is
+/// exact as long as the value range fits in an f64 mantissa (53 levels over
+/// single goods); lower levels beyond that only break ties approximately.
+#[derive(Debug, Clone)]
+pub struct LexicographicValuation {
+    additive: AdditiveValuation,
+}
+
+impl LexicographicValuation {
+    /// Holding the first good beats anything without it, then the second, ...
+    pub fn over_goods(priority: &[Good]) -> Self {
+        let levels = priority.len() as i32;
+        let additive = priority.iter().enumerate().fold(AdditiveValuation::new(), |v, (k, good)| {
+            v.with(good, 2f64.powi(levels - 1 - k as i32))
+        });
+        Self { additive }
+    }
+
+    /// More goods of the first category beats any bundle with fewer, then
+    /// the second category, ... Goods outside the listed categories are
+    /// worth nothing.
+    pub fn over_categories(priority: &[Category], goods: &[Good]) -> Self {
+        let members: Vec<Vec<&Good>> = priority
+            .iter()
+            .map(|category| {
+                goods.iter().filter(|good| good.category.as_ref() == Some(category)).collect()
+            })
+            .collect();
+        // A level's weight exceeds everything the levels below can add up to
+        let base = members.iter().map(Vec::len).max().unwrap_or(0) as f64 + 1.0;
+        let levels = priority.len() as i32;
+        let mut additive = AdditiveValuation::new();
+        for (k, level) in members.iter().enumerate() {
+            for good in level {
+                additive = additive.with(good, base.powi(levels - 1 - k as i32));
+            }
+        }
+        Self { additive }
+    }
+}
+
+impl Valuation for LexicographicValuation {
+    fn value(&self, bundle: &Bundle) -> f64 {
+        self.additive.value(bundle)
+    }
+
+    fn bundles(&self) -> Vec<Bundle> {
+        self.additive.bundles()
+    }
+
+    fn to_bid_language(&self) -> Option<BidLanguage> {
+        self.additive.to_bid_language()
+    }
+}
+
+impl DemandOracle for LexicographicValuation {
+    fn demand(&self, prices: &PriceVector) -> Vec<Bundle> {
+        self.additive.demand(prices)
+    }
+}
+
+/// k-additive valuation: per-good values plus synergy terms on small sets
+/// of goods. A bundle is worth the sum of the terms whose goods it holds
+/// all of, so pairwise (or up to k-wise) complements and substitutes take
+/// one term each instead of listing every bundle they affect.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KAdditiveValuation {
+    terms: Vec<AtomicBid>,
+}
+
+impl KAdditiveValuation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Value a single good
+    pub fn with_good(self, good: &Good, value: f64) -> Self {
+        self.with_synergy(std::slice::from_ref(good), value)
+    }
+
+    /// Add `value` whenever a bundle holds all of `goods` (positive for
+    /// complements, negative for substitutes). Repeated sets add up.
+    pub fn with_synergy(mut self, goods: &[Good], value: f64) -> Self {
+        let bundle: Bundle = goods.iter().cloned().collect();
+        match self.terms.iter_mut().find(|term| term.bundle == bundle) {
+            Some(term) => term.value += value,
+            None => self.terms.push(AtomicBid { bundle, value }),
+        }
+        self
+    }
+
+    /// Largest number of goods in one term (the k in k-additive)
+    pub fn order(&self) -> usize {
+        self.terms.iter().map(|term| term.bundle.len()).max().unwrap_or(0)
+    }
+
+    /// Terms (goods and value) in the order they were added
+    pub fn terms(&self) -> &[AtomicBid] {
+        &self.terms
+    }
+}
+
+impl Valuation for KAdditiveValuation {
+    fn value(&self, bundle: &Bundle) -> f64 {
+        self.terms
+            .iter()
+            .filter(|term| term.bundle.is_subset(bundle))
+            .map(|term| term.value)
+            .sum()
+    }
+
+    /// The goods of each term plus all goods together
+    fn bundles(&self) -> Vec<Bundle> {
+        let mut bundles: Vec<Bundle> = Vec::new();
+        for term in &self.terms {
+            if !term.bundle.is_empty() && !bundles.contains(&term.bundle) {
+                bundles.push(term.bundle.clone());
+            }
+        }
+        let all: Bundle = self.terms.iter().flat_map(|term| term.bundle.iter().cloned()).collect();
+        if !all.is_empty() && !bundles.contains(&all) {
+            bundles.push(all);
+        }
+        bundles
+    }
+
+    fn to_bid_language(&self) -> Option<BidLanguage> {
+        Some(BidLanguage::KAdditive(self.clone()))
+    }
+}
+
+/// Programmatic valuation backed by a closure.
+/// Not serializable; list candidate bundles with `with_bundles` so demand
+/// searches have something to consider.
+#[derive(Clone)]
+pub struct FnValuation {
+    value: Arc<dyn Fn(&Bundle) -> f64 + Send + Sync>,
+    bundles: Vec<Bundle>,
+}
+
+impl FnValuation {
+    pub fn new(value: impl Fn(&Bundle) -> f64 + Send + Sync + 'static) -> Self {
+        Self {
+            value: Arc::new(value),
+            bundles: Vec::new(),
+        }
+    }
+
+    pub fn with_bundles(mut self, bundles: Vec<Bundle>) -> Self {
+        self.bundles = bundles;
+        self
+    }
+}
+
+impl fmt::Debug for FnValuation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnValuation")
+            .field("bundles", &self.bundles.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Valuation for FnValuation {
+    fn value(&self, bundle: &Bundle) -> f64 {
+        (self.value)(bundle)
+    }
+
+    fn bundles(&self) -> Vec<Bundle> {
+        self.bundles.clone()
+    }
+}
@@ -0,0 +1,124 @@
+use crate::brace::BRACEMechanism;
+use crate::types::{Agent, Allocation, Bundle, Good};
+
+/// How an allocation compares with every feasible allocation of an instance
+#[derive(Debug, Clone)]
+pub struct ExhaustiveReport {
+    /// Number of feasible allocations enumerated
+    pub allocations: usize,
+    /// No feasible allocation leaves every agent at least as well off and
+    /// some agent better off
+    pub is_pareto_optimal: bool,
+    /// No feasible allocation makes every agent strictly better off (the
+    /// property `BRACEMechanism::verify_ordinal_efficiency` approximates
+    /// with pairwise swaps)
+    pub is_ordinal_efficient: bool,
+    /// Total welfare is the largest of any feasible allocation
+    pub is_welfare_optimal: bool,
+    pub welfare: f64,
+    pub optimal_welfare: f64,
+    /// A feasible allocation that Pareto-dominates the checked one
+    pub pareto_improvement: Option<Allocation>,
+}
+
+/// Run BRACE on a tiny instance and check its allocation against every
+/// feasible allocation (see `check_allocation`)
+pub fn exhaustive(agents: &[Agent], goods: &[Good]) -> ExhaustiveReport {
+    let (allocation, _) = BRACEMechanism::new(0.01).compute_allocation(agents, goods);
+    check_allocation(agents, goods, &allocation)
+}
+
+/// Compare `allocation` with every feasible allocation of the instance.
+/// Exponential in the number of agents and goods, so only for tiny instances.
+pub fn check_allocation(agents: &[Agent], goods: &[Good], allocation: &Allocation) -> ExhaustiveReport {
+    let own: Vec<Bundle> = agents
+        .iter()
+        .map(|agent| allocation.get_bundle(agent.id).cloned().unwrap_or_default())
+        .collect();
+    let welfare: f64 = agents.iter().zip(&own).map(|(agent, b)| agent.preference(b)).sum();
+
+    let mut report = ExhaustiveReport {
+        allocations: 0,
+        is_pareto_optimal: true,
+        is_ordinal_efficient: true,
+        is_welfare_optimal: true,
+        welfare,
+        optimal_welfare: welfare,
+        pareto_improvement: None,
+    };
+    for_each_allocation(agents, goods, &mut |bundles| {
+        report.allocations += 1;
+        let total: f64 = agents.iter().zip(bundles).map(|(agent, b)| agent.preference(b)).sum();
+        report.optimal_welfare = report.optimal_welfare.max(total);
+
+        let pairs = || agents.iter().zip(bundles).zip(&own);
+        if pairs().all(|((agent, alt), mine)| agent.prefers(alt, mine)) {
+            report.is_ordinal_efficient = false;
+        }
+        if report.pareto_improvement.is_none()
+            && pairs().all(|((agent, alt), mine)| !agent.prefers(mine, alt))
+            && pairs().any(|((agent, alt), mine)| agent.prefers(alt, mine))
+        {
+            report.is_pareto_optimal = false;
+            report.pareto_improvement = Some(to_allocation(agents, bundles));
+        }
+    });
+    report.is_welfare_optimal = welfare >= report.optimal_welfare - 1e-9;
+    report
+}
+
+/// Every feasible allocation: each unit of a good goes to at most one agent
+/// (one unit per holder) and no agent exceeds its capacity. Instance-level
+/// constraints are not applied.
+pub fn feasible_allocations(agents: &[Agent], goods: &[Good]) -> Vec<Allocation> {
+    let mut allocations = Vec::new();
+    for_each_allocation(agents, goods, &mut |bundles| {
+        allocations.push(to_allocation(agents, bundles));
+    });
+    allocations
+}
+
+fn to_allocation(agents: &[Agent], bundles: &[Bundle]) -> Allocation {
+    let mut allocation = Allocation::new();
+    for (agent, bundle) in agents.iter().zip(bundles) {
+        allocation.assign(agent.id, bundle.clone());
+    }
+    allocation
+}
+
+/// Call `visit` with the agents' bundles in every feasible allocation
+fn for_each_allocation(agents: &[Agent], goods: &[Good], visit: &mut dyn FnMut(&[Bundle])) {
+    fn place(
+        agents: &[Agent],
+        goods: &[Good],
+        next: usize,
+        bundles: &mut Vec<Bundle>,
+        visit: &mut dyn FnMut(&[Bundle]),
+    ) {
+        let Some(good) = goods.get(next) else {
+            if agents.iter().zip(bundles.iter()).all(|(agent, b)| agent.within_capacity(b, None)) {
+                visit(bundles);
+            }
+            return;
+        };
+        // Every set of at most `supply` holders
+        for holders in 0..1usize << agents.len() {
+            if holders.count_ones() > good.supply {
+                continue;
+            }
+            for (i, bundle) in bundles.iter_mut().enumerate() {
+                if holders & (1 << i) != 0 {
+                    bundle.insert(good.clone());
+                }
+            }
+            place(agents, goods, next + 1, bundles, visit);
+            for (i, bundle) in bundles.iter_mut().enumerate() {
+                if holders & (1 << i) != 0 {
+                    bundle.remove(good);
+                }
+            }
+        }
+    }
+    let mut bundles = vec![Bundle::new(); agents.len()];
+    place(agents, goods, 0, &mut bundles, visit);
+}
@@ -0,0 +1,110 @@
+use crate::ids::{AgentId, GoodId};
+use crate::types::{sorted_ids, Agent, AuctionResult};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Render an auction outcome as a Graphviz DOT graph.
+/// Agents are ellipses and goods are boxes labelled with their final price;
+/// dashed gray edges point from agents to their endowed goods and bold edges
+/// from goods to the agents that were allocated them.
+pub fn to_dot(result: &AuctionResult, agents: &[Agent]) -> String {
+    to_dot_with_trades(result, agents, &[])
+}
+
+/// `to_dot`, plus one red edge set per executed trade (as reported by
+/// `AuctionObserver::on_trade`): a two-way edge for swaps, a directed cycle otherwise
+pub fn to_dot_with_trades(
+    result: &AuctionResult,
+    agents: &[Agent],
+    trades: &[Vec<AgentId>],
+) -> String {
+    let mut goods: BTreeSet<&'static str> = result.prices.keys().map(|g| g.as_str()).collect();
+    for agent in agents {
+        goods.extend(agent.endowment.iter().map(|g| g.id.as_str()));
+    }
+    for bundle in result.allocation.assignments().values() {
+        goods.extend(bundle.iter().map(|g| g.id.as_str()));
+    }
+
+    let mut dot = String::from("digraph auction {\n    rankdir=LR;\n");
+    for agent in agents {
+        let _ = writeln!(
+            dot,
+            "    {} [shape=ellipse, label={}];",
+            agent_node(agent.id),
+            quote(agent.id.as_str())
+        );
+    }
+    for good in &goods {
+        let price = result.prices.get(&GoodId::from(*good)).copied().unwrap_or(0.0);
+        let _ = writeln!(
+            dot,
+            "    {} [shape=box, label={}];",
+            good_node(good),
+            quote(&format!("{}\\np = {:.2}", good, price))
+        );
+    }
+
+    for agent in agents {
+        for good in sorted_ids(&agent.endowment) {
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [style=dashed, color=gray, label=\"endowed\"];",
+                agent_node(agent.id),
+                good_node(good.as_str())
+            );
+        }
+    }
+    for agent in agents {
+        if let Some(bundle) = result.allocation.get_bundle(agent.id) {
+            for good in sorted_ids(bundle) {
+                let _ = writeln!(
+                    dot,
+                    "    {} -> {} [penwidth=2];",
+                    good_node(good.as_str()),
+                    agent_node(agent.id)
+                );
+            }
+        }
+    }
+
+    for (k, trade) in trades.iter().enumerate() {
+        let label = format!("trade {}", k + 1);
+        if trade.len() == 2 {
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [color=red, dir=both, label={}];",
+                agent_node(trade[0]),
+                agent_node(trade[1]),
+                quote(&label)
+            );
+            continue;
+        }
+        for (i, &from) in trade.iter().enumerate() {
+            let to = trade[(i + 1) % trade.len()];
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [color=red, label={}];",
+                agent_node(from),
+                agent_node(to),
+                quote(&label)
+            );
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+fn agent_node(id: AgentId) -> String {
+    quote(&format!("agent:{}", id))
+}
+
+fn good_node(id: &str) -> String {
+    quote(&format!("good:{}", id))
+}
+
+/// Quote a DOT identifier, escaping embedded quotes
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\\\""))
+}
@@ -0,0 +1,9 @@
+use crate::io::solve_json;
+use wasm_bindgen::prelude::*;
+
+/// Run BRACE on a JSON `AuctionInstance` and return the `AuctionResult` as
+/// JSON. Errors are thrown to JavaScript as their message string.
+#[wasm_bindgen]
+pub fn run_auction(json_instance: &str) -> Result<String, JsValue> {
+    solve_json(json_instance).map_err(|err| JsValue::from_str(&err.to_string()))
+}
@@ -0,0 +1,455 @@
+use crate::compact::GoodIndex;
+use crate::ids::GoodId;
+use crate::lp::solve_lp_relaxation;
+use crate::objective::Objective;
+use crate::types::{unit_count, Agent, Allocation, Bundle, Good, Units};
+use std::collections::HashMap;
+
+#[cfg(feature = "cbc")]
+mod cbc;
+#[cfg(feature = "highs")]
+mod highs;
+
+#[cfg(feature = "cbc")]
+pub use cbc::CbcSolver;
+#[cfg(feature = "highs")]
+pub use highs::HighsSolver;
+
+/// A winner determination backend
+pub trait WdpSolver: Send + Sync {
+    /// A feasible allocation (at most one preference bundle per agent, no
+    /// good over its supply) and its welfare
+    fn solve(&self, agents: &[Agent], goods: &[Good]) -> (Allocation, f64);
+
+    /// An upper bound on the optimal welfare; exact solvers return the optimum
+    fn upper_bound(&self, agents: &[Agent], goods: &[Good]) -> f64 {
+        self.solve(agents, goods).1
+    }
+}
+
+/// Branch and bound (`solve_exact`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExactSolver;
+
+impl WdpSolver for ExactSolver {
+    fn solve(&self, agents: &[Agent], goods: &[Good]) -> (Allocation, f64) {
+        solve_exact(agents, goods)
+    }
+}
+
+/// Dynamic program over good subsets (`solve_dp`), falling back to
+/// branch and bound when the instance is out of its reach
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DpSolver;
+
+impl WdpSolver for DpSolver {
+    fn solve(&self, agents: &[Agent], goods: &[Good]) -> (Allocation, f64) {
+        solve_dp(agents, goods).unwrap_or_else(|| solve_exact(agents, goods))
+    }
+}
+
+/// Accepts bids in decreasing order of value / sqrt(bundle size) while they fit
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GreedySolver;
+
+impl WdpSolver for GreedySolver {
+    fn solve(&self, agents: &[Agent], goods: &[Good]) -> (Allocation, f64) {
+        let mut bids = candidate_bids(agents, goods);
+        let score = |&(i, bundle): &(usize, &Bundle)| {
+            let size: u32 = bundle
+                .iter()
+                .map(|g| unit_count(bundle, agents[i].bundle_units(bundle), g.id))
+                .sum();
+            agents[i].preference(bundle) / (size.max(1) as f64).sqrt()
+        };
+        bids.sort_by(|a, b| score(b).total_cmp(&score(a)));
+        accept_in_order(agents, goods, &bids)
+    }
+
+    fn upper_bound(&self, agents: &[Agent], goods: &[Good]) -> f64 {
+        solve_lp_relaxation(agents, goods).welfare
+    }
+}
+
+/// Rounds the LP relaxation: bids are accepted greedily in decreasing order
+/// of their LP share. The upper bound is the LP optimum.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LpRelaxationSolver;
+
+impl WdpSolver for LpRelaxationSolver {
+    fn solve(&self, agents: &[Agent], goods: &[Good]) -> (Allocation, f64) {
+        let solution = solve_lp_relaxation(agents, goods);
+        let share = |i: usize, bundle: &Bundle| {
+            solution
+                .allocation
+                .get_shares(agents[i].id)
+                .iter()
+                .find(|(b, _)| b == bundle)
+                .map_or(0.0, |(_, p)| *p)
+        };
+        let mut bids: Vec<(usize, &Bundle)> = candidate_bids(agents, goods)
+            .into_iter()
+            .filter(|&(i, bundle)| share(i, bundle) > 1e-9)
+            .collect();
+        bids.sort_by(|&(i, a), &(k, b)| share(k, b).total_cmp(&share(i, a)));
+        accept_in_order(agents, goods, &bids)
+    }
+
+    fn upper_bound(&self, agents: &[Agent], goods: &[Good]) -> f64 {
+        solve_lp_relaxation(agents, goods).welfare
+    }
+}
+
+/// Positive-value preference bundles over known goods, as (agent index, bundle)
+pub(crate) fn candidate_bids<'a>(agents: &'a [Agent], goods: &[Good]) -> Vec<(usize, &'a Bundle)> {
+    let known: HashMap<GoodId, u32> = goods.iter().map(|g| (g.id, g.supply)).collect();
+    agents
+        .iter()
+        .enumerate()
+        .flat_map(|(i, agent)| {
+            agent
+                .preference_bundles()
+                .iter()
+                .filter(|bundle| {
+                    agent.preference(bundle) > 0.0
+                        && bundle.iter().all(|g| known.contains_key(&g.id))
+                        && agent.within_capacity(bundle, agent.bundle_units(bundle))
+                })
+                .map(move |bundle| (i, bundle))
+        })
+        .collect::<Vec<_>>()
+}
+
+/// Accept bids in order whenever the agent has no bundle yet and the goods fit;
+/// every agent ends up assigned (possibly the empty bundle)
+pub(crate) fn accept_in_order(
+    agents: &[Agent],
+    goods: &[Good],
+    bids: &[(usize, &Bundle)],
+) -> (Allocation, f64) {
+    let mut remaining: HashMap<GoodId, u32> = goods.iter().map(|g| (g.id, g.supply)).collect();
+    let mut allocation = Allocation::new();
+    let mut welfare = 0.0;
+    for &(i, bundle) in bids {
+        let agent = &agents[i];
+        if allocation.get_bundle(agent.id).is_some() {
+            continue;
+        }
+        let units = agent.bundle_units(bundle);
+        let fits = bundle.iter().all(|g| {
+            remaining.get(&g.id).copied().unwrap_or(0) >= unit_count(bundle, units, g.id)
+        });
+        if !fits {
+            continue;
+        }
+        for good in bundle {
+            if let Some(left) = remaining.get_mut(&good.id) {
+                *left -= unit_count(bundle, units, good.id);
+            }
+        }
+        allocation.assign_units(agent.id, bundle.clone(), units.cloned().unwrap_or_default());
+        welfare += agent.preference(bundle);
+    }
+    for agent in agents {
+        if allocation.get_bundle(agent.id).is_none() {
+            allocation.assign(agent.id, Bundle::new());
+        }
+    }
+    (allocation, welfare)
+}
+
+/// Solve the winner determination problem exactly.
+/// Each agent receives at most one of its preference bundles and no good is
+/// used beyond its supply. Runs a depth-first branch and bound, so it is only suitable
+/// for small instances. Returns the allocation and its total welfare.
+pub fn solve_exact(agents: &[Agent], goods: &[Good]) -> (Allocation, f64) {
+    let supply: HashMap<GoodId, u32> = goods.iter().map(|g| (g.id, g.supply)).collect();
+
+    // Candidate bundles per agent: known goods, positive value, within capacity
+    let mut candidates: Vec<Vec<Candidate>> = vec![Vec::new(); agents.len()];
+    for (i, bundle) in candidate_bids(agents, goods) {
+        let agent = &agents[i];
+        candidates[i].push((bundle, agent.bundle_units(bundle), agent.preference(bundle)));
+    }
+    for options in &mut candidates {
+        options.sort_by(|a, b| b.2.total_cmp(&a.2));
+    }
+
+    // Optimistic bound: every remaining agent gets its best bundle
+    let mut suffix_bound = vec![0.0; agents.len() + 1];
+    for i in (0..agents.len()).rev() {
+        let best = candidates[i].first().map(|(_, _, v)| *v).unwrap_or(0.0);
+        suffix_bound[i] = suffix_bound[i + 1] + best;
+    }
+
+    let mut search = Search {
+        candidates: &candidates,
+        suffix_bound: &suffix_bound,
+        supply: &supply,
+        used: HashMap::new(),
+        current: vec![None; agents.len()],
+        best: vec![None; agents.len()],
+        best_value: 0.0,
+    };
+    search.run(0, 0.0);
+
+    let mut allocation = Allocation::new();
+    for (i, agent) in agents.iter().enumerate() {
+        match search.best[i] {
+            Some(option) => {
+                let (bundle, units, _) = candidates[i][option];
+                allocation.assign_units(
+                    agent.id,
+                    bundle.clone(),
+                    units.cloned().unwrap_or_default(),
+                );
+            }
+            None => allocation.assign(agent.id, Bundle::new()),
+        }
+    }
+    (allocation, search.best_value)
+}
+
+/// Solve winner determination for any objective. Utilitarian uses branch
+/// and bound; the others search every feasible assignment, so they are only
+/// suitable for small instances. Returns the allocation and its objective value.
+pub fn solve_for_objective(
+    agents: &[Agent],
+    goods: &[Good],
+    objective: Objective,
+) -> (Allocation, f64) {
+    if objective == Objective::Utilitarian {
+        return solve_exact(agents, goods);
+    }
+
+    let (allocation, values) = search_all(agents, goods, |new, old| {
+        objective.score(new) > objective.score(old) + 1e-12
+    });
+    (allocation, objective.score(&values))
+}
+
+/// Search every feasible assignment for the one whose per-agent values
+/// (in agent order) are best under `better(new, old)`. Exponential: small instances only.
+pub(crate) fn search_all(
+    agents: &[Agent],
+    goods: &[Good],
+    better: impl Fn(&[f64], &[f64]) -> bool,
+) -> (Allocation, Vec<f64>) {
+    let supply: HashMap<GoodId, u32> = goods.iter().map(|g| (g.id, g.supply)).collect();
+    let mut options: Vec<Vec<&Bundle>> = vec![Vec::new(); agents.len()];
+    for (i, bundle) in candidate_bids(agents, goods) {
+        options[i].push(bundle);
+    }
+
+    let mut search = Exhaustive {
+        agents,
+        options: &options,
+        supply: &supply,
+        better: &better,
+        used: HashMap::new(),
+        current: vec![None; agents.len()],
+        best: vec![None; agents.len()],
+        best_values: None,
+    };
+    search.run(0);
+
+    let mut allocation = Allocation::new();
+    for (agent, bundle) in agents.iter().zip(&search.best) {
+        match bundle {
+            Some(bundle) => allocation.assign_units(
+                agent.id,
+                (*bundle).clone(),
+                agent.bundle_units(bundle).cloned().unwrap_or_default(),
+            ),
+            None => allocation.assign(agent.id, Bundle::new()),
+        }
+    }
+    (allocation, search.best_values.unwrap_or_default())
+}
+
+/// Most goods for which `welfare_upper_bound` solves exactly
+const EXACT_BOUND_GOODS: usize = 12;
+
+/// An upper bound on optimal welfare: the exact optimum for tiny
+/// unit-supply instances, the LP relaxation otherwise
+pub fn welfare_upper_bound(agents: &[Agent], goods: &[Good]) -> f64 {
+    if goods.len() <= EXACT_BOUND_GOODS {
+        if let Some((_, welfare)) = solve_dp(agents, goods) {
+            return welfare;
+        }
+    }
+    solve_lp_relaxation(agents, goods).welfare
+}
+
+/// Most goods `solve_dp` accepts
+pub const MAX_DP_GOODS: usize = 20;
+
+/// Solve winner determination exactly with a dynamic program over bitmasks
+/// of used goods: agents are added one at a time, each taking one of its bids
+/// disjoint from the goods already used. Runs in O(agents · bids · 2^goods)
+/// time; memory is two welfare tables of 2^goods entries (16 MB at 20 goods)
+/// plus one entry per agent and set of goods its bid improved.
+/// Returns None for more than `MAX_DP_GOODS` goods or goods with supply other than one.
+pub fn solve_dp(agents: &[Agent], goods: &[Good]) -> Option<(Allocation, f64)> {
+    if goods.len() > MAX_DP_GOODS || goods.iter().any(|g| g.supply != 1) {
+        return None;
+    }
+    let index = GoodIndex::new(goods);
+
+    // Bids as (mask, value, bundle); bids needing several units can never fit
+    let mut bids: Vec<Vec<(usize, f64, &Bundle)>> = vec![Vec::new(); agents.len()];
+    for (i, bundle) in candidate_bids(agents, goods) {
+        let units = agents[i].bundle_units(bundle);
+        if bundle.iter().any(|g| unit_count(bundle, units, g.id) > 1) {
+            continue;
+        }
+        let mask = bundle
+            .iter()
+            .filter_map(|g| index.index_of(g.id))
+            .fold(0usize, |mask, bit| mask | 1 << bit);
+        bids[i].push((mask, agents[i].preference(bundle), bundle));
+    }
+
+    // best[mask]: top welfare of the agents so far using exactly `mask`
+    let size = 1usize << goods.len();
+    let mut best = vec![f64::NEG_INFINITY; size];
+    best[0] = 0.0;
+    // choices[i][mask]: the bid agent i takes, kept only where it improved
+    let mut choices: Vec<HashMap<usize, usize>> = Vec::with_capacity(agents.len());
+    for agent_bids in &bids {
+        let mut next = best.clone();
+        let mut choice = HashMap::new();
+        for mask in (0..size).filter(|&mask| best[mask].is_finite()) {
+            for (k, &(bid_mask, value, _)) in agent_bids.iter().enumerate() {
+                if mask & bid_mask == 0 && best[mask] + value > next[mask | bid_mask] + 1e-12 {
+                    next[mask | bid_mask] = best[mask] + value;
+                    choice.insert(mask | bid_mask, k);
+                }
+            }
+        }
+        best = next;
+        choices.push(choice);
+    }
+
+    let (mut mask, welfare) = best
+        .iter()
+        .copied()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let mut allocation = Allocation::new();
+    for (i, agent) in agents.iter().enumerate().rev() {
+        match choices[i].get(&mask) {
+            Some(&k) => {
+                let (bid_mask, _, bundle) = bids[i][k];
+                allocation.assign(agent.id, bundle.clone());
+                mask ^= bid_mask;
+            }
+            None => allocation.assign(agent.id, Bundle::new()),
+        }
+    }
+    Some((allocation, welfare))
+}
+
+/// A preference bundle, its unit counts, and its value
+type Candidate<'a> = (&'a Bundle, Option<&'a Units>, f64);
+
+struct Search<'a> {
+    candidates: &'a [Vec<Candidate<'a>>],
+    suffix_bound: &'a [f64],
+    supply: &'a HashMap<GoodId, u32>,
+    used: HashMap<GoodId, u32>,
+    current: Vec<Option<usize>>,
+    best: Vec<Option<usize>>,
+    best_value: f64,
+}
+
+impl Search<'_> {
+    fn run(&mut self, agent: usize, value: f64) {
+        if value > self.best_value + 1e-12 {
+            self.best_value = value;
+            self.best = self.current.clone();
+        }
+        if agent == self.candidates.len()
+            || value + self.suffix_bound[agent] <= self.best_value + 1e-12
+        {
+            return;
+        }
+
+        for option in 0..self.candidates[agent].len() {
+            let (bundle, units, bundle_value) = self.candidates[agent][option];
+            let count = |good: &Good| units.and_then(|u| u.get(&good.id)).copied().unwrap_or(1);
+            let fits = bundle.iter().all(|g| {
+                let used = self.used.get(&g.id).copied().unwrap_or(0);
+                used + count(g) <= self.supply[&g.id]
+            });
+            if !fits {
+                continue;
+            }
+            for good in bundle {
+                *self.used.entry(good.id).or_insert(0) += count(good);
+            }
+            self.current[agent] = Some(option);
+            self.run(agent + 1, value + bundle_value);
+            self.current[agent] = None;
+            for good in bundle {
+                *self.used.entry(good.id).or_insert(0) -= count(good);
+            }
+        }
+
+        // This agent receives nothing
+        self.run(agent + 1, value);
+    }
+}
+
+/// Depth-first search over every feasible assignment
+struct Exhaustive<'a> {
+    agents: &'a [Agent],
+    options: &'a [Vec<&'a Bundle>],
+    supply: &'a HashMap<GoodId, u32>,
+    better: &'a dyn Fn(&[f64], &[f64]) -> bool,
+    used: HashMap<GoodId, u32>,
+    current: Vec<Option<&'a Bundle>>,
+    best: Vec<Option<&'a Bundle>>,
+    best_values: Option<Vec<f64>>,
+}
+
+impl<'a> Exhaustive<'a> {
+    fn run(&mut self, agent: usize) {
+        if agent == self.agents.len() {
+            let values: Vec<f64> = self
+                .current
+                .iter()
+                .zip(self.agents)
+                .map(|(bundle, a)| bundle.map_or(0.0, |b| a.preference(b)))
+                .collect();
+            if self.best_values.as_deref().is_none_or(|best| (self.better)(&values, best)) {
+                self.best_values = Some(values);
+                self.best = self.current.clone();
+            }
+            return;
+        }
+        for &bundle in &self.options[agent] {
+            let counts = self.agents[agent].bundle_units(bundle);
+            let count = |good: &Good| unit_count(bundle, counts, good.id);
+            let fits = bundle.iter().all(|g| {
+                let used = self.used.get(&g.id).copied().unwrap_or(0);
+                used + count(g) <= self.supply[&g.id]
+            });
+            if !fits {
+                continue;
+            }
+            for good in bundle {
+                *self.used.entry(good.id).or_insert(0) += count(good);
+            }
+            self.current[agent] = Some(bundle);
+            self.run(agent + 1);
+            self.current[agent] = None;
+            for good in bundle {
+                *self.used.entry(good.id).or_insert(0) -= count(good);
+            }
+        }
+
+        // This agent receives nothing
+        self.run(agent + 1);
+    }
+}
@@ -0,0 +1,58 @@
+use super::{accept_in_order, candidate_bids, WdpSolver};
+use crate::ids::GoodId;
+use crate::types::{unit_count, Agent, Allocation, Good};
+use coin_cbc::{Model, Sense};
+use std::collections::HashMap;
+
+/// Exact winner determination as a binary program solved by CBC
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CbcSolver;
+
+impl WdpSolver for CbcSolver {
+    fn solve(&self, agents: &[Agent], goods: &[Good]) -> (Allocation, f64) {
+        let bids = candidate_bids(agents, goods);
+        let mut model = Model::default();
+        model.set_parameter("log", "0");
+        model.set_obj_sense(Sense::Maximize);
+
+        let agent_rows: Vec<_> = agents
+            .iter()
+            .map(|_| {
+                let row = model.add_row();
+                model.set_row_upper(row, 1.0);
+                row
+            })
+            .collect();
+        let good_rows: HashMap<GoodId, _> = goods
+            .iter()
+            .map(|good| {
+                let row = model.add_row();
+                model.set_row_upper(row, good.supply as f64);
+                (good.id, row)
+            })
+            .collect();
+        let columns: Vec<_> = bids
+            .iter()
+            .map(|&(i, bundle)| {
+                let column = model.add_binary();
+                model.set_obj_coeff(column, agents[i].preference(bundle));
+                model.set_weight(agent_rows[i], column, 1.0);
+                let units = agents[i].bundle_units(bundle);
+                for good in bundle {
+                    let count = unit_count(bundle, units, good.id) as f64;
+                    model.set_weight(good_rows[&good.id], column, count);
+                }
+                column
+            })
+            .collect();
+
+        let solution = model.solve();
+        let chosen: Vec<_> = bids
+            .iter()
+            .zip(&columns)
+            .filter(|(_, &column)| solution.col(column) > 0.5)
+            .map(|(bid, _)| *bid)
+            .collect();
+        accept_in_order(agents, goods, &chosen)
+    }
+}
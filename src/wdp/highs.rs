@@ -0,0 +1,43 @@
+use super::{accept_in_order, candidate_bids, WdpSolver};
+use crate::ids::GoodId;
+use crate::types::{unit_count, Agent, Allocation, Good};
+use highs::{ColProblem, Sense};
+use std::collections::HashMap;
+
+/// Exact winner determination as a binary program solved by HiGHS
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HighsSolver;
+
+impl WdpSolver for HighsSolver {
+    fn solve(&self, agents: &[Agent], goods: &[Good]) -> (Allocation, f64) {
+        let bids = candidate_bids(agents, goods);
+        let mut problem = ColProblem::new();
+        let agent_rows: Vec<_> = agents.iter().map(|_| problem.add_row(..=1.0)).collect();
+        let good_rows: HashMap<GoodId, _> = goods
+            .iter()
+            .map(|good| (good.id, problem.add_row(..=good.supply as f64)))
+            .collect();
+        for &(i, bundle) in &bids {
+            let units = agents[i].bundle_units(bundle);
+            let factors: Vec<_> = std::iter::once((agent_rows[i], 1.0))
+                .chain(
+                    bundle
+                        .iter()
+                        .map(|g| (good_rows[&g.id], unit_count(bundle, units, g.id) as f64)),
+                )
+                .collect();
+            problem.add_integer_column(agents[i].preference(bundle), 0.0..=1.0, factors);
+        }
+
+        let mut model = problem.optimise(Sense::Maximise);
+        model.make_quiet();
+        let solution = model.solve().get_solution();
+        let chosen: Vec<_> = bids
+            .iter()
+            .zip(solution.columns())
+            .filter(|(_, &x)| x > 0.5)
+            .map(|(bid, _)| *bid)
+            .collect();
+        accept_in_order(agents, goods, &chosen)
+    }
+}
@@ -0,0 +1,41 @@
+use brace_sybil::analysis::{classify_valuation, ValuationClass};
+use brace_sybil::{bundle, Agent};
+
+#[test]
+fn test_classify_valuation() {
+    // Complements: the pair is worth more than its parts
+    let mut complements = Agent::new("Agent1", bundle![]);
+    complements.add_preference(bundle!["A"], 2.0);
+    complements.add_preference(bundle!["B"], 3.0);
+    complements.add_preference(bundle!["A", "B"], 10.0);
+    assert_eq!(
+        classify_valuation(&complements),
+        ValuationClass {
+            submodular: false,
+            superadditive: true,
+            unit_demand: false,
+        }
+    );
+
+    // Substitutes: the pair is worth only the better good
+    let mut substitutes = Agent::new("Agent2", bundle![]);
+    substitutes.add_preference(bundle!["A"], 4.0);
+    substitutes.add_preference(bundle!["B"], 3.0);
+    substitutes.add_preference(bundle!["A", "B"], 4.0);
+    assert_eq!(
+        classify_valuation(&substitutes),
+        ValuationClass {
+            submodular: true,
+            superadditive: false,
+            unit_demand: true,
+        }
+    );
+
+    // Additive values are both submodular and superadditive
+    let mut additive = Agent::new("Agent3", bundle![]);
+    additive.add_preference(bundle!["A"], 1.0);
+    additive.add_preference(bundle!["B"], 2.0);
+    additive.add_preference(bundle!["A", "B"], 3.0);
+    let class = classify_valuation(&additive);
+    assert!(class.submodular && class.superadditive && !class.unit_demand);
+}
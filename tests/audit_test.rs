@@ -0,0 +1,27 @@
+use brace_sybil::audit::{audit, audit_with, AuditConfig, Misreport};
+use brace_sybil::gen::random_instance;
+
+#[test]
+fn test_audit_reports_gains() {
+    let instance = random_instance(4, 3, 3, 7);
+    let report = audit(&instance.agents, &instance.goods, instance.epsilon);
+
+    assert_eq!(report.agents.len(), 3);
+    for entry in &report.agents {
+        assert!(entry.gain >= 0.0);
+        assert!(entry.best_utility >= entry.truthful_utility);
+        if entry.gain == 0.0 {
+            assert_eq!(entry.best_misreport, Misreport::Truthful);
+        }
+    }
+    let max = report.agents.iter().map(|e| e.gain).fold(0.0, f64::max);
+    assert_eq!(report.max_gain, max);
+
+    // With no misreports to try, nobody can gain
+    let config = AuditConfig {
+        scale_factors: Vec::new(),
+        drop_bundles: false,
+    };
+    let report = audit_with(&instance.agents, &instance.goods, instance.epsilon, &config);
+    assert_eq!(report.max_gain, 0.0);
+}
@@ -0,0 +1,112 @@
+use brace_sybil::{bundle, AgentId, Allocation, Bundle};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+const CONSTRAINED_TOML: &str = r#"
+epsilon = 0.01
+conflicts = [["A", "C"]]
+
+[[goods]]
+id = "A"
+
+[[goods]]
+id = "B"
+
+[[goods]]
+id = "C"
+
+[[agents]]
+id = "Carrier"
+endowment = ["A"]
+preferences = [
+    { goods = ["A", "C"], value = 10.0 },
+    { goods = ["A"], value = 1.0 },
+]
+
+[[agents]]
+id = "Holder"
+endowment = ["C"]
+max_goods = 1
+"#;
+
+/// Write `contents` to a temporary file unique to this test process
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("brace-cli-{}-{}", std::process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Run `brace-sybil verify` on the scenario and an allocation
+fn verify(scenario: &PathBuf, assignments: [(&str, Bundle); 2], name: &str) -> Value {
+    let mut allocation = Allocation::new();
+    for (agent, bundle) in assignments {
+        allocation.assign(AgentId::from(agent), bundle);
+    }
+    let path = temp_file(name, &serde_json::to_string(&allocation).unwrap());
+    let output = Command::new(env!("CARGO_BIN_EXE_brace-sybil"))
+        .arg("verify")
+        .arg(scenario)
+        .arg(&path)
+        .output()
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn test_verify_applies_scenario_constraints() {
+    let scenario = temp_file("constrained.toml", CONSTRAINED_TOML);
+
+    // Carrier holding both conflicting goods is what an unconstrained run would do
+    let conflicting = [("Carrier", bundle!["A", "C"]), ("Holder", Bundle::new())];
+    let report = verify(&scenario, conflicting, "conflicting.json");
+    assert_eq!(report["is_feasible"], false);
+
+    // Holder may receive one good only
+    let over_capacity = [("Carrier", Bundle::new()), ("Holder", bundle!["A", "C"])];
+    let report = verify(&scenario, over_capacity, "over-capacity.json");
+    assert_eq!(report["is_feasible"], false);
+
+    let endowments = [("Carrier", bundle!["A"]), ("Holder", bundle!["C"])];
+    let report = verify(&scenario, endowments, "endowments.json");
+    fs::remove_file(&scenario).unwrap();
+    assert_eq!(report["is_feasible"], true);
+    assert_eq!(report["is_individually_rational"], true);
+}
+
+#[test]
+fn test_solve_mechanism_flag() {
+    let constrained = temp_file("mechanism-constrained.toml", CONSTRAINED_TOML);
+    let free = CONSTRAINED_TOML.replace("conflicts = [[\"A\", \"C\"]]\n", "");
+    let unconstrained = temp_file("mechanism.toml", &free);
+    let solve = |scenario: &PathBuf, mechanism: &str| {
+        Command::new(env!("CARGO_BIN_EXE_brace-sybil"))
+            .args(["solve", "--mechanism", mechanism])
+            .arg(scenario)
+            .output()
+            .unwrap()
+    };
+
+    // Greedy pools the endowments
+    let output = solve(&unconstrained, "greedy");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let result: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["total_welfare"], 10.0);
+    assert_eq!(result["is_feasible"], true);
+    assert!(solve(&unconstrained, "rsd:1").status.success());
+
+    // Settings the mechanism would ignore are errors, not infeasible results
+    let conflicts = solve(&constrained, "greedy");
+    let capacity = solve(&unconstrained, "ttc");
+    let bogus = solve(&unconstrained, "bogus");
+    fs::remove_file(&constrained).unwrap();
+    fs::remove_file(&unconstrained).unwrap();
+    assert!(!conflicts.status.success());
+    assert!(String::from_utf8_lossy(&conflicts.stderr).contains("constraints"));
+    assert!(!capacity.status.success());
+    assert!(String::from_utf8_lossy(&capacity.stderr).contains("capacities"));
+    assert!(!bogus.status.success());
+}
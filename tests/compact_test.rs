@@ -0,0 +1,30 @@
+use brace_sybil::compact::{CompactBundle, GoodIndex};
+use brace_sybil::{Bundle, Good};
+
+#[test]
+fn test_compact_bundle_set_operations() {
+    let goods: Vec<Good> = (0..100)
+        .map(|i| Good::new(format!("G{}", i), format!("Good {}", i)))
+        .collect();
+    let index = GoodIndex::new(&goods);
+
+    let first: Bundle = [goods[1].clone(), goods[70].clone()].into_iter().collect();
+    let second: Bundle = [goods[70].clone(), goods[99].clone()].into_iter().collect();
+    let a = index.compact(&first);
+    let b = index.compact(&second);
+
+    assert_eq!(a.len(), 2);
+    assert!(a.contains(70) && !a.contains(99));
+    assert!(!a.is_disjoint(&b));
+    assert_eq!(a.union(&b).len(), 3);
+    assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), vec![70]);
+    assert!(a.intersection(&b).is_subset(&a));
+    assert_eq!(index.expand(&a, &goods), first);
+
+    // Equal sets hash and compare equal regardless of how they were built
+    let mut c = a.union(&b).difference(&b);
+    c.insert(1);
+    assert_eq!(c, index.compact(&[goods[1].clone()].into_iter().collect()));
+    c.remove(1);
+    assert_eq!(c, CompactBundle::new());
+}
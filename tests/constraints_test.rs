@@ -0,0 +1,151 @@
+use brace_sybil::scenario::Scenario;
+use brace_sybil::{
+    bundle, Agent, Allocation, AuctionError, AuctionInstance, BRACEMechanism, CategoryRule,
+    CombinatorialAuction, Constraints, Good, Improvement, Violation,
+};
+
+fn instance() -> AuctionInstance {
+    let goods = vec![
+        Good::new("A", "License A"),
+        Good::new("B", "License B"),
+        Good::new("C", "License C"),
+    ];
+    let agents = vec![
+        Agent::builder("Carrier")
+            .endow(["A"])
+            .prefer(["A", "C"], 10.0)
+            .prefer(["A"], 1.0)
+            .build(&goods)
+            .unwrap(),
+        Agent::builder("Holder").endow(["C"]).build(&goods).unwrap(),
+    ];
+    AuctionInstance::new(agents, goods, 0.01)
+}
+
+#[test]
+fn test_conflicting_goods_are_not_traded_together() {
+    // Holder is indifferent to giving C away, so this needs weak improvement
+    let weak = |instance| {
+        CombinatorialAuction::from_instance(instance)
+            .unwrap()
+            .with_improvement(Improvement::Weak)
+            .run()
+            .unwrap()
+    };
+    let unconstrained = weak(instance());
+    assert_eq!(
+        unconstrained.allocation.get_bundle("Carrier"),
+        Some(&bundle!["A", "C"])
+    );
+
+    let constrained = instance().with_constraints(Constraints::new().with_conflict(["A", "C"]));
+    let result = weak(constrained);
+    assert_eq!(result.allocation.get_bundle("Carrier"), Some(&bundle!["A"]));
+    assert!(result.is_feasible);
+}
+
+#[test]
+fn test_feasibility_checks_conflicts() {
+    let constraints = Constraints::new().with_conflict(["A", "B", "C"]);
+    let goods = instance().goods;
+    assert!(constraints.permits("Carrier".into(), &bundle!["A"], &goods));
+    assert!(!constraints.permits("Carrier".into(), &bundle!["B", "C"], &goods));
+
+    let mut allocation = Allocation::new();
+    allocation.assign("Carrier", bundle!["A", "B"]);
+    let mechanism = BRACEMechanism::new(0.01);
+    assert!(mechanism.verify_feasibility(&allocation, &goods));
+    assert!(!mechanism
+        .with_constraints(constraints)
+        .verify_feasibility(&allocation, &goods));
+}
+
+#[test]
+fn test_constraints_reject_unknown_goods() {
+    let constrained = instance().with_constraints(Constraints::new().with_conflict(["A", "Z"]));
+    assert_eq!(
+        constrained.validate(),
+        Err(vec![Violation::UnknownConstrainedGood("Z".into())])
+    );
+    assert!(matches!(
+        CombinatorialAuction::from_instance(constrained),
+        Err(AuctionError::UnknownConstrainedGood(good)) if good.as_str() == "Z"
+    ));
+}
+
+#[test]
+fn test_constraints_round_trip() {
+    let constrained = instance().with_constraints(Constraints::new().with_conflict(["A", "C"]));
+    let restored = AuctionInstance::from_json(&constrained.to_json().unwrap()).unwrap();
+    assert_eq!(restored.constraints, constrained.constraints);
+
+    // Instances without constraints keep their old JSON shape
+    assert!(!instance().to_json().unwrap().contains("constraints"));
+}
+
+#[test]
+fn test_category_limits() {
+    let mut banded = instance();
+    for good in &mut banded.goods {
+        if good.id != "B" {
+            *good = good.clone().with_category("band");
+        }
+    }
+    let rule = CategoryRule::new("band").with_max_per_agent(1);
+    let constrained = banded.clone().with_constraints(Constraints::new().with_category_rule(rule));
+    let result = CombinatorialAuction::from_instance(constrained).unwrap().run().unwrap();
+    assert_eq!(result.allocation.get_bundle("Carrier"), Some(&bundle!["A"]));
+    assert!(result.is_feasible);
+
+    // Goods outside the category are unaffected
+    let rules = Constraints::new().with_category_rule(CategoryRule::new("band").with_max_per_agent(1));
+    assert!(rules.permits("Carrier".into(), &bundle!["A", "B"], &banded.goods));
+    assert!(!rules.permits("Carrier".into(), &bundle!["A", "C"], &banded.goods));
+}
+
+#[test]
+fn test_category_eligibility() {
+    let mut banded = instance();
+    banded.goods[1] = banded.goods[1].clone().with_category("reserved");
+    let rules = Constraints::new()
+        .with_category_rule(CategoryRule::new("reserved").with_eligible(["Holder"]));
+
+    let mut allocation = Allocation::new();
+    allocation.assign("Holder", bundle!["B"]);
+    let mechanism = BRACEMechanism::new(0.01).with_constraints(rules);
+    assert!(mechanism.verify_feasibility(&allocation, &banded.goods));
+    allocation.assign("Carrier", bundle!["A", "B"]);
+    allocation.assign("Holder", bundle![]);
+    assert!(!mechanism.verify_feasibility(&allocation, &banded.goods));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_scenario_conflicts() {
+    let scenario = Scenario::from_toml(
+        r#"
+epsilon = 0.01
+conflicts = [["A", "B"]]
+categories = [{ category = "band", max_per_agent = 1, eligible = ["Agent1"] }]
+
+[[goods]]
+id = "A"
+
+[[goods]]
+id = "B"
+category = "band"
+
+[[agents]]
+id = "Agent1"
+"#,
+    )
+    .unwrap();
+    let instance = scenario.to_instance().unwrap();
+    let expected = Constraints::new().with_conflict(["A", "B"]).with_category_rule(
+        CategoryRule::new("band")
+            .with_max_per_agent(1)
+            .with_eligible(["Agent1"]),
+    );
+    assert_eq!(instance.constraints, expected);
+    assert_eq!(instance.goods[1].category, Some("band".into()));
+}
@@ -0,0 +1,117 @@
+use brace_sybil::elicit::{Elicitor, Respondent};
+use brace_sybil::gen::{generate, GeneratorConfig};
+use brace_sybil::pricing::{max_ce_violation, PriceVector};
+use brace_sybil::{bundle, Agent, AgentId, BRACEMechanism, Bundle, Good, Improvement};
+
+#[test]
+fn test_truthful_agents_need_partial_revelation() {
+    let config = GeneratorConfig {
+        num_goods: 6,
+        num_agents: 4,
+        bundles_per_agent: 12,
+        ..GeneratorConfig::default()
+    };
+    let instance = generate(&config, 0);
+    let listed: usize = instance.agents.iter().map(|a| a.preference_bundles().len()).sum();
+
+    let mut respondents = instance.agents.clone();
+    let elicitor = Elicitor::new(BRACEMechanism::new(instance.epsilon));
+    let outcome = elicitor.run(&mut respondents, &instance.goods);
+    assert!(outcome.certified);
+    assert!(outcome.value_queries < listed);
+    // The certificate holds for the full preferences
+    let violation = max_ce_violation(&outcome.prices, &instance.agents, &outcome.allocation);
+    assert!(violation <= instance.epsilon);
+    // The last round asked every agent for its demand
+    assert!(outcome.demand_queries >= instance.agents.len());
+
+    // Learned values are the true ones
+    for (learned, truth) in outcome.agents.iter().zip(&instance.agents) {
+        for (bundle, value) in learned.preferences() {
+            assert_eq!(value, truth.preference(bundle));
+        }
+    }
+    let mechanism = BRACEMechanism::new(instance.epsilon);
+    assert!(mechanism.verify_feasibility(&outcome.allocation, &instance.goods));
+    assert!(mechanism.verify_individual_rationality(&instance.agents, &outcome.allocation));
+}
+
+/// Wants any one of its goods, worth their listed value; never lists a bundle
+struct UnitDemand {
+    id: AgentId,
+    values: Vec<(Good, f64)>,
+    queries: usize,
+}
+
+impl Respondent for UnitDemand {
+    fn id(&self) -> AgentId {
+        self.id
+    }
+
+    fn endowment(&self) -> Bundle {
+        Bundle::new()
+    }
+
+    fn value(&mut self, bundle: &Bundle) -> f64 {
+        self.queries += 1;
+        self.values
+            .iter()
+            .filter(|(good, _)| bundle.contains(good))
+            .map(|(_, value)| *value)
+            .fold(0.0, f64::max)
+    }
+
+    fn demand(&mut self, prices: &PriceVector) -> Bundle {
+        self.queries += 1;
+        self.values
+            .iter()
+            .map(|(good, value)| (good, value - prices.get_price(good.id)))
+            .filter(|(_, surplus)| *surplus > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(good, _)| Bundle::from([good.clone()]))
+            .unwrap_or_default()
+    }
+}
+
+#[test]
+fn test_custom_respondents() {
+    let goods: Vec<Good> = ["A", "B"].iter().map(|id| Good::new(*id, *id)).collect();
+    let bidder = |id: &str, a: f64, b: f64| {
+        Box::new(UnitDemand {
+            id: id.into(),
+            values: vec![(goods[0].clone(), a), (goods[1].clone(), b)],
+            queries: 0,
+        }) as Box<dyn Respondent>
+    };
+    let mut respondents = vec![
+        bidder("Bidder1", 10.0, 4.0),
+        bidder("Bidder2", 8.0, 7.0),
+        Box::new(Agent::new("Seller", bundle!["A", "B"])),
+    ];
+
+    // The seller is indifferent to its goods, so it needs weak improvement to hand them out
+    let mechanism = BRACEMechanism::new(0.01).with_improvement(Improvement::Weak);
+    let outcome = Elicitor::new(mechanism).run(&mut respondents, &goods);
+    assert!(outcome.rounds >= 2);
+    assert_eq!(outcome.agents.len(), 3);
+    assert!(outcome.value_queries > 0);
+    let holder = |good: &str| {
+        outcome
+            .allocation
+            .iter()
+            .find(|(_, bundle)| bundle.iter().any(|g| g.id == good))
+            .map(|(id, _)| id.as_str())
+    };
+    assert_eq!(holder("A"), Some("Bidder1"));
+    assert_eq!(holder("B"), Some("Bidder2"));
+}
+
+#[test]
+fn test_round_cap() {
+    let instance = generate(&GeneratorConfig::default(), 1);
+    let mut respondents = instance.agents.clone();
+    let outcome = Elicitor::new(BRACEMechanism::new(instance.epsilon))
+        .with_max_rounds(1)
+        .run(&mut respondents, &instance.goods);
+    assert_eq!(outcome.rounds, 1);
+}
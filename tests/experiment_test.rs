@@ -0,0 +1,25 @@
+use brace_sybil::experiment::Experiment;
+use brace_sybil::gen::{generate, GeneratorConfig};
+
+#[test]
+fn test_run_many_aggregates() {
+    let config = GeneratorConfig::default();
+    let summary = Experiment::run_many(|seed| generate(&config, seed), 12, 100);
+
+    assert_eq!(summary.instances, 12);
+    assert_eq!(summary.failures, 0);
+    let seeds: Vec<u64> = summary.runs.iter().map(|run| run.seed).collect();
+    assert_eq!(seeds, (100..112).collect::<Vec<_>>());
+    assert_eq!(summary.individually_rational_fraction, 1.0);
+    assert!(summary.mean_iterations >= 1.0);
+
+    // Same seeds give the same outcomes
+    let again = Experiment::run_many(|seed| generate(&config, seed), 12, 100);
+    assert_eq!(again.mean_welfare, summary.mean_welfare);
+
+    let csv = summary.to_csv();
+    assert_eq!(csv.lines().count(), 13);
+    assert!(csv.starts_with("seed,total_welfare"));
+    let json = serde_json::to_string(&summary).unwrap();
+    assert!(json.contains("\"mean_welfare\""));
+}
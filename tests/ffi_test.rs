@@ -0,0 +1,49 @@
+use brace_sybil::auction;
+use brace_sybil::ffi::{
+    brace_solve, brace_string_free, BRACE_AUCTION_ERROR, BRACE_INVALID_ARGUMENT, BRACE_OK,
+};
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+/// Call `brace_solve` and take ownership of its output
+fn solve(input: &CStr) -> (i32, Option<String>) {
+    let mut out: *mut c_char = ptr::null_mut();
+    let status = unsafe { brace_solve(input.as_ptr(), &mut out) };
+    if out.is_null() {
+        return (status, None);
+    }
+    let text = unsafe { CStr::from_ptr(out) }.to_str().unwrap().to_string();
+    unsafe { brace_string_free(out) };
+    (status, Some(text))
+}
+
+#[test]
+fn test_brace_solve() {
+    let instance = auction! {
+        goods: ["A", "B"],
+        agents: [
+            "Agent1" => { endow: ["A"], prefer: [["B"] => 10.0, ["A"] => 5.0] },
+            "Agent2" => { endow: ["B"], prefer: [["A"] => 10.0, ["B"] => 5.0] },
+        ],
+        epsilon: 0.01,
+    };
+    let input = CString::new(instance.to_json().unwrap()).unwrap();
+
+    let (status, output) = solve(&input);
+    assert_eq!(status, BRACE_OK);
+    let result: serde_json::Value = serde_json::from_str(&output.unwrap()).unwrap();
+    assert_eq!(result["total_welfare"], 20.0);
+}
+
+#[test]
+fn test_brace_solve_errors() {
+    let (status, output) = solve(c"not json");
+    assert_eq!(status, BRACE_AUCTION_ERROR);
+    let error: serde_json::Value = serde_json::from_str(&output.unwrap()).unwrap();
+    assert!(error["error"].as_str().unwrap().contains("JSON"));
+
+    let mut out: *mut c_char = ptr::null_mut();
+    assert_eq!(unsafe { brace_solve(ptr::null(), &mut out) }, BRACE_INVALID_ARGUMENT);
+    assert!(out.is_null());
+    unsafe { brace_string_free(ptr::null_mut()) };
+}
@@ -0,0 +1,245 @@
+use brace_sybil::gen::{
+    generate, generate_cats, generate_with, CatsConfig, CatsDistribution, EndowmentScheme,
+    GeneratorConfig, ValueDistribution, ValueModel,
+};
+use brace_sybil::{Bundle, CombinatorialAuction, Good};
+use rand::rngs::StdRng;
+use std::collections::HashSet;
+
+#[test]
+fn test_generator_is_seeded() {
+    let config = GeneratorConfig {
+        num_goods: 8,
+        num_agents: 4,
+        endowments: EndowmentScheme::Random,
+        values: ValueDistribution::Normal {
+            mean: 5.0,
+            std_dev: 2.0,
+        },
+        ..GeneratorConfig::default()
+    };
+
+    let first = generate(&config, 42);
+    let second = generate(&config, 42);
+    for (a, b) in first.agents.iter().zip(&second.agents) {
+        assert_eq!(a.endowment, b.endowment);
+        assert_eq!(a.preference_bundles(), b.preference_bundles());
+        for bundle in a.preference_bundles() {
+            assert_eq!(a.preference(bundle), b.preference(bundle));
+        }
+    }
+}
+
+#[test]
+fn test_generator_respects_config() {
+    let config = GeneratorConfig {
+        num_goods: 6,
+        num_agents: 3,
+        bundles_per_agent: 5,
+        max_bundle_size: 2,
+        values: ValueDistribution::Additive {
+            min: 1.0,
+            max: 2.0,
+            complementarity: 0.5,
+        },
+        ..GeneratorConfig::default()
+    };
+    let instance = generate(&config, 7);
+    assert_eq!(instance.goods.len(), 6);
+    assert_eq!(instance.agents.len(), 3);
+
+    // Round-robin endowments partition the goods
+    let mut owned = HashSet::new();
+    for agent in &instance.agents {
+        assert_eq!(agent.endowment.len(), 2);
+        for good in &agent.endowment {
+            assert!(owned.insert(good.id));
+        }
+        // Endowment bundle plus the random bundles
+        assert_eq!(agent.preference_bundles().len(), 6);
+        for bundle in &agent.preference_bundles()[1..] {
+            assert!(bundle.len() <= 2);
+            assert!(agent.preference(bundle) >= 1.0);
+        }
+    }
+}
+
+#[test]
+fn test_cats_distributions() {
+    let distributions = [
+        CatsDistribution::Paths { budget_factor: 1.5 },
+        CatsDistribution::Regions { additivity: 0.2 },
+        CatsDistribution::Arbitrary { additivity: 0.2 },
+        CatsDistribution::Scheduling,
+    ];
+    for distribution in distributions {
+        let config = CatsConfig {
+            distribution,
+            ..CatsConfig::default()
+        };
+        let instance = generate_cats(&config, 3);
+        assert_eq!(instance.goods.len(), 16);
+        assert_eq!(instance.agents.len(), 5);
+        let mut bids = 0;
+        for agent in &instance.agents {
+            assert!(agent.endowment.is_empty());
+            assert!(agent.preference_bundles().len() <= 3);
+            for bundle in agent.preference_bundles() {
+                assert!(!bundle.is_empty() && bundle.len() <= 4);
+                assert!(agent.preference(bundle) > 0.0);
+                bids += 1;
+            }
+        }
+        assert!(bids > 0, "{:?} generated no bids", config.distribution);
+
+        let again = generate_cats(&config, 3);
+        for (a, b) in instance.agents.iter().zip(&again.agents) {
+            assert_eq!(a.preference_bundles(), b.preference_bundles());
+        }
+        assert!(CombinatorialAuction::from_instance(instance).unwrap().run().is_ok());
+    }
+}
+
+#[test]
+fn test_cats_structure() {
+    // Scheduling bundles are consecutive slots of one job length per agent
+    let config = CatsConfig {
+        distribution: CatsDistribution::Scheduling,
+        ..CatsConfig::default()
+    };
+    let instance = generate_cats(&config, 11);
+    for agent in &instance.agents {
+        let lengths: HashSet<usize> = agent.preference_bundles().iter().map(|b| b.len()).collect();
+        assert!(lengths.len() <= 1);
+        let mut values = Vec::new();
+        for bundle in agent.preference_bundles() {
+            let mut slots: Vec<usize> = bundle
+                .iter()
+                .map(|good| good.id.as_str()[1..].parse().unwrap())
+                .collect();
+            slots.sort_unstable();
+            assert!(slots.windows(2).all(|w| w[1] == w[0] + 1));
+            values.push(agent.preference(bundle));
+        }
+        // Later deadlines are worth less
+        assert!(values.windows(2).all(|w| w[1] < w[0]));
+    }
+
+    // Region bundles are connected on the grid
+    let config = CatsConfig::default();
+    let instance = generate_cats(&config, 11);
+    for agent in &instance.agents {
+        for bundle in agent.preference_bundles() {
+            let cells: Vec<(i64, i64)> = bundle
+                .iter()
+                .map(|good| {
+                    let i: i64 = good.id.as_str()[1..].parse().unwrap();
+                    (i % 4, i / 4)
+                })
+                .collect();
+            let mut reached = vec![cells[0]];
+            let mut grew = true;
+            while grew {
+                grew = false;
+                for &cell in &cells {
+                    let near = reached
+                        .iter()
+                        .any(|r| (r.0 - cell.0).abs() + (r.1 - cell.1).abs() == 1);
+                    if !reached.contains(&cell) && near {
+                        reached.push(cell);
+                        grew = true;
+                    }
+                }
+            }
+            assert_eq!(reached.len(), cells.len());
+        }
+    }
+}
+
+/// Each agent's value for every single good it bid on, by good
+fn quotes(config: &GeneratorConfig, seed: u64) -> Vec<Vec<f64>> {
+    let instance = generate(config, seed);
+    instance
+        .goods
+        .iter()
+        .map(|good| {
+            let bundle = Bundle::from([good.clone()]);
+            instance
+                .agents
+                .iter()
+                .filter(|agent| agent.preference_bundles().contains(&bundle))
+                .map(|agent| agent.preference(&bundle))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_correlated_and_common_values() {
+    // Single-good bids only, so each quote is an agent's value for one good
+    let shared = |values| GeneratorConfig {
+        num_goods: 4,
+        num_agents: 3,
+        bundles_per_agent: 4,
+        max_bundle_size: 1,
+        value_endowment: false,
+        values,
+        ..GeneratorConfig::default()
+    };
+    for values in [
+        ValueDistribution::Correlated {
+            mean: 5.0,
+            std_dev: 2.0,
+            correlation: 1.0,
+        },
+        ValueDistribution::CommonValue {
+            min: 1.0,
+            max: 10.0,
+            noise: 0.0,
+        },
+    ] {
+        // Perfect correlation or exact estimates: everybody agrees on every good
+        for good in quotes(&shared(values), 9) {
+            assert!(good.windows(2).all(|w| (w[0] - w[1]).abs() < 1e-12));
+        }
+    }
+
+    // Independent values and noisy estimates disagree
+    for values in [
+        ValueDistribution::Correlated {
+            mean: 5.0,
+            std_dev: 2.0,
+            correlation: 0.0,
+        },
+        ValueDistribution::CommonValue {
+            min: 1.0,
+            max: 10.0,
+            noise: 2.0,
+        },
+    ] {
+        let goods = quotes(&shared(values), 9);
+        assert!(goods
+            .iter()
+            .any(|good| good.windows(2).any(|w| (w[0] - w[1]).abs() > 1e-9)));
+    }
+}
+
+/// Values every bundle at the number of goods in it
+struct CountGoods;
+
+impl ValueModel for CountGoods {
+    fn value(&mut self, _rng: &mut StdRng, _goods: &[Good], bundle: &Bundle) -> f64 {
+        bundle.len() as f64
+    }
+}
+
+#[test]
+fn test_pluggable_value_model() {
+    let config = GeneratorConfig::default();
+    let instance = generate_with(&config, &mut CountGoods, 5);
+    for agent in &instance.agents {
+        for bundle in agent.preference_bundles() {
+            assert_eq!(agent.preference(bundle), bundle.len() as f64);
+        }
+    }
+}
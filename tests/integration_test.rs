@@ -1,17 +1,19 @@
-use brace_sybil::{CombinatorialAuction, Agent, Good};
+use brace_sybil::ids::Interner;
+use brace_sybil::pricing::PriceAdjustmentConfig;
+use brace_sybil::{auction, bundle};
+use brace_sybil::{
+    Agent, AgentId, Allocation, AuctionError, AuctionInstance, AuctionObserver, BRACEMechanism,
+    BidLanguage, Bundle, Category, CombinatorialAuction, Flow, Good, GoodId, Improvement,
+    NonConvergence, Objective, OrBid, OrOfXorBid, PriceTrajectory, PriceVector, TieBreaker,
+    Violation, XorBid,
+};
 use std::collections::HashSet;
 
 #[test]
 fn test_simple_auction() {
     // Create two goods
-    let good_a = Good {
-        id: "A".to_string(),
-        name: "Good A".to_string(),
-    };
-    let good_b = Good {
-        id: "B".to_string(),
-        name: "Good B".to_string(),
-    };
+    let good_a = Good::new("A", "Good A");
+    let good_b = Good::new("B", "Good B");
     let goods = vec![good_a.clone(), good_b.clone()];
 
     // Create two agents
@@ -48,8 +50,8 @@ fn test_simple_auction() {
     let agents = vec![agent1, agent2];
 
     // Run auction
-    let auction = CombinatorialAuction::new(agents, goods, 0.01);
-    let result = auction.run();
+    let auction = CombinatorialAuction::new(agents, goods, 0.01).unwrap();
+    let result = auction.run().unwrap();
 
     // Verify properties
     assert!(result.is_feasible, "Allocation should be feasible");
@@ -63,15 +65,12 @@ fn test_simple_auction() {
     );
 
     // Verify all agents have allocations
-    assert_eq!(result.allocation.assignments.len(), 2);
+    assert_eq!(result.allocation.assignments().len(), 2);
 }
 
 #[test]
 fn test_individual_rationality() {
-    let good_a = Good {
-        id: "A".to_string(),
-        name: "Good A".to_string(),
-    };
+    let good_a = Good::new("A", "Good A");
     let goods = vec![good_a.clone()];
 
     let mut agent = Agent::new("Agent1".to_string(), {
@@ -85,11 +84,1245 @@ fn test_individual_rationality() {
     agent.add_preference(bundle_a, 5.0);
 
     let agents = vec![agent];
-    let auction = CombinatorialAuction::new(agents, goods, 0.01);
-    let result = auction.run();
+    let auction = CombinatorialAuction::new(agents, goods, 0.01).unwrap();
+    let result = auction.run().unwrap();
 
     // Agent should keep their endowment (individual rationality)
     assert!(result.is_individually_rational);
     assert!(result.allocation.get_bundle("Agent1").is_some());
 }
 
+
+#[test]
+fn test_xor_bid_valuation() {
+    let good_a = Good::new("A", "Good A");
+    let good_b = Good::new("B", "Good B");
+    let good_c = Good::new("C", "Good C");
+
+    let bundle_a: HashSet<Good> = [good_a.clone()].into_iter().collect();
+    let bundle_bc: HashSet<Good> = [good_b.clone(), good_c.clone()].into_iter().collect();
+    let bid = XorBid::new().with(bundle_a.clone(), 5.0).with(bundle_bc.clone(), 8.0);
+
+    let mut agent = Agent::new("Agent1".to_string(), HashSet::new());
+    agent.set_xor_bid(bid);
+
+    // Bundles containing an atomic bid take the best contained atom
+    let abc: HashSet<Good> = [good_a.clone(), good_b.clone(), good_c.clone()]
+        .into_iter()
+        .collect();
+    assert_eq!(agent.preference(&abc), 8.0);
+    let ab: HashSet<Good> = [good_a.clone(), good_b.clone()].into_iter().collect();
+    assert_eq!(agent.preference(&ab), 5.0);
+    let b: HashSet<Good> = [good_b.clone()].into_iter().collect();
+    assert_eq!(agent.preference(&b), 0.0);
+
+    // Atomic bundles are available for demand computation
+    assert_eq!(agent.preference_bundles().len(), 2);
+}
+
+#[test]
+fn test_or_and_or_of_xor_valuations() {
+    let goods: Vec<Good> = ["A", "B", "C"]
+        .iter()
+        .map(|id| Good::new(*id, format!("Good {}", id)))
+        .collect();
+    let single = |i: usize| -> HashSet<Good> { [goods[i].clone()].into_iter().collect() };
+    let all: HashSet<Good> = goods.iter().cloned().collect();
+
+    // OR of singletons is additive
+    let additive = OrBid::new()
+        .with(single(0), 1.0)
+        .with(single(1), 2.0)
+        .with(single(2), 4.0);
+    assert_eq!(additive.value(&all), 7.0);
+
+    // One atom from each clause, atoms must be disjoint
+    let ab: HashSet<Good> = [goods[0].clone(), goods[1].clone()].into_iter().collect();
+    let bid = OrOfXorBid::new()
+        .with(XorBid::new().with(ab.clone(), 10.0).with(single(0), 3.0))
+        .with(XorBid::new().with(single(1), 4.0).with(single(2), 5.0));
+    assert_eq!(bid.value(&all), 15.0);
+    assert_eq!(bid.value(&ab), 10.0);
+
+    let mut agent = Agent::new("Agent1".to_string(), HashSet::new());
+    agent.set_valuation(bid);
+    assert_eq!(agent.preference(&all), 15.0);
+}
+
+#[test]
+fn test_large_additive_or_bid() {
+    let goods: Vec<Good> = (0..40).map(|i| Good::new(format!("G{}", i), "")).collect();
+    let all: HashSet<Good> = goods.iter().cloned().collect();
+    let mut bid = OrBid::new();
+    for (i, good) in goods.iter().enumerate() {
+        bid.add([good.clone()].into_iter().collect(), (i + 1) as f64);
+    }
+    // Additive: no search over 2^40 subsets
+    assert_eq!(bid.value(&all), 820.0);
+
+    // A pair overlapping two singletons is still weighed against them
+    let pair: HashSet<Good> = goods[..2].iter().cloned().collect();
+    bid.add(pair, 5.0);
+    assert_eq!(bid.value(&all), 822.0);
+    let first: HashSet<Good> = goods[..1].iter().cloned().collect();
+    assert_eq!(bid.value(&first), 1.0);
+}
+
+#[test]
+fn test_multi_unit_feasibility() {
+    let good_a = Good::new("A", "Good A").with_supply(3);
+    let goods = vec![good_a.clone()];
+    let bundle_a: HashSet<Good> = [good_a.clone()].into_iter().collect();
+
+    let mut agent1 = Agent::new("Agent1".to_string(), bundle_a.clone());
+    agent1.endowment_units = [(good_a.id, 2)].into_iter().collect();
+    agent1.add_multi_unit_preference(
+        bundle_a.clone(),
+        [(good_a.id, 2)].into_iter().collect(),
+        6.0,
+    );
+    let mut agent2 = Agent::new("Agent2".to_string(), bundle_a.clone());
+    agent2.add_preference(bundle_a.clone(), 4.0);
+
+    let auction = CombinatorialAuction::new(vec![agent1, agent2], goods.clone(), 0.01).unwrap();
+    let result = auction.run().unwrap();
+    assert!(result.is_feasible);
+    assert_eq!(result.allocation.unit_count("Agent1", "A"), 2);
+    assert_eq!(result.allocation.unit_count("Agent2", "A"), 1);
+
+    // Four units of a three-unit good is over-allocated
+    let mut allocation = result.allocation.clone();
+    allocation.assign_units(
+        "Agent2".to_string(),
+        bundle_a,
+        [(good_a.id, 2)].into_iter().collect(),
+    );
+    let mechanism = BRACEMechanism::new(0.01);
+    assert!(!mechanism.verify_feasibility(&allocation, &goods));
+}
+
+#[test]
+fn test_fractional_allocation() {
+    let good_a = Good::new("A", "Good A");
+    let good_b = Good::new("B", "Good B");
+    let goods = vec![good_a.clone(), good_b.clone()];
+    let bundle_a: HashSet<Good> = [good_a.clone()].into_iter().collect();
+    let bundle_b: HashSet<Good> = [good_b.clone()].into_iter().collect();
+
+    let mut agent1 = Agent::new("Agent1".to_string(), bundle_a.clone());
+    agent1.add_preference(bundle_b.clone(), 5.0);
+    agent1.add_preference(bundle_a.clone(), 1.0);
+    let mut agent2 = Agent::new("Agent2".to_string(), bundle_b.clone());
+    agent2.add_preference(bundle_a.clone(), 5.0);
+    agent2.add_preference(bundle_b.clone(), 1.0);
+    let agents = vec![agent1, agent2];
+
+    let mechanism = BRACEMechanism::new(0.01);
+    let fractional = mechanism.compute_fractional_allocation(&agents, &goods, 8, 3);
+
+    for agent in &agents {
+        let total: f64 = fractional.get_shares(agent.id).iter().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+    // Every sampled order finds the mutually beneficial swap
+    assert!((fractional.good_share("Agent1", "B") - 1.0).abs() < 1e-9);
+    assert!((fractional.expected_value(&agents[1]) - 5.0).abs() < 1e-9);
+    assert!(mechanism.verify_fractional_feasibility(&fractional, &goods));
+}
+
+#[test]
+fn test_interned_ids() {
+    let first = GoodId::from("Interned");
+    let second = GoodId::new("Interned");
+    assert_eq!(first, second);
+    assert_eq!(first.as_str(), "Interned");
+    assert_eq!(GoodId::lookup("Interned"), Some(first));
+    assert_eq!(first.to_string(), "Interned");
+    assert_eq!(format!("{}/{:?}", AgentId::from("Agent1"), first), "Agent1/GoodId(\"Interned\")");
+
+    // Ids serialize as their names
+    let json = serde_json::to_string(&AgentId::from("Agent1")).unwrap();
+    assert_eq!(json, "\"Agent1\"");
+    let agent_id: AgentId = serde_json::from_str(&json).unwrap();
+    assert_eq!(agent_id, "Agent1");
+    assert_eq!(AgentId::try_new("Agent1"), Some(agent_id));
+
+    // A full interner keeps resolving known names but takes no new ones
+    let mut interner = Interner::with_limit(2);
+    assert_eq!(interner.try_intern("A"), Some(0));
+    assert_eq!(interner.try_intern("B"), Some(1));
+    assert_eq!(interner.try_intern("A"), Some(0));
+    assert_eq!(interner.try_intern("C"), None);
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.resolve(1), "B");
+}
+
+#[test]
+fn test_explain_report() {
+    let a = Good::new("A", "Good A");
+    let b = Good::new("B", "Good B");
+
+    let mut agent1 = Agent::new("Agent1", [a.clone()].into_iter().collect());
+    agent1.add_preference([a.clone()].into_iter().collect(), 5.0);
+    agent1.add_preference([b.clone()].into_iter().collect(), 8.0);
+    let mut agent2 = Agent::new("Agent2", [b.clone()].into_iter().collect());
+    agent2.add_preference([b.clone()].into_iter().collect(), 5.0);
+    agent2.add_preference([a.clone()].into_iter().collect(), 8.0);
+
+    let agents = vec![agent1, agent2];
+    let auction = CombinatorialAuction::new(agents.clone(), vec![a, b], 0.01).unwrap();
+    let result = auction.run().unwrap();
+    let report = result.explain(&agents);
+
+    assert_eq!(report.agents.len(), 2);
+    let first = &report.agents[0];
+    assert_eq!(first.agent, "Agent1");
+    assert_eq!(first.bundle, vec![GoodId::from("B")]);
+    assert_eq!(first.value, 8.0);
+    assert_eq!(first.endowment_value, 5.0);
+    assert!(first.best_alternative.is_some());
+    assert!(report.to_string().contains("Agent1: got {B}"));
+}
+
+#[test]
+fn test_envy_freeness() {
+    let a = Good::new("A", "Good A");
+
+    let mut agent1 = Agent::new("Agent1", HashSet::new());
+    agent1.add_preference([a.clone()].into_iter().collect(), 5.0);
+    let mut agent2 = Agent::new("Agent2", HashSet::new());
+    agent2.add_preference([a.clone()].into_iter().collect(), 3.0);
+    let agents = vec![agent1, agent2];
+
+    let mut allocation = Allocation::new();
+    allocation.assign("Agent1", [a.clone()].into_iter().collect());
+    allocation.assign("Agent2", HashSet::new());
+
+    let mechanism = BRACEMechanism::new(0.01);
+    let mut prices = PriceVector::new();
+    assert!(!mechanism.verify_envy_freeness(&agents, &allocation, &prices));
+
+    // Pricing A at 2 leaves Agent2 envying by 1
+    prices.set_price("A", 2.0);
+    assert!(!mechanism.verify_envy_freeness(&agents, &allocation, &prices));
+    assert!(mechanism.verify_epsilon_envy_freeness(&agents, &allocation, &prices, 1.0));
+    prices.set_price("A", 3.0);
+    assert!(mechanism.verify_envy_freeness(&agents, &allocation, &prices));
+
+    let result = CombinatorialAuction::new(agents, vec![a], 0.01).unwrap().run().unwrap();
+    assert!(!result.is_envy_free || result.is_epsilon_envy_free);
+}
+
+#[test]
+fn test_blocking_coalition() {
+    let goods: Vec<Good> = ["A", "B", "C"]
+        .iter()
+        .map(|id| Good::new(*id, format!("Good {}", id)))
+        .collect();
+
+    // Each agent wants the next agent's endowment: only the full cycle trades
+    let mut agents = Vec::new();
+    let mut allocation = Allocation::new();
+    for i in 0..3 {
+        let own: HashSet<Good> = [goods[i].clone()].into_iter().collect();
+        let next: HashSet<Good> = [goods[(i + 1) % 3].clone()].into_iter().collect();
+        let mut agent = Agent::new(format!("Agent{}", i), own.clone());
+        agent.add_preference(own.clone(), 5.0);
+        agent.add_preference(next, 10.0);
+        allocation.assign(agent.id, own);
+        agents.push(agent);
+    }
+
+    let mechanism = BRACEMechanism::new(0.01);
+    assert!(mechanism.verify_ordinal_efficiency(&agents, &allocation));
+    assert!(!mechanism.verify_core_stability(&agents, &allocation));
+    let coalition = mechanism.find_blocking_coalition(&agents, &allocation).unwrap();
+    assert_eq!(coalition.len(), 3);
+}
+
+#[test]
+fn test_three_way_trade() {
+    let goods: Vec<Good> = ["A", "B", "C"]
+        .iter()
+        .map(|id| Good::new(*id, format!("Good {}", id)))
+        .collect();
+
+    let mut agents = Vec::new();
+    for i in 0..3 {
+        let own: HashSet<Good> = [goods[i].clone()].into_iter().collect();
+        let next: HashSet<Good> = [goods[(i + 1) % 3].clone()].into_iter().collect();
+        let mut agent = Agent::new(format!("Agent{}", i), own.clone());
+        agent.add_preference(own, 5.0);
+        agent.add_preference(next, 10.0);
+        agents.push(agent);
+    }
+
+    // Pairwise swaps alone leave everyone with their endowment
+    let pairwise = BRACEMechanism::new(0.01).with_max_cycle_length(2);
+    let (allocation, _) = pairwise.compute_allocation(&agents, &goods);
+    assert!(!pairwise.verify_core_stability(&agents, &allocation));
+
+    let mechanism = BRACEMechanism::new(0.01);
+    let (allocation, _) = mechanism.compute_allocation(&agents, &goods);
+    assert!(mechanism.verify_core_stability(&agents, &allocation));
+    for agent in &agents {
+        assert_eq!(agent.preference(allocation.get_bundle(agent.id).unwrap()), 10.0);
+    }
+}
+
+#[test]
+fn test_partial_trades() {
+    let goods: Vec<Good> = ["A", "B", "C", "D", "E"]
+        .iter()
+        .map(|id| Good::new(*id, format!("Good {}", id)))
+        .collect();
+    let set = |ids: &[usize]| -> HashSet<Good> { ids.iter().map(|&i| goods[i].clone()).collect() };
+
+    // Agent1 swaps B for Agent2's C; neither wants the other's whole bundle
+    let mut agent1 = Agent::new("Agent1", set(&[0, 1]));
+    agent1.add_preference(set(&[0, 1]), 5.0);
+    agent1.add_preference(set(&[0, 2]), 10.0);
+    let mut agent2 = Agent::new("Agent2", set(&[2, 3]));
+    agent2.add_preference(set(&[2, 3]), 5.0);
+    agent2.add_preference(set(&[1, 3]), 10.0);
+    // Agent4 is indifferent to E, so giving it to Agent3 is only a weak improvement
+    let mut agent3 = Agent::new("Agent3", HashSet::new());
+    agent3.add_preference(set(&[4]), 2.0);
+    let agent4 = Agent::new("Agent4", set(&[4]));
+
+    let agents = vec![agent1, agent2, agent3, agent4];
+    let whole_only = BRACEMechanism::new(0.01).with_max_transfer_size(0);
+    let (allocation, _) = whole_only.compute_allocation(&agents, &goods);
+    assert_eq!(allocation.get_bundle("Agent1"), Some(&set(&[0, 1])));
+
+    let (allocation, _) = BRACEMechanism::new(0.01).compute_allocation(&agents, &goods);
+    assert_eq!(allocation.get_bundle("Agent1"), Some(&set(&[0, 2])));
+    assert_eq!(allocation.get_bundle("Agent2"), Some(&set(&[1, 3])));
+    // Strict improvement: Agent4 must gain too, so it keeps E
+    assert_eq!(allocation.get_bundle("Agent3"), Some(&HashSet::new()));
+    assert_eq!(allocation.get_bundle("Agent4"), Some(&set(&[4])));
+
+    let weak = BRACEMechanism::new(0.01).with_improvement(Improvement::Weak);
+    let (allocation, _) = weak.compute_allocation(&agents, &goods);
+    assert_eq!(allocation.get_bundle("Agent1"), Some(&set(&[0, 2])));
+    assert_eq!(allocation.get_bundle("Agent3"), Some(&set(&[4])));
+}
+
+#[test]
+fn test_invalid_inputs_are_errors() {
+    let a = Good::new("A", "Good A");
+    let b = Good::new("B", "Good B");
+
+    let duplicate = CombinatorialAuction::new(Vec::new(), vec![a.clone(), a.clone()], 0.01);
+    assert!(matches!(duplicate, Err(AuctionError::DuplicateGood(id)) if id == "A"));
+
+    let endowed = Agent::new("Agent1", [b.clone()].into_iter().collect());
+    let unknown = CombinatorialAuction::new(vec![endowed], vec![a.clone()], 0.01);
+    assert!(matches!(unknown, Err(AuctionError::UnknownEndowedGood { .. })));
+
+    let mut agent = Agent::new("Agent1", HashSet::new());
+    agent.add_preference([a.clone()].into_iter().collect(), f64::NAN);
+    let nan = CombinatorialAuction::new(vec![agent], vec![a], 0.01);
+    let err = nan.err().unwrap();
+    assert!(matches!(err, AuctionError::NonFiniteValue { .. }));
+    assert!(err.to_string().contains("Agent1"));
+}
+
+#[test]
+fn test_instance_validation() {
+    let a = Good::new("A", "Good A");
+    let b = Good::new("B", "Good B");
+    let c = Good::new("C", "Good C");
+
+    let mut agent1 = Agent::new("Agent1", [a.clone()].into_iter().collect());
+    agent1.add_preference([a.clone(), c.clone()].into_iter().collect(), 4.0);
+    let mut agent2 = Agent::new("Agent1", [a.clone()].into_iter().collect());
+    agent2.add_preference([b.clone()].into_iter().collect(), f64::INFINITY);
+
+    let valid = AuctionInstance::new(vec![agent1.clone()], vec![a.clone(), c.clone()], 0.01);
+    assert!(valid.validate().is_ok());
+
+    let instance = AuctionInstance::new(vec![agent1, agent2], vec![a.clone(), a.clone(), b], 0.01);
+    let violations = instance.validate().unwrap_err();
+    assert!(violations.contains(&Violation::DuplicateGood(a.id)));
+    assert!(violations.contains(&Violation::DuplicateAgent(AgentId::from("Agent1"))));
+    assert!(violations.contains(&Violation::UnknownPreferenceGood {
+        agent: AgentId::from("Agent1"),
+        good: c.id,
+    }));
+    assert!(violations
+        .iter()
+        .any(|v| matches!(v, Violation::NonFiniteValue { .. })));
+    assert!(violations.contains(&Violation::OverEndowed {
+        good: a.id,
+        endowed: 2,
+        supply: 1,
+    }));
+}
+
+#[test]
+fn test_agent_builder() {
+    let goods: Vec<Good> = ["A", "B", "C"]
+        .iter()
+        .map(|id| Good::new(*id, format!("Good {}", id)))
+        .collect();
+
+    let agent = Agent::builder("A1")
+        .endow(["A"])
+        .prefer(["B", "C"], 10.0)
+        .prefer(["A", "B"], 7.0)
+        .budget(20.0)
+        .build(&goods)
+        .unwrap();
+    assert_eq!(agent.id, "A1");
+    assert_eq!(agent.endowment, [goods[0].clone()].into_iter().collect());
+    assert_eq!(agent.preference(&[goods[1].clone(), goods[2].clone()].into_iter().collect()), 10.0);
+    assert_eq!(agent.preference_bundles().len(), 2);
+    assert_eq!(agent.budget, Some(20.0));
+
+    let unknown = Agent::builder("A2").prefer(["D"], 1.0).build(&goods);
+    assert!(matches!(unknown, Err(AuctionError::UnknownPreferenceGood { good, .. }) if good == "D"));
+    let unknown = Agent::builder("A2").endow(["D"]).build(&goods);
+    assert!(matches!(unknown, Err(AuctionError::UnknownEndowedGood { .. })));
+}
+
+#[test]
+fn test_construction_macros() {
+    let ab = bundle!["A", "B"];
+    assert_eq!(ab.len(), 2);
+    assert!(ab.contains(&Good::new("A", "Good A")));
+    assert!(bundle![].is_empty());
+
+    let instance = auction! {
+        goods: ["A", "B"],
+        agents: [
+            "Agent1" => { endow: ["A"], prefer: [["B"] => 8.0, ["A"] => 5.0] },
+            "Agent2" => { endow: ["B"], prefer: [["A"] => 8.0, ["B"] => 5.0] },
+            "Agent3" => { endow: [], prefer: [] },
+        ],
+        epsilon: 0.01,
+    };
+    assert_eq!(instance.goods.len(), 2);
+    assert_eq!(instance.agents[0].preference(&bundle!["B"]), 8.0);
+    assert!(instance.validate().is_ok());
+
+    let result = CombinatorialAuction::from_instance(instance).unwrap().run().unwrap();
+    assert_eq!(result.allocation.get_bundle("Agent1"), Some(&bundle!["B"]));
+}
+
+#[test]
+fn test_ordinal_ranking() {
+    let a = Good::new("A", "Good A");
+    let b = Good::new("B", "Good B");
+
+    let mut agent1 = Agent::new("Agent1", bundle!["A"]);
+    agent1.add_ranking(vec![bundle!["B"], bundle!["A"]]);
+    let mut agent2 = Agent::new("Agent2", bundle!["B"]);
+    agent2.add_ranking(vec![bundle!["A", "B"], bundle!["A"], bundle!["B"]]);
+
+    assert_eq!(agent2.rank(&bundle!["A"]), Some(1));
+    assert!(agent2.prefers(&bundle!["A"], &bundle!["B"]));
+    assert!(agent1.prefers(&bundle!["A"], &bundle![]));
+    assert!(!agent1.prefers(&bundle![], &bundle!["A", "B"]));
+
+    let agents = vec![agent1, agent2];
+    let mechanism = BRACEMechanism::new(0.01);
+    let (allocation, _) = mechanism.compute_allocation(&agents, &[a, b]);
+    assert_eq!(allocation.get_bundle("Agent1"), Some(&bundle!["B"]));
+    assert_eq!(allocation.get_bundle("Agent2"), Some(&bundle!["A"]));
+    assert!(mechanism.verify_ordinal_efficiency(&agents, &allocation));
+    assert!(mechanism.verify_individual_rationality(&agents, &allocation));
+
+    // Rankings survive a JSON round trip
+    let json = serde_json::to_string(&agents[1]).unwrap();
+    let restored: Agent = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.rank(&bundle!["A", "B"]), Some(0));
+    assert_eq!(restored.preference_bundles().len(), 3);
+
+    // A new ranking replaces the old one and any cardinal preferences
+    let mut agent = agents[1].clone();
+    agent.add_preference(bundle!["B"], 50.0);
+    agent.add_ranking(vec![bundle!["B"], bundle!["A"]]);
+    assert_eq!(agent.preference_bundles().len(), 2);
+    assert_eq!(agent.rank(&bundle!["A", "B"]), None);
+    assert_eq!(agent.preference(&bundle!["A", "B"]), 0.0);
+    assert_eq!(agent.preference(&bundle!["B"]), 2.0);
+    assert!(agent.prefers(&bundle!["B"], &bundle!["A"]));
+}
+
+#[test]
+fn test_pluggable_valuations() {
+    use brace_sybil::valuation::{AdditiveValuation, FnValuation, UnitDemandValuation};
+
+    let a = Good::new("A", "Good A");
+    let b = Good::new("B", "Good B");
+
+    let additive = Agent::with_valuation(
+        "Additive",
+        bundle!["A"],
+        AdditiveValuation::new().with(&a, 3.0).with(&b, 4.0),
+    );
+    assert_eq!(additive.preference(&bundle!["A", "B"]), 7.0);
+    assert_eq!(additive.preference(&bundle!["B"]), 4.0);
+
+    let unit = Agent::with_valuation(
+        "Unit",
+        bundle!["B"],
+        UnitDemandValuation::new().with(&a, 5.0).with(&b, 2.0),
+    );
+    assert_eq!(unit.preference(&bundle!["A", "B"]), 5.0);
+    assert_eq!(unit.preference(&bundle![]), 0.0);
+
+    let size = Agent::with_valuation(
+        "Size",
+        bundle![],
+        FnValuation::new(|bundle| bundle.len() as f64).with_bundles(vec![bundle!["A", "B"]]),
+    );
+    assert_eq!(size.preference(&bundle!["A", "B"]), 2.0);
+    assert_eq!(size.preference_bundles().len(), 1);
+
+    // Explicit values take precedence over the valuation
+    let mut overridden = additive.clone();
+    overridden.add_preference(bundle!["A", "B"], 1.0);
+    assert_eq!(overridden.preference(&bundle!["A", "B"]), 1.0);
+
+    // Bid-language valuations survive a JSON round trip
+    for agent in [&additive, &unit] {
+        let json = serde_json::to_string(agent).unwrap();
+        let restored: Agent = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.preference(&bundle!["A", "B"]), agent.preference(&bundle!["A", "B"]));
+    }
+}
+
+#[test]
+fn test_lexicographic_valuations() {
+    use brace_sybil::valuation::LexicographicValuation;
+
+    let goods: Vec<Good> = ["A", "B", "C", "D"].iter().map(|id| Good::new(*id, *id)).collect();
+    let valuation = LexicographicValuation::over_goods(&goods);
+    let agent = Agent::with_valuation("Agent1", bundle![], valuation);
+    // A alone beats everything else together
+    assert!(agent.prefers(&bundle!["A"], &bundle!["B", "C", "D"]));
+    assert!(agent.prefers(&bundle!["A", "B"], &bundle!["A", "C", "D"]));
+    assert!(agent.prefers(&bundle!["B", "C"], &bundle!["B", "D"]));
+
+    let goods = vec![
+        Good::new("Math1", "Math 1").with_category("math"),
+        Good::new("Math2", "Math 2").with_category("math"),
+        Good::new("Art1", "Art 1").with_category("art"),
+        Good::new("Art2", "Art 2").with_category("art"),
+        Good::new("Gym", "Gym"),
+    ];
+    let priority = [Category::from("math"), Category::from("art")];
+    let student = Agent::with_valuation(
+        "Student",
+        bundle![],
+        LexicographicValuation::over_categories(&priority, &goods),
+    );
+    assert!(student.prefers(&bundle!["Math1", "Math2"], &bundle!["Math1", "Art1", "Art2"]));
+    assert!(student.prefers(&bundle!["Math2", "Art1"], &bundle!["Math1", "Gym"]));
+    assert_eq!(student.preference(&bundle!["Math1"]), student.preference(&bundle!["Math2"]));
+    assert_eq!(student.preference(&bundle!["Gym"]), 0.0);
+
+    let json = serde_json::to_string(&student).unwrap();
+    let restored: Agent = serde_json::from_str(&json).unwrap();
+    assert!(restored.prefers(&bundle!["Math1"], &bundle!["Art1", "Art2"]));
+}
+
+#[test]
+fn test_k_additive_valuations() {
+    use brace_sybil::valuation::KAdditiveValuation;
+
+    let goods: Vec<Good> = ["A", "B", "C"].iter().map(|id| Good::new(*id, *id)).collect();
+    let (a, b, c) = (&goods[0], &goods[1], &goods[2]);
+    // A and B complement each other, B and C are substitutes
+    let valuation = KAdditiveValuation::new()
+        .with_good(a, 2.0)
+        .with_good(b, 3.0)
+        .with_good(c, 3.0)
+        .with_synergy(&[a.clone(), b.clone()], 4.0)
+        .with_synergy(&[b.clone(), c.clone()], -2.0)
+        .with_synergy(&goods, 1.0);
+    assert_eq!(valuation.order(), 3);
+    assert_eq!(valuation.terms().len(), 6);
+
+    let agent = Agent::with_valuation("Agent1", bundle![], valuation);
+    assert_eq!(agent.preference(&bundle!["A", "B"]), 9.0);
+    assert_eq!(agent.preference(&bundle!["B", "C"]), 4.0);
+    assert_eq!(agent.preference(&bundle!["A", "B", "C"]), 11.0);
+    assert_eq!(agent.preference(&bundle![]), 0.0);
+    // Each term's goods plus everything together
+    assert_eq!(agent.preference_bundles().len(), 6);
+
+    let json = serde_json::to_string(&agent).unwrap();
+    assert!(json.contains("k_additive"));
+    let restored: Agent = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.preference(&bundle!["A", "B", "C"]), 11.0);
+}
+
+#[test]
+fn test_seeded_tie_breaking() {
+    let a = Good::new("A", "Good A");
+
+    // The holder is indifferent, so under weak improvement either bidder
+    // may end up with A
+    let holder = Agent::new("Holder", bundle!["A"]);
+    let mut bidder1 = Agent::new("Bidder1", bundle![]);
+    bidder1.add_preference(bundle!["A"], 1.0);
+    let mut bidder2 = Agent::new("Bidder2", bundle![]);
+    bidder2.add_preference(bundle!["A"], 1.0);
+    let agents = vec![holder, bidder1, bidder2];
+    let goods = vec![a];
+
+    let weak = BRACEMechanism::new(0.01).with_improvement(Improvement::Weak);
+    let (allocation, _) = weak.compute_allocation(&agents, &goods);
+    assert_eq!(allocation.get_bundle("Bidder1"), Some(&bundle!["A"]));
+
+    let winner = |seed| {
+        let mechanism = weak.clone().with_tie_breaker(TieBreaker::Seeded(seed));
+        let (allocation, _) = mechanism.compute_allocation(&agents, &goods);
+        ["Bidder1", "Bidder2"]
+            .into_iter()
+            .find(|id| allocation.get_bundle(*id) == Some(&bundle!["A"]))
+            .unwrap()
+    };
+    for seed in 0..10 {
+        assert_eq!(winner(seed), winner(seed));
+    }
+    let winners: HashSet<&str> = (0..20).map(winner).collect();
+    assert_eq!(winners.len(), 2);
+}
+
+#[test]
+fn test_largest_gain_trade_first() {
+    let goods: Vec<Good> = ["A", "B", "C"].iter().map(|id| Good::new(*id, *id)).collect();
+    let agent = |id: &str, own: &str, values: &[(&str, f64)]| {
+        let mut builder = Agent::builder(id).endow([own]);
+        for (good, value) in values {
+            builder = builder.prefer([*good], *value);
+        }
+        builder.build(&goods).unwrap()
+    };
+    // Agent1 and Agent2 gain 2 in total by swapping, Agent2 and Agent3 gain
+    // 18; both trades need Agent2's B, so the first pair must wait
+    let agents = vec![
+        agent("Agent1", "A", &[("A", 1.0), ("B", 2.0)]),
+        agent("Agent2", "B", &[("B", 1.0), ("A", 2.0), ("C", 10.0)]),
+        agent("Agent3", "C", &[("C", 1.0), ("B", 10.0)]),
+    ];
+
+    let (allocation, _) = BRACEMechanism::new(0.01).compute_allocation(&agents, &goods);
+    assert_eq!(allocation.get_bundle("Agent1"), Some(&bundle!["A"]));
+    assert_eq!(allocation.get_bundle("Agent2"), Some(&bundle!["C"]));
+    assert_eq!(allocation.get_bundle("Agent3"), Some(&bundle!["B"]));
+}
+
+#[test]
+fn test_observer_hooks() {
+    #[derive(Default)]
+    struct Recorder {
+        trades: Vec<Vec<AgentId>>,
+        price_updates: usize,
+        iterations: usize,
+        stop_prices: bool,
+    }
+
+    impl AuctionObserver for Recorder {
+        fn on_trade(&mut self, agents: &[AgentId], _allocation: &Allocation) {
+            self.trades.push(agents.to_vec());
+        }
+
+        fn on_price_update(&mut self, _iteration: usize, _prices: &PriceVector) -> Flow {
+            self.price_updates += 1;
+            if self.stop_prices {
+                Flow::Stop
+            } else {
+                Flow::Continue
+            }
+        }
+
+        fn on_iteration_end(&mut self, _iteration: usize, _allocation: &Allocation) -> Flow {
+            self.iterations += 1;
+            Flow::Continue
+        }
+    }
+
+    let auction = auction! {
+        goods: ["A", "B"],
+        agents: [
+            "Agent1" => { endow: ["A"], prefer: [["B"] => 10.0, ["A"] => 5.0, ["A", "B"] => 12.0] },
+            "Agent2" => { endow: ["B"], prefer: [["A"] => 10.0, ["B"] => 5.0] },
+        ],
+        epsilon: 0.01,
+    };
+    let auction = CombinatorialAuction::from_instance(auction).unwrap();
+
+    let mut recorder = Recorder::default();
+    let result = auction.run_observed(&mut recorder).unwrap();
+    assert_eq!(recorder.trades, vec![vec![AgentId::from("Agent1"), AgentId::from("Agent2")]]);
+    assert_eq!(recorder.iterations, 2);
+    assert!(recorder.price_updates >= 1);
+    assert_eq!(result.allocation.get_bundle("Agent1"), Some(&bundle!["B"]));
+
+    // Stopping the price adjustment after its first step
+    let mut stopper = Recorder {
+        stop_prices: true,
+        ..Recorder::default()
+    };
+    auction.run_observed(&mut stopper).unwrap();
+    assert_eq!(stopper.price_updates, 1);
+}
+
+#[test]
+fn test_welfare_gap() {
+    let swap = auction! {
+        goods: ["A", "B"],
+        agents: [
+            "Agent1" => { endow: ["A"], prefer: [["B"] => 10.0, ["A"] => 5.0] },
+            "Agent2" => { endow: ["B"], prefer: [["A"] => 10.0, ["B"] => 5.0] },
+        ],
+        epsilon: 0.01,
+    };
+    let result = CombinatorialAuction::from_instance(swap).unwrap().run().unwrap();
+    assert_eq!(result.welfare_upper_bound, Some(20.0));
+    assert_eq!(result.welfare_gap, Some(0.0));
+
+    // Individual rationality keeps A with its owner although the other agent values it more
+    let stuck = auction! {
+        goods: ["A"],
+        agents: [
+            "Owner" => { endow: ["A"], prefer: [["A"] => 1.0] },
+            "Buyer" => { endow: [], prefer: [["A"] => 10.0] },
+        ],
+        epsilon: 0.01,
+    };
+    let result = CombinatorialAuction::from_instance(stuck).unwrap().run().unwrap();
+    assert_eq!(result.total_welfare, 1.0);
+    assert_eq!(result.welfare_upper_bound, Some(10.0));
+    assert_eq!(result.welfare_gap, Some(9.0));
+
+    // An OR bid is worth more on the union of its atoms than on any listed
+    // bundle, so winner determination over the listed bundles bounds nothing
+    let goods = vec![Good::new("A", "A"), Good::new("B", "B")];
+    let bidder = |bid: BidLanguage| {
+        let mut agent = Agent::new("Bidder", Bundle::new());
+        agent.set_valuation(bid);
+        agent
+    };
+    let both = || {
+        let mut allocation = Allocation::new();
+        allocation.assign(AgentId::from("Bidder"), bundle!["A", "B"]);
+        allocation
+    };
+    let or = OrBid::new().with(bundle!["A"], 3.0).with(bundle!["B"], 4.0);
+    let result = CombinatorialAuction::new(vec![bidder(BidLanguage::Or(or))], goods.clone(), 0.01)
+        .unwrap()
+        .evaluate(both(), &PriceVector::new());
+    assert_eq!(result.total_welfare, 7.0);
+    assert_eq!(result.welfare_upper_bound, None);
+    assert_eq!(result.welfare_gap, None);
+
+    // Under XOR semantics (and free disposal) a listed subset is worth as much
+    let xor = XorBid::new().with(bundle!["A"], 3.0).with(bundle!["B"], 4.0);
+    let result = CombinatorialAuction::new(vec![bidder(BidLanguage::Xor(xor))], goods.clone(), 0.01)
+        .unwrap()
+        .evaluate(both(), &PriceVector::new());
+    assert_eq!(result.total_welfare, 4.0);
+    assert_eq!(result.welfare_upper_bound, Some(4.0));
+    let disposing = Agent::builder("Bidder").prefer(["B"], 4.0).free_disposal().build(&goods);
+    let result = CombinatorialAuction::new(vec![disposing.unwrap()], goods, 0.01)
+        .unwrap()
+        .evaluate(both(), &PriceVector::new());
+    assert_eq!(result.total_welfare, 4.0);
+    assert_eq!(result.welfare_gap, Some(0.0));
+}
+
+#[test]
+fn test_fairness_objectives() {
+    assert_eq!(Objective::Utilitarian.score(&[1.0, 4.0]), 5.0);
+    assert!((Objective::NashSocialWelfare.score(&[1.0, 4.0]) - 2.0).abs() < 1e-9);
+    assert_eq!(Objective::NashSocialWelfare.score(&[0.0, 4.0]), 0.0);
+    assert_eq!(Objective::Egalitarian.score(&[1.0, 4.0]), 1.0);
+
+    // The holder is indifferent to A (so may give it away under weak
+    // improvement); X gains most from it, Y is worst off
+    let instance = auction! {
+        goods: ["A", "C", "D", "E"],
+        agents: [
+            "Holder" => { endow: ["A", "D"], prefer: [["D"] => 5.0, ["A", "D"] => 5.0] },
+            "X" => { endow: ["E"], prefer: [["E"] => 5.0, ["A", "E"] => 15.0] },
+            "Y" => { endow: ["C"], prefer: [["C"] => 1.0, ["A", "C"] => 4.0] },
+        ],
+        epsilon: 0.01,
+    };
+    let run = |objective| {
+        CombinatorialAuction::from_instance(instance.clone())
+            .unwrap()
+            .with_objective(objective)
+            .with_improvement(Improvement::Weak)
+            .run()
+            .unwrap()
+    };
+
+    let utilitarian = run(Objective::Utilitarian);
+    assert_eq!(utilitarian.allocation.get_bundle("X"), Some(&bundle!["A", "E"]));
+    assert_eq!(utilitarian.objective_value, 21.0);
+
+    let nash = run(Objective::NashSocialWelfare);
+    assert_eq!(nash.allocation.get_bundle("Y"), Some(&bundle!["A", "C"]));
+    assert_eq!(nash.objective, Objective::NashSocialWelfare);
+    assert!((nash.objective_value - 100f64.cbrt()).abs() < 1e-9);
+
+    let egalitarian = run(Objective::Egalitarian);
+    assert_eq!(egalitarian.objective_value, 4.0);
+
+    // The exhaustive benchmark agrees on the egalitarian optimum
+    let (_, best) = brace_sybil::wdp::solve_for_objective(
+        &instance.agents,
+        &instance.goods,
+        Objective::Egalitarian,
+    );
+    assert_eq!(best, 4.0);
+}
+
+#[test]
+fn test_price_trajectory_recording() {
+    let auction = auction! {
+        goods: ["A", "B"],
+        agents: [
+            "Agent1" => { endow: ["A"], prefer: [["B"] => 10.0, ["A"] => 5.0, ["A", "B"] => 12.0] },
+            "Agent2" => { endow: ["B"], prefer: [["A"] => 10.0, ["B"] => 5.0] },
+        ],
+        epsilon: 0.01,
+    };
+    let auction = CombinatorialAuction::from_instance(auction).unwrap();
+
+    let mut trajectory = PriceTrajectory::new();
+    let result = auction.run_observed(&mut trajectory).unwrap();
+    assert!(!trajectory.steps.is_empty());
+    let last = trajectory.steps.last().unwrap();
+    for (good, price) in &result.prices {
+        assert_eq!(last.get_price(*good), *price);
+    }
+
+    let csv = trajectory.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("iteration,A,B"));
+    assert_eq!(lines.count(), trajectory.steps.len());
+}
+
+#[test]
+fn test_csv_export() {
+    let instance = auction! {
+        goods: ["A", "B"],
+        agents: [
+            "Agent1" => { endow: ["A"], prefer: [["B"] => 10.0, ["A"] => 5.0] },
+            "Agent2" => { endow: ["B"], prefer: [["A"] => 10.0, ["B"] => 5.0] },
+        ],
+        epsilon: 0.01,
+    };
+    let auction = CombinatorialAuction::from_instance(instance).unwrap().with_vcg_payments();
+    let result = auction.run().unwrap();
+
+    let (mut allocation, mut prices, mut outcomes) = (Vec::new(), Vec::new(), Vec::new());
+    result
+        .to_csv_writers(auction.agents(), &mut allocation, &mut prices, &mut outcomes)
+        .unwrap();
+
+    let allocation = String::from_utf8(allocation).unwrap();
+    assert_eq!(allocation, "agent,good,units\nAgent1,B,1\nAgent2,A,1\n");
+
+    let prices = String::from_utf8(prices).unwrap();
+    let rows: Vec<&str> = prices.lines().collect();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0], "good,price");
+    assert!(rows[1].starts_with("A,") && rows[2].starts_with("B,"));
+
+    let outcomes = String::from_utf8(outcomes).unwrap();
+    let mut lines = outcomes.lines();
+    let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+    assert_eq!(header[0], "agent");
+    for line in lines {
+        let fields: Vec<&str> = line.split(',').collect();
+        assert_eq!(fields.len(), header.len());
+        assert_eq!(fields[1], "10");
+        assert!(!fields[8].is_empty(), "VCG payment column is filled");
+        assert!(fields[9].is_empty(), "core payments were not requested");
+    }
+}
+
+#[test]
+fn test_preference_editing() {
+    let mut agent = Agent::new("Agent1", bundle!["A"]);
+    agent.add_preference(bundle!["A"], 5.0);
+    agent.add_preference(bundle!["B"], 10.0);
+
+    let listed: Vec<f64> = agent.preferences().map(|(_, value)| value).collect();
+    assert_eq!(listed, vec![5.0, 10.0]);
+
+    // Updating keeps a single entry per bundle
+    agent.set_preference(bundle!["A"], 12.0);
+    agent.set_preference(bundle!["A", "B"], 15.0);
+    assert_eq!(agent.preference(&bundle!["A"]), 12.0);
+    assert_eq!(agent.preference_bundles().len(), 3);
+    assert!(agent.prefers(&bundle!["A"], &bundle!["B"]));
+
+    assert_eq!(agent.remove_preference(&bundle!["B"]), Some(10.0));
+    assert_eq!(agent.remove_preference(&bundle!["B"]), None);
+    assert_eq!(agent.preference(&bundle!["B"]), 0.0);
+    assert_eq!(agent.preference_bundles().len(), 2);
+
+    agent.clear_preferences();
+    assert_eq!(agent.preferences().count(), 0);
+    assert!(agent.preference_bundles().is_empty());
+}
+
+#[test]
+fn test_allocation_tracks_allocated_units() {
+    let mut allocation = Allocation::new();
+    allocation.assign("Agent1", bundle!["A", "B"]);
+    allocation.assign("Agent2", bundle!["B"]);
+    assert_eq!(allocation.allocated_units("A"), 1);
+    assert_eq!(allocation.allocated_units("B"), 2);
+
+    // Reassigning replaces the old holding in the totals
+    allocation.assign("Agent2", bundle!["C"]);
+    assert_eq!(allocation.allocated_units("B"), 1);
+    assert_eq!(allocation.allocated_units("C"), 1);
+
+    let supply = |_| 1;
+    assert!(allocation.fits_after("Agent1", &bundle!["A", "B"], None, supply));
+    assert!(!allocation.fits_after("Agent3", &bundle!["C"], None, supply));
+
+    let json = serde_json::to_string(&allocation).unwrap();
+    let restored: Allocation = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.allocated_units("B"), 1);
+    assert_eq!(restored.allocated_units("C"), 1);
+}
+
+#[test]
+fn test_preference_lookup_for_large_bundles() {
+    let names: Vec<String> = (0..12).map(|i| format!("L{}", i)).collect();
+    let goods: Vec<Good> = names.iter().map(|n| Good::new(n.as_str(), n.as_str())).collect();
+    let forward: Bundle = goods.iter().cloned().collect();
+    let backward: Bundle = goods.iter().rev().cloned().collect();
+    let small: Bundle = goods[..8].iter().cloned().collect();
+
+    let mut agent = Agent::new("Collector", Bundle::new());
+    agent.add_preference(forward, 40.0);
+    agent.add_preference(small, 25.0);
+    assert_eq!(agent.preference(&backward), 40.0);
+    assert_eq!(agent.preference(&goods[..8].iter().rev().cloned().collect()), 25.0);
+    assert_eq!(agent.preference(&goods[..9].iter().cloned().collect()), 0.0);
+}
+
+#[test]
+fn test_agent_capacity() {
+    let goods = vec![Good::new("A", "Good A"), Good::new("B", "Good B")];
+    let collector = Agent::builder("Collector")
+        .max_goods(1)
+        .prefer(["A", "B"], 10.0)
+        .prefer(["A"], 3.0)
+        .build(&goods)
+        .unwrap();
+    assert!(collector.within_capacity(&bundle!["A"], None));
+    assert!(!collector.within_capacity(&bundle!["A", "B"], None));
+
+    // The pair is out of reach, so only the single good is demanded
+    let prices = PriceVector::new();
+    assert_eq!(prices.demand_set(&collector), vec![bundle!["A"]]);
+
+    let holder = Agent::builder("Holder")
+        .endow(["A", "B"])
+        .prefer(["A", "B"], 1.0)
+        .build(&goods)
+        .unwrap();
+    let agents = vec![collector.clone(), holder];
+    let result = CombinatorialAuction::new(agents.clone(), goods, 0.01).unwrap().run().unwrap();
+    assert!(result.is_feasible);
+    assert!(BRACEMechanism::new(0.01).verify_capacity(&agents, &result.allocation));
+
+    let json = serde_json::to_string(&collector).unwrap();
+    let restored: Agent = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.max_goods, Some(1));
+}
+
+#[test]
+fn test_free_disposal() {
+    let goods = vec![Good::new("A", "Good A"), Good::new("B", "Good B")];
+    let builder = Agent::builder("Agent1").prefer(["A"], 5.0).prefer(["A", "B"], 4.0);
+    let strict = builder.clone().build(&goods).unwrap();
+    assert_eq!(strict.preference(&bundle!["B"]), 0.0);
+    assert!(strict.prefers(&bundle!["A"], &bundle!["A", "B"]));
+
+    // Undeclared bundles take their best declared subset; declared ones keep their value
+    let monotone = builder.free_disposal().build(&goods).unwrap();
+    assert_eq!(monotone.preference(&bundle!["B"]), 0.0);
+    assert_eq!(monotone.preference(&bundle!["A"]), 5.0);
+    assert_eq!(monotone.preference(&bundle!["A", "B"]), 4.0);
+    let mut extended = monotone.clone();
+    extended.remove_preference(&bundle!["A", "B"]);
+    assert_eq!(extended.preference(&bundle!["A", "B"]), 5.0);
+    assert!(!extended.prefers(&bundle!["A"], &bundle!["A", "B"]));
+
+    let json = serde_json::to_string(&extended).unwrap();
+    let restored: Agent = serde_json::from_str(&json).unwrap();
+    assert!(restored.free_disposal);
+    assert_eq!(restored.preference(&bundle!["A", "B"]), 5.0);
+    assert!(!serde_json::to_string(&strict).unwrap().contains("free_disposal"));
+}
+
+#[test]
+fn test_housing_market_mode() {
+    let goods: Vec<Good> = ["A", "B", "C"].iter().map(|id| Good::new(*id, *id)).collect();
+    // Each agent wants the next agent's house
+    let agents: Vec<Agent> = [("Agent1", "A", "B"), ("Agent2", "B", "C"), ("Agent3", "C", "A")]
+        .iter()
+        .map(|(id, own, wanted)| {
+            Agent::builder(*id)
+                .endow([*own])
+                .prefer([*wanted], 10.0)
+                .prefer([*own], 1.0)
+                .build(&goods)
+                .unwrap()
+        })
+        .collect();
+
+    let result = CombinatorialAuction::new(agents, goods, 0.01)
+        .unwrap()
+        .with_housing_market()
+        .run()
+        .unwrap();
+    assert_eq!(result.allocation.get_bundle("Agent1"), Some(&bundle!["B"]));
+    assert!(result.prices.is_empty());
+    assert!(result.ce_violations.is_empty());
+    assert!(result.personalized_prices.is_none());
+    let cycles = result.trade_cycles.unwrap();
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(cycles[0].len(), 3);
+}
+
+#[test]
+fn test_weak_improvement_unlocks_cycles() {
+    let goods: Vec<Good> = ["A", "B", "C"].iter().map(|id| Good::new(*id, *id)).collect();
+    // Agent1 does not mind swapping A for B, which lets the others trade
+    let indifferent = Agent::builder("Agent1")
+        .endow(["A"])
+        .prefer(["A"], 5.0)
+        .prefer(["B"], 5.0)
+        .build(&goods)
+        .unwrap();
+    assert!(indifferent.prefers_or_indifferent(&bundle!["B"], &bundle!["A"]));
+    assert!(!indifferent.prefers(&bundle!["B"], &bundle!["A"]));
+    assert!(!indifferent.prefers_or_indifferent(&bundle!["C"], &bundle!["A"]));
+    let mut agents = vec![indifferent];
+    // No pairwise swap helps both Agent2 and Agent3
+    for (id, own, wanted) in [("Agent2", "B", "C"), ("Agent3", "C", "A")] {
+        let agent = Agent::builder(id).endow([own]).prefer([wanted], 10.0).prefer([own], 1.0);
+        agents.push(agent.build(&goods).unwrap());
+    }
+
+    let mechanism = BRACEMechanism::new(0.01).without_pricing();
+    let (strict, _) = mechanism.compute_allocation(&agents, &goods);
+    assert_eq!(strict.get_bundle("Agent1"), Some(&bundle!["A"]));
+
+    let weak = mechanism.with_improvement(Improvement::Weak);
+    let (allocation, _) = weak.compute_allocation(&agents, &goods);
+    assert_eq!(allocation.get_bundle("Agent1"), Some(&bundle!["B"]));
+    assert_eq!(allocation.get_bundle("Agent2"), Some(&bundle!["C"]));
+    assert_eq!(allocation.get_bundle("Agent3"), Some(&bundle!["A"]));
+    assert!(weak.verify_individual_rationality(&agents, &allocation));
+}
+
+#[test]
+fn test_auto_epsilon() {
+    // Agent1 only gains from the swap if B's reserve of 5 is waived by 2
+    let goods = vec![Good::new("A", "A"), Good::new("B", "B").with_reserve_price(5.0)];
+    let agents: Vec<Agent> = [("Agent1", "A", "B"), ("Agent2", "B", "A")]
+        .iter()
+        .map(|(id, own, wanted)| {
+            Agent::builder(*id)
+                .endow([*own])
+                .prefer([*wanted], 3.0)
+                .prefer([*own], 1.0)
+                .build(&goods)
+                .unwrap()
+        })
+        .collect();
+
+    let mechanism = BRACEMechanism::new(0.01).with_auto_epsilon(0.0, 10.0);
+    let epsilon = mechanism.select_epsilon(&agents, &goods);
+    assert!((2.0..2.001).contains(&epsilon));
+    assert_eq!(BRACEMechanism::new(0.5).select_epsilon(&agents, &goods), 0.5);
+
+    let result = CombinatorialAuction::new(agents.clone(), goods, 0.01)
+        .unwrap()
+        .with_auto_epsilon(0.0, 10.0)
+        .run()
+        .unwrap();
+    assert_eq!(result.epsilon_used, Some(epsilon));
+    assert!(result.is_feasible);
+    // Agent1 prefers B, but paying its reserve of 5 for a gain of 2 leaves it worse off
+    assert!(mechanism.verify_individual_rationality(&agents, &result.allocation));
+    assert!(!result.is_individually_rational);
+}
+
+#[test]
+fn test_auto_epsilon_waits_for_prices_to_settle() {
+    // Both agents demand B, which Agent2 keeps, so within five iterations
+    // its price keeps rising by a step of 0.1; the price adjustment only
+    // stops in time once epsilon exceeds that step
+    let goods = vec![Good::new("A", "A"), Good::new("B", "B")];
+    let agents: Vec<Agent> = [("Agent1", "A"), ("Agent2", "B")]
+        .iter()
+        .map(|(id, own)| {
+            Agent::builder(*id)
+                .endow([*own])
+                .prefer(["B"], 10.0)
+                .prefer(["A"], 1.0)
+                .build(&goods)
+                .unwrap()
+        })
+        .collect();
+    let config = PriceAdjustmentConfig {
+        max_iterations: 5,
+        ..PriceAdjustmentConfig::default()
+    };
+
+    let mechanism = BRACEMechanism::new(0.01)
+        .with_price_config(config)
+        .with_auto_epsilon(0.0, 1.0);
+    let epsilon = mechanism.select_epsilon(&agents, &goods);
+    assert!((0.1..0.1001).contains(&epsilon));
+
+    let result = CombinatorialAuction::new(agents, goods, 0.01)
+        .unwrap()
+        .with_price_adjustment(config)
+        .with_auto_epsilon(0.0, 1.0)
+        .run()
+        .unwrap();
+    assert_eq!(result.epsilon_used, Some(epsilon));
+    assert!(result.converged);
+}
+
+#[test]
+fn test_price_non_convergence_is_reported() {
+    // Agent1 keeps demanding B, which Agent2 will not give up, so raising
+    // the price of A never puts A into Agent1's demand set
+    let goods = vec![Good::new("A", "A"), Good::new("B", "B")];
+    let agents: Vec<Agent> = [("Agent1", "A"), ("Agent2", "B")]
+        .iter()
+        .map(|(id, own)| {
+            Agent::builder(*id)
+                .endow([*own])
+                .prefer(["B"], 10.0)
+                .prefer(["A"], 1.0)
+                .build(&goods)
+                .unwrap()
+        })
+        .collect();
+    let config = PriceAdjustmentConfig {
+        max_iterations: 5,
+        ..PriceAdjustmentConfig::default()
+    };
+
+    let result = CombinatorialAuction::new(agents, goods, 0.01)
+        .unwrap()
+        .with_price_adjustment(config)
+        .run()
+        .unwrap();
+    assert!(!result.converged);
+    assert_eq!(
+        result.non_convergence,
+        vec![NonConvergence::PriceAdjustment { iterations: 5 }]
+    );
+    let json = serde_json::to_string(&result).unwrap();
+    let restored: brace_sybil::AuctionResult = serde_json::from_str(&json).unwrap();
+    assert!(!restored.converged);
+}
+
+#[test]
+fn test_serialized_results_are_byte_stable() {
+    let goods: Vec<Good> = ["C", "A", "D", "B"].iter().map(|id| Good::new(*id, *id)).collect();
+    let agents: Vec<Agent> = ["Agent2", "Agent3", "Agent1"]
+        .iter()
+        .zip([["C", "A"], ["D", "B"], ["B", "C"]])
+        .map(|(id, wanted)| {
+            Agent::builder(*id)
+                .prefer(wanted, 6.0)
+                .prefer([wanted[0]], 2.0)
+                .build(&goods)
+                .unwrap()
+        })
+        .collect();
+    let auction = CombinatorialAuction::new(agents, goods, 0.01).unwrap().with_vcg_payments();
+    let first = serde_json::to_string(&auction.run().unwrap()).unwrap();
+    let second = serde_json::to_string(&auction.run().unwrap()).unwrap();
+    assert_eq!(first, second);
+
+    // Maps list agents and goods by ID, bundles list goods by ID
+    let result = auction.run().unwrap();
+    let json: serde_json::Value = serde_json::from_str(&first).unwrap();
+    let prices: Vec<&String> = json["prices"].as_object().unwrap().keys().collect();
+    assert_eq!(prices, ["A", "B", "C", "D"]);
+    let holders: Vec<&String> = json["vcg_payments"].as_object().unwrap().keys().collect();
+    assert_eq!(holders, ["Agent1", "Agent2", "Agent3"]);
+    for (id, goods) in result.allocation.sorted_assignments() {
+        let listed: Vec<&str> = json["allocation"]["assignments"][id.as_str()]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|good| good["id"].as_str().unwrap())
+            .collect();
+        let goods: Vec<&str> = goods.iter().map(|g| g.as_str()).collect();
+        assert_eq!(listed, goods);
+    }
+    let ids: Vec<GoodId> = result.sorted_prices().iter().map(|(id, _)| *id).collect();
+    assert_eq!(ids, ["A", "B", "C", "D"].map(GoodId::from));
+}
+
+#[test]
+fn test_run_from_previous_result() {
+    let houses = |ids: &[&str]| -> Vec<Good> { ids.iter().map(|id| Good::new(*id, *id)).collect() };
+    let resident = |goods: &[Good], id: &str, own: &str, wanted: &str| {
+        Agent::builder(id)
+            .endow([own])
+            .prefer([wanted], 10.0)
+            .prefer([own], 1.0)
+            .build(goods)
+            .unwrap()
+    };
+    let goods = houses(&["A", "B", "C"]);
+    let agents = vec![
+        resident(&goods, "Agent1", "A", "B"),
+        resident(&goods, "Agent2", "B", "C"),
+        resident(&goods, "Agent3", "C", "A"),
+    ];
+    let auction = CombinatorialAuction::new(agents.clone(), goods, 0.01)
+        .unwrap()
+        .with_housing_market();
+    let first = auction.run().unwrap();
+    assert_eq!(first.trade_cycles.as_ref().unwrap().len(), 1);
+
+    // Starting from the outcome leaves nothing to trade
+    let again = auction.run_from(&first).unwrap();
+    assert_eq!(again.allocation.assignments(), first.allocation.assignments());
+    assert!(again.trade_cycles.unwrap().is_empty());
+
+    // A newcomer starts from its endowment while the others keep their houses
+    let goods = houses(&["A", "B", "C", "D"]);
+    let mut grown = agents;
+    grown.push(resident(&goods, "Agent4", "D", "D"));
+    let grown = CombinatorialAuction::new(grown, goods, 0.01).unwrap().with_housing_market();
+    let result = grown.run_from(&first).unwrap();
+    assert!(result.trade_cycles.unwrap().is_empty());
+    assert_eq!(result.allocation.get_bundle("Agent1"), Some(&bundle!["B"]));
+    assert_eq!(result.allocation.get_bundle("Agent4"), Some(&bundle!["D"]));
+
+    // An allocation that no longer fits is ignored
+    let mut stale = first.clone();
+    stale.allocation.assign("Agent2", bundle!["B"]);
+    let result = auction.run_from(&stale).unwrap();
+    assert_eq!(result.allocation.assignments(), first.allocation.assignments());
+    assert_eq!(result.trade_cycles.unwrap().len(), 1);
+}
@@ -0,0 +1,121 @@
+use brace_sybil::io::{parse_bid, parse_cats, solve_json, ParseError};
+use brace_sybil::{
+    auction, bundle, Agent, AuctionError, AuctionInstance, AuctionResult, BidLanguage, Good,
+};
+use std::collections::HashSet;
+
+const CATS_INSTANCE: &str = "\
+% generated by CATS
+goods 3
+bids 4
+dummy 1
+
+0\t10.0\t0\t1\t#
+1\t7.5\t1\t2\t3\t#
+2\t6.0\t0\t3\t#
+3\t4.0\t2\t#
+";
+
+#[test]
+fn test_parse_cats_instance() {
+    let (agents, goods) = parse_cats(CATS_INSTANCE).unwrap();
+    assert_eq!(goods.len(), 3);
+    // Two single-minded bidders plus one XOR bidder owning dummy good 3
+    assert_eq!(agents.len(), 3);
+
+    let xor_bidder = agents.iter().find(|a| a.id == "Bidder3").unwrap();
+    assert_eq!(xor_bidder.preference_bundles().len(), 2);
+
+    let all: HashSet<Good> = goods.iter().cloned().collect();
+    assert_eq!(xor_bidder.preference(&all), 7.5);
+}
+
+#[test]
+fn test_parse_cats_reports_line_numbers() {
+    let err = parse_cats("goods 2\nbids 1\n0 5.0 0 1\n").unwrap_err();
+    assert!(matches!(err, ParseError::Syntax { line: 3, .. }));
+}
+
+#[test]
+fn test_parse_bid_expressions() {
+    let goods: Vec<Good> = ["A", "B", "C"].iter().map(|id| Good::new(*id, *id)).collect();
+    let value = |bid: &BidLanguage, bundle| {
+        Agent::with_valuation("Agent1", bundle![], bid.clone()).preference(&bundle)
+    };
+
+    let xor = parse_bid("(A & B):10 XOR C:5", &goods).unwrap();
+    assert!(matches!(&xor, BidLanguage::Xor(bid) if bid.atoms.len() == 2));
+    assert_eq!(value(&xor, bundle!["A", "B", "C"]), 10.0);
+    assert_eq!(value(&xor, bundle!["A", "C"]), 5.0);
+
+    let or = parse_bid("A:3 or B & C:4.5", &goods).unwrap();
+    assert!(matches!(&or, BidLanguage::Or(bid) if bid.atoms.len() == 2));
+    assert_eq!(value(&or, bundle!["A", "B", "C"]), 7.5);
+
+    let nested = parse_bid("(A:3 XOR (A & B):5) OR (C:2)", &goods).unwrap();
+    assert!(matches!(&nested, BidLanguage::OrOfXor(bid) if bid.clauses.len() == 2));
+    assert_eq!(value(&nested, bundle!["A", "B", "C"]), 7.0);
+}
+
+#[test]
+fn test_parse_bid_errors() {
+    let goods = vec![Good::new("A", "A"), Good::new("B", "B")];
+    let column = |input: &str| match parse_bid(input, &goods) {
+        Err(ParseError::BidSyntax { column, .. }) => column,
+        other => panic!("expected a bid syntax error, got {:?}", other),
+    };
+    assert_eq!(column(""), 1);
+    assert_eq!(column("A:1 XOR D:2"), 9);
+    assert_eq!(column("(A & B):x"), 9);
+    assert_eq!(column("A & B"), 6);
+    assert_eq!(column("A:1 B:2"), 5);
+    assert_eq!(column("(A:1 XOR B:2"), 13);
+}
+
+#[test]
+fn test_instance_json_round_trip() {
+    let (mut agents, goods) = parse_cats(CATS_INSTANCE).unwrap();
+    let endowed: HashSet<Good> = [goods[0].clone()].into_iter().collect();
+    let mut trader = Agent::new("Trader".to_string(), endowed.clone());
+    trader.add_preference(endowed.clone(), 2.5);
+    agents.push(trader);
+
+    let instance = AuctionInstance::new(agents, goods.clone(), 0.01);
+    let json = instance.to_json().unwrap();
+    let restored = AuctionInstance::from_json(&json).unwrap();
+
+    assert_eq!(restored.goods, goods);
+    assert_eq!(restored.epsilon, 0.01);
+    assert_eq!(restored.agents.len(), instance.agents.len());
+
+    // Explicit preferences and bidding-language valuations both survive
+    let all: HashSet<Good> = goods.iter().cloned().collect();
+    for (original, copy) in instance.agents.iter().zip(&restored.agents) {
+        assert_eq!(original.id, copy.id);
+        assert_eq!(original.endowment, copy.endowment);
+        assert_eq!(original.preference(&all), copy.preference(&all));
+        assert_eq!(original.preference(&endowed), copy.preference(&endowed));
+    }
+}
+
+#[test]
+fn test_solve_json_round_trip() {
+    let instance = auction! {
+        goods: ["A", "B"],
+        agents: [
+            "Agent1" => { endow: ["A"], prefer: [["B"] => 10.0, ["A"] => 5.0] },
+            "Agent2" => { endow: ["B"], prefer: [["A"] => 10.0, ["B"] => 5.0] },
+        ],
+        epsilon: 0.01,
+    };
+
+    let output = solve_json(&instance.to_json().unwrap()).unwrap();
+    let result: AuctionResult = serde_json::from_str(&output).unwrap();
+    assert_eq!(result.total_welfare, 20.0);
+    assert!(result.is_feasible);
+
+    assert!(matches!(
+        solve_json("{\"agents\": ["),
+        Err(AuctionError::Parse(ParseError::Json(_)))
+    ));
+}
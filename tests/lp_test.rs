@@ -0,0 +1,163 @@
+use brace_sybil::lp::{sd_dominating_allocation, solve_lp_relaxation};
+use brace_sybil::mechanisms::ProbabilisticSerial;
+use brace_sybil::wdp::solve_exact;
+use brace_sybil::{bundle, Agent, BRACEMechanism, FractionalAllocation, Good};
+
+fn good(id: &str) -> Good {
+    Good::new(id, format!("Good {}", id))
+}
+
+#[test]
+fn test_lp_duals_are_competitive_prices() {
+    let goods = vec![good("A")];
+    let mut agent1 = Agent::new("Agent1", bundle![]);
+    agent1.add_preference(bundle!["A"], 5.0);
+    let mut agent2 = Agent::new("Agent2", bundle![]);
+    agent2.add_preference(bundle!["A"], 3.0);
+    let agents = vec![agent1, agent2];
+
+    let solution = solve_lp_relaxation(&agents, &goods);
+    assert!((solution.welfare - 5.0).abs() < 1e-9);
+    assert!((solution.allocation.good_share("Agent1", "A") - 1.0).abs() < 1e-9);
+    // The price clears the market: the loser cannot afford A, the winner still wants it
+    let price = solution.prices.get_price("A");
+    assert!((3.0 - 1e-9..=5.0 + 1e-9).contains(&price));
+    assert!((solution.utilities[&agents[0].id] - (5.0 - price)).abs() < 1e-9);
+}
+
+#[test]
+fn test_lp_relaxation_is_fractional_on_odd_cycle() {
+    let goods = vec![good("A"), good("B"), good("C")];
+    let mut agents = Vec::new();
+    let pairs = [
+        ("Agent1", bundle!["A", "B"]),
+        ("Agent2", bundle!["B", "C"]),
+        ("Agent3", bundle!["A", "C"]),
+    ];
+    for (id, pair) in pairs {
+        let mut agent = Agent::new(id, bundle![]);
+        agent.add_preference(pair, 2.0);
+        agents.push(agent);
+    }
+
+    let solution = solve_lp_relaxation(&agents, &goods);
+    let (_, integral) = solve_exact(&agents, &goods);
+    assert!((solution.welfare - 3.0).abs() < 1e-9);
+    assert!((integral - 2.0).abs() < 1e-9);
+    for agent in &agents {
+        let shares = solution.allocation.get_shares(agent.id);
+        assert_eq!(shares.len(), 1);
+        assert!((shares[0].1 - 0.5).abs() < 1e-9);
+    }
+    // Strong duality: prices and surpluses add up to the LP welfare
+    let dual: f64 = goods.iter().map(|g| solution.prices.get_price(g.id)).sum::<f64>()
+        + solution.utilities.values().sum::<f64>();
+    assert!((dual - solution.welfare).abs() < 1e-9);
+}
+
+#[test]
+fn test_wdp_solvers() {
+    use brace_sybil::wdp::{ExactSolver, GreedySolver, LpRelaxationSolver, WdpSolver};
+    use brace_sybil::{BRACEMechanism, CombinatorialAuction};
+
+    let goods = vec![good("A"), good("B")];
+    // Greedy takes the pair first (10 / sqrt 2 > 6), missing the better split
+    let mut agent1 = Agent::new("Agent1", bundle![]);
+    agent1.add_preference(bundle!["A", "B"], 10.0);
+    let mut agent2 = Agent::new("Agent2", bundle![]);
+    agent2.add_preference(bundle!["A"], 6.0);
+    let mut agent3 = Agent::new("Agent3", bundle![]);
+    agent3.add_preference(bundle!["B"], 6.0);
+    let agents = vec![agent1, agent2, agent3];
+
+    let mechanism = BRACEMechanism::new(0.01);
+    let solvers: Vec<(Box<dyn WdpSolver>, f64)> = vec![
+        (Box::new(ExactSolver), 12.0),
+        (Box::new(GreedySolver), 10.0),
+        (Box::new(LpRelaxationSolver), 12.0),
+    ];
+    for (solver, expected) in &solvers {
+        let (allocation, welfare) = solver.solve(&agents, &goods);
+        assert!((welfare - expected).abs() < 1e-9);
+        assert!(mechanism.verify_feasibility(&allocation, &goods));
+        assert!(solver.upper_bound(&agents, &goods) >= welfare - 1e-9);
+    }
+
+    let result = CombinatorialAuction::new(agents, goods, 0.01)
+        .unwrap()
+        .with_wdp_solver(ExactSolver)
+        .run()
+        .unwrap();
+    assert_eq!(result.benchmark_welfare, Some(12.0));
+}
+
+#[test]
+fn test_run_with_benchmark_reports_approximation_ratio() {
+    use brace_sybil::wdp::GreedySolver;
+    use brace_sybil::CombinatorialAuction;
+
+    let goods = vec![good("A"), good("B")];
+    let mut agent1 = Agent::new("Agent1", bundle![]);
+    agent1.add_preference(bundle!["A", "B"], 10.0);
+    let mut agent2 = Agent::new("Agent2", bundle![]);
+    agent2.add_preference(bundle!["A"], 6.0);
+    let mut agent3 = Agent::new("Agent3", bundle![]);
+    agent3.add_preference(bundle!["B"], 6.0);
+    let agents = vec![agent1, agent2, agent3];
+
+    let auction = CombinatorialAuction::new(agents, goods, 0.01).unwrap();
+    let run = auction.run_with_benchmark().unwrap();
+    assert!((run.optimal.total_welfare - 12.0).abs() < 1e-9);
+    assert!(run.optimal.is_feasible);
+    assert!(run.approximation_ratio <= 1.0 + 1e-9);
+    assert_eq!(run.approximation_ratio, run.brace.approximation_ratio(&run.optimal));
+    assert_eq!(run.optimal.approximation_ratio(&run.optimal), 1.0);
+
+    // A configured solver is used as the benchmark instead
+    let greedy = auction.with_wdp_solver(GreedySolver).run_with_benchmark().unwrap();
+    assert!((greedy.optimal.total_welfare - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_sd_efficiency_of_random_assignments() {
+    // Bogomolnaia and Moulin's example: Agent1 and Agent2 rank a > b > c > d,
+    // Agent3 and Agent4 rank b > a > d > c
+    let goods: Vec<Good> = ["a", "b", "c", "d"].iter().map(|id| good(id)).collect();
+    let ranking = |id: &str, order: [&str; 4]| {
+        let mut agent = Agent::new(id, bundle![]);
+        for (rank, g) in order.iter().enumerate() {
+            agent.add_preference(bundle![*g], (4 - rank) as f64);
+        }
+        agent
+    };
+    let agents = vec![
+        ranking("Agent1", ["a", "b", "c", "d"]),
+        ranking("Agent2", ["a", "b", "c", "d"]),
+        ranking("Agent3", ["b", "a", "d", "c"]),
+        ranking("Agent4", ["b", "a", "d", "c"]),
+    ];
+    let mechanism = BRACEMechanism::new(0.01);
+
+    // Random serial dictatorship's assignment is stochastically dominated
+    let mut serial = FractionalAllocation::new();
+    for (agent, [first, second, third, fourth]) in [
+        ("Agent1", ["a", "b", "c", "d"]),
+        ("Agent2", ["a", "b", "c", "d"]),
+        ("Agent3", ["b", "a", "d", "c"]),
+        ("Agent4", ["b", "a", "d", "c"]),
+    ] {
+        serial.add_share(agent, bundle![first], 5.0 / 12.0);
+        serial.add_share(agent, bundle![second], 1.0 / 12.0);
+        serial.add_share(agent, bundle![third], 5.0 / 12.0);
+        serial.add_share(agent, bundle![fourth], 1.0 / 12.0);
+    }
+    assert!(!mechanism.verify_sd_efficiency(&agents, &goods, &serial));
+    let dominating = sd_dominating_allocation(&agents, &goods, &serial).unwrap();
+    assert!(mechanism.verify_fractional_feasibility(&dominating, &goods));
+    assert!(dominating.good_share("Agent1", "a") >= 5.0 / 12.0 - 1e-9);
+
+    // Probabilistic serial gives a = c = 1/2 to Agent1 and Agent2: sd-efficient
+    let eating = ProbabilisticSerial::new().compute_allocation(&agents, &goods);
+    assert!((eating.good_share("Agent1", "a") - 0.5).abs() < 1e-9);
+    assert!(mechanism.verify_sd_efficiency(&agents, &goods, &eating));
+}
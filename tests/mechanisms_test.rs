@@ -0,0 +1,392 @@
+use brace_sybil::mechanisms::{
+    Ask, CombinatorialClockAuction, DeferredAcceptanceAuction, DoubleAuction, EnvyCycleMechanism, GreedyMechanism, IBundleAuction, LeximinMechanism,
+    ProbabilisticSerial, RandomSerialDictatorship, SmraAuction, TtcMechanism,
+};
+use brace_sybil::valuation::AdditiveValuation;
+use brace_sybil::{
+    Agent, AgentId, Allocation, AuctionError, BRACEMechanism, Bundle, FractionalAllocation, Good,
+    GoodId,
+};
+use std::collections::HashSet;
+
+fn good(id: &str) -> Good {
+    Good::new(id, format!("Good {}", id))
+}
+
+fn bundle(goods: &[&Good]) -> Bundle {
+    goods.iter().map(|g| (*g).clone()).collect()
+}
+
+#[test]
+fn test_ibundle_allocates_efficiently() {
+    let a = good("A");
+    let b = good("B");
+
+    let mut pair = Agent::new("Pair", HashSet::new());
+    pair.add_preference(bundle(&[&a, &b]), 3.0);
+    let mut left = Agent::new("Left", HashSet::new());
+    left.add_preference(bundle(&[&a]), 2.0);
+    let mut right = Agent::new("Right", HashSet::new());
+    right.add_preference(bundle(&[&b]), 2.0);
+
+    let result = IBundleAuction::new(0.1).run(&[pair, left, right], &[a.clone(), b.clone()]);
+
+    assert!(result.converged);
+    assert_eq!(result.allocation.get_bundle("Left"), Some(&bundle(&[&a])));
+    assert_eq!(result.allocation.get_bundle("Right"), Some(&bundle(&[&b])));
+    assert!(result.allocation.get_bundle("Pair").unwrap().is_empty());
+
+    // The pair bidder is priced out just above its value of 3
+    let pair_price = result.prices[&AgentId::from("Pair")].bundle_price(&bundle(&[&a, &b]));
+    assert!(pair_price > 3.0);
+    let paid = result.payments[&AgentId::from("Left")] + result.payments[&AgentId::from("Right")];
+    assert!((paid - result.revenue).abs() < 1e-9);
+    assert!((3.0 - 1e-9..=4.0).contains(&paid));
+}
+
+#[test]
+fn test_ttc_finds_three_way_cycle() {
+    let goods: Vec<Good> = ["A", "B", "C"].iter().map(|id| good(id)).collect();
+
+    // Each agent wants the next agent's endowment: pairwise swaps cannot help
+    let mut agents = Vec::new();
+    for i in 0..3 {
+        let own = bundle(&[&goods[i]]);
+        let mut agent = Agent::new(format!("Agent{}", i), own.clone());
+        agent.add_preference(own, 5.0);
+        agent.add_preference(bundle(&[&goods[(i + 1) % 3]]), 10.0);
+        agents.push(agent);
+    }
+
+    let allocation = TtcMechanism::new().compute_allocation(&agents);
+    for i in 0..3 {
+        let expected = bundle(&[&goods[(i + 1) % 3]]);
+        assert_eq!(allocation.get_bundle(agents[i].id), Some(&expected));
+    }
+    assert!(BRACEMechanism::new(0.01).verify_core_stability(&agents, &allocation));
+}
+
+#[test]
+fn test_greedy_within_ratio() {
+    let a = good("A");
+    let b = good("B");
+    let goods = vec![a.clone(), b.clone()];
+
+    // Single-minded bidders: greedy takes the pair (10 / sqrt 2 > 6) and loses the split
+    let mut pair = Agent::new("Pair", HashSet::new());
+    pair.add_preference(bundle(&[&a, &b]), 10.0);
+    let mut left = Agent::new("Left", HashSet::new());
+    left.add_preference(bundle(&[&a]), 6.0);
+    let mut right = Agent::new("Right", HashSet::new());
+    right.add_preference(bundle(&[&b]), 6.0);
+    let agents = vec![pair, left, right];
+
+    let result = GreedyMechanism::new().run(&agents, &goods);
+    assert!(result.guarantee_holds);
+    assert_eq!(result.total_welfare, 10.0);
+    assert_eq!(result.allocation.get_bundle("Pair"), Some(&bundle(&[&a, &b])));
+    assert!(12.0 <= result.approximation_ratio * result.total_welfare);
+    assert!(BRACEMechanism::new(0.01).verify_feasibility(&result.allocation, &goods));
+}
+
+#[test]
+fn test_leximin_favours_worst_off() {
+    let a = good("A");
+    let b = good("B");
+    let c = good("C");
+    let goods = vec![a.clone(), b.clone(), c.clone()];
+
+    let mut x = Agent::new("X", HashSet::new());
+    x.add_preference(bundle(&[&a]), 1.0);
+    let mut y = Agent::new("Y", HashSet::new());
+    y.add_preference(bundle(&[&b]), 10.0);
+    y.add_preference(bundle(&[&c]), 1.0);
+    let mut z = Agent::new("Z", HashSet::new());
+    z.add_preference(bundle(&[&b]), 4.0);
+    z.add_preference(bundle(&[&c]), 0.5);
+    let agents = vec![x, y, z];
+
+    // Welfare would give B to Y (11.5); leximin lifts Z instead
+    let result = LeximinMechanism::new().run(&agents, &goods);
+    assert_eq!(result.allocation.get_bundle("Y"), Some(&bundle(&[&c])));
+    assert_eq!(result.allocation.get_bundle("Z"), Some(&bundle(&[&b])));
+    assert_eq!(result.min_utility(), 1.0);
+    let utilities: Vec<f64> = result.utilities.iter().map(|&(_, u)| u).collect();
+    assert_eq!(utilities, vec![1.0, 1.0, 4.0]);
+}
+
+#[test]
+fn test_envy_cycle_is_ef1() {
+    let goods: Vec<Good> = ["A", "B", "C", "D", "E"].iter().map(|id| good(id)).collect();
+    let weights = [[9.0, 7.0, 5.0, 2.0, 1.0], [1.0, 8.0, 8.0, 3.0, 6.0], [4.0, 4.0, 4.0, 4.0, 4.0]];
+    let agents: Vec<Agent> = weights
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let valuation = goods
+                .iter()
+                .zip(row)
+                .fold(AdditiveValuation::new(), |v, (g, &w)| v.with(g, w));
+            Agent::with_valuation(format!("Agent{}", i), HashSet::new(), valuation)
+        })
+        .collect();
+
+    let allocation = EnvyCycleMechanism::new().compute_allocation(&agents, &goods);
+    let brace = BRACEMechanism::new(0.01);
+    assert!(brace.verify_ef1(&agents, &allocation));
+    assert!(brace.verify_feasibility(&allocation, &goods));
+    assert!(brace.unallocated_goods(&allocation, &goods).is_empty());
+}
+
+#[test]
+fn test_verify_ef1_detects_envy() {
+    let a = good("A");
+    let b = good("B");
+    let valuation = AdditiveValuation::new().with(&a, 5.0).with(&b, 5.0);
+    let rich = Agent::with_valuation("Rich", HashSet::new(), valuation.clone());
+    let poor = Agent::with_valuation("Poor", HashSet::new(), valuation);
+    let agents = vec![rich, poor];
+
+    let mut allocation = Allocation::new();
+    allocation.assign("Rich", bundle(&[&a, &b]));
+    allocation.assign("Poor", Bundle::new());
+    assert!(!BRACEMechanism::new(0.01).verify_ef1(&agents, &allocation));
+
+    allocation.assign("Rich", bundle(&[&a]));
+    allocation.assign("Poor", bundle(&[&b]));
+    assert!(BRACEMechanism::new(0.01).verify_ef1(&agents, &allocation));
+}
+
+#[test]
+fn test_double_auction_clears_with_budget_balance() {
+    let a = good("A");
+    let b = good("B");
+    let c = good("C");
+
+    let mut pair_buyer = Agent::new("PairBuyer", HashSet::new());
+    pair_buyer.add_preference(bundle(&[&a, &b]), 10.0);
+    let mut lowballer = Agent::new("Lowballer", HashSet::new());
+    lowballer.add_preference(bundle(&[&c]), 1.0);
+
+    let asks = vec![
+        Ask::new("SellerA", bundle(&[&a]), 3.0),
+        Ask::new("SellerB", bundle(&[&b]), 1.0),
+        Ask::new("SellerC", bundle(&[&c]), 2.0),
+    ];
+    let result = DoubleAuction::new().run(&[pair_buyer, lowballer], &asks);
+
+    // The pair is assembled from two sellers; C's ask is above its only bid
+    assert_eq!(result.matches.len(), 1);
+    assert_eq!(result.matches[0].asks, vec![0, 1]);
+    assert!((result.surplus - 6.0).abs() < 1e-9);
+    assert!(result.is_budget_balanced());
+    assert_eq!(result.allocation.get_bundle("PairBuyer"), Some(&bundle(&[&a, &b])));
+    assert_eq!(result.allocation.get_bundle("SellerC"), Some(&bundle(&[&c])));
+
+    // Buyer pays the asks plus half the surplus; sellers split the rest by ask
+    let pays = result.payments[&AgentId::from("PairBuyer")];
+    assert!((pays - 7.0).abs() < 1e-9);
+    assert!((result.payments[&AgentId::from("SellerA")] + 5.25).abs() < 1e-9);
+    let price_sum = result.prices.get_price("A") + result.prices.get_price("B");
+    assert!((price_sum - pays).abs() < 1e-9);
+}
+
+#[test]
+fn test_cca_clock_and_supplementary_rounds() {
+    let a = good("A");
+    let b = good("B");
+
+    let mut pair = Agent::new("Pair", HashSet::new());
+    pair.add_preference(bundle(&[&a, &b]), 10.0);
+    let mut left = Agent::new("Left", HashSet::new());
+    left.add_preference(bundle(&[&a]), 6.0);
+    let mut right = Agent::new("Right", HashSet::new());
+    right.add_preference(bundle(&[&b]), 6.0);
+
+    let result =
+        CombinatorialClockAuction::new(1.0).run(&[pair, left, right], &[a.clone(), b.clone()]);
+
+    // The pair bidder drops out of the clock once both goods cost 5
+    assert!(result.converged);
+    assert_eq!(result.clock_prices.get_price("A"), 5.0);
+    assert!(!result.clock_bids.last().unwrap().contains_key(&AgentId::from("Pair")));
+    assert_eq!(result.supplementary_bids[&AgentId::from("Pair")][0].1, 10.0);
+
+    // Splitting the goods wins; core prices make the winners outbid the pair
+    assert_eq!(result.allocation.get_bundle("Left"), Some(&bundle(&[&a])));
+    assert_eq!(result.allocation.get_bundle("Right"), Some(&bundle(&[&b])));
+    assert!(result.revenue >= 10.0 - 1e-6);
+    assert!(result.payments[&AgentId::from("Left")] <= 6.0 + 1e-9);
+}
+
+#[test]
+fn test_smra_ascends_until_bidding_stops() {
+    let a = good("A");
+    let b = good("B");
+
+    let mut keen = Agent::new("Keen", HashSet::new());
+    keen.add_preference(bundle(&[&a]), 5.0);
+    let mut casual = Agent::new("Casual", HashSet::new());
+    casual.add_preference(bundle(&[&a]), 3.0);
+
+    let smra = SmraAuction::new(1.0).run(&[keen, casual], &[a.clone(), b.clone()]).unwrap();
+
+    // Bids go 0 (Keen), 1 (Casual), 2 (Keen); Casual will not pay 3
+    assert!(smra.converged);
+    assert_eq!(smra.rounds.len(), 4);
+    assert_eq!(smra.rounds[1].winners[&a.id], AgentId::from("Casual"));
+    assert_eq!(smra.result.allocation.get_bundle("Keen"), Some(&bundle(&[&a])));
+    assert_eq!(smra.payments[&AgentId::from("Keen")], 2.0);
+    assert_eq!(smra.result.prices[&a.id], 2.0);
+    assert_eq!(smra.result.unallocated_goods, vec![b.id]);
+
+    // Casual was not active in the last round, so it loses its eligibility
+    assert_eq!(smra.rounds.last().unwrap().eligibility[&AgentId::from("Casual")], 0);
+}
+
+#[test]
+fn test_deferred_acceptance_procurement() {
+    let a = good("A");
+    let b = good("B");
+
+    let mut both = Agent::new("Both", bundle(&[&a, &b]));
+    both.add_preference(bundle(&[&a, &b]), 10.0);
+    let mut only_a = Agent::new("OnlyA", bundle(&[&a]));
+    only_a.add_preference(bundle(&[&a]), 3.0);
+    let mut only_b = Agent::new("OnlyB", bundle(&[&b]));
+    only_b.add_preference(bundle(&[&b]), 4.0);
+    let sellers = [both, only_a, only_b];
+
+    let result = DeferredAcceptanceAuction::new(20.0, 1.0).run(&sellers, &[a.clone(), b.clone()]);
+
+    // Both drops out below its cost of 10, which makes the single-good sellers necessary
+    assert!(result.covered);
+    assert_eq!(result.winners, vec![AgentId::from("OnlyA"), AgentId::from("OnlyB")]);
+    assert_eq!(result.payments[&AgentId::from("OnlyA")], 10.0);
+    assert_eq!(result.total_cost, 20.0);
+    assert_eq!(result.allocation.get_bundle("Both"), Some(&bundle(&[&a, &b])));
+    assert_eq!(result.allocation.get_bundle("OnlyA"), Some(&Bundle::new()));
+    for seller in &sellers {
+        if let Some(payment) = result.payments.get(&seller.id) {
+            assert!(*payment >= seller.preference(&seller.endowment));
+        }
+    }
+
+    // Without a seller for B nothing can be bought
+    let uncovered = DeferredAcceptanceAuction::new(20.0, 1.0).run(&sellers[1..2], &[a, b]);
+    assert!(!uncovered.covered);
+    assert!(uncovered.winners.is_empty());
+}
+
+#[test]
+fn test_random_serial_dictatorship() {
+    let a = good("A");
+    let b = good("B");
+
+    let agents: Vec<Agent> = ["First", "Second", "Third"]
+        .iter()
+        .map(|id| {
+            let mut agent = Agent::new(*id, HashSet::new());
+            agent.add_preference(bundle(&[&a, &b]), 8.0);
+            agent.add_preference(bundle(&[&a]), 5.0);
+            agent.add_preference(bundle(&[&b]), 4.0);
+            agent
+        })
+        .collect();
+    let goods = [a.clone(), b.clone()];
+
+    // Whoever picks first takes both goods; the rest get nothing
+    let rsd = RandomSerialDictatorship::new(3);
+    let result = rsd.run(&agents, &goods).unwrap();
+    assert!(result.is_feasible);
+    assert_eq!(result.total_welfare, 8.0);
+    let winners: Vec<_> = agents
+        .iter()
+        .filter(|agent| result.allocation.get_bundle(agent.id).is_some_and(|b| !b.is_empty()))
+        .collect();
+    assert_eq!(winners.len(), 1);
+
+    // The same seed gives the same order
+    let again = rsd.compute_allocation(&agents, &goods);
+    assert_eq!(again.get_bundle(winners[0].id), Some(&bundle(&[&a, &b])));
+}
+
+#[test]
+fn test_probabilistic_serial_eating() {
+    let a = good("A");
+    let b = good("B");
+    let goods = [a.clone(), b.clone()];
+
+    let ranked = |id: &str, first: &Good, second: &Good| {
+        let mut agent = Agent::new(id, HashSet::new());
+        agent.add_preference(bundle(&[first]), 2.0);
+        agent.add_preference(bundle(&[second]), 1.0);
+        agent
+    };
+
+    // Both want A first, so both eat A until it is gone, then B
+    let same = [ranked("Agent1", &a, &b), ranked("Agent2", &a, &b)];
+    let ps = ProbabilisticSerial::new();
+    let shares = ps.compute_allocation(&same, &goods);
+    assert!((shares.good_share("Agent1", "A") - 0.5).abs() < 1e-9);
+    assert!((shares.good_share("Agent2", "B") - 0.5).abs() < 1e-9);
+    assert!(ps.verify_ordinal_efficiency(&same, &goods, &shares));
+
+    // Opposite favourites each get theirs for sure
+    let opposite = [ranked("Agent1", &a, &b), ranked("Agent2", &b, &a)];
+    let shares = ps.compute_allocation(&opposite, &goods);
+    assert!((shares.good_share("Agent1", "A") - 1.0).abs() < 1e-9);
+    assert!(ps.verify_ordinal_efficiency(&opposite, &goods, &shares));
+
+    // A coin flip between the two goods is stochastically dominated
+    let mut coin_flip = brace_sybil::FractionalAllocation::new();
+    for agent in &opposite {
+        coin_flip.add_share(agent.id, bundle(&[&a]), 0.5);
+        coin_flip.add_share(agent.id, bundle(&[&b]), 0.5);
+    }
+    assert!(!ps.verify_ordinal_efficiency(&opposite, &goods, &coin_flip));
+}
+
+#[test]
+fn test_probabilistic_serial_as_lottery() {
+    let goods: Vec<Good> = ["a", "b", "c"].iter().map(|id| good(id)).collect();
+    let mut agents = Vec::new();
+    for (id, order) in [
+        ("Agent1", ["a", "b", "c"]),
+        ("Agent2", ["a", "c", "b"]),
+        ("Agent3", ["b", "a", "c"]),
+    ] {
+        let mut agent = Agent::new(id, HashSet::new());
+        for (rank, g) in order.iter().enumerate() {
+            let g = goods.iter().find(|x| x.id == GoodId::from(*g)).unwrap();
+            agent.add_preference(bundle(&[g]), (3 - rank) as f64);
+        }
+        agents.push(agent);
+    }
+
+    let random = ProbabilisticSerial::new().compute_allocation(&agents, &goods);
+    let lottery = random.to_lottery(&goods).unwrap();
+    let total: f64 = lottery.iter().map(|(_, p)| p).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+
+    // Every outcome is a feasible assignment and together they reproduce the shares
+    let mechanism = BRACEMechanism::new(0.01);
+    for (allocation, _) in &lottery {
+        assert!(mechanism.verify_feasibility(allocation, &goods));
+    }
+    let expected = FractionalAllocation::from_lottery(&lottery);
+    for agent in &agents {
+        for g in &goods {
+            let share = random.good_share(agent.id, g.id);
+            assert!((expected.good_share(agent.id, g.id) - share).abs() < 1e-9);
+        }
+    }
+
+    // Bundles of several goods are not an assignment
+    let mut pairs = FractionalAllocation::new();
+    pairs.add_share("Agent1", bundle(&[&goods[0], &goods[1]]), 1.0);
+    assert!(matches!(
+        pairs.to_lottery(&goods),
+        Err(AuctionError::NotAnAssignment(_))
+    ));
+}
@@ -0,0 +1,52 @@
+use brace_sybil::{bundle, Agent, AuctionError, BRACEMechanism, Good, OnlineAuction};
+
+fn good(id: &str) -> Good {
+    Good::new(id, format!("Good {}", id))
+}
+
+#[test]
+fn test_online_arrivals_keep_commitments() {
+    let goods = vec![good("A"), good("B"), good("C")];
+    let mut auction = OnlineAuction::new(goods.clone()).unwrap();
+
+    // Agent1 brings A but would rather have the unclaimed B
+    let mut agent1 = Agent::new("Agent1", bundle!["A"]);
+    agent1.add_preference(bundle!["A"], 5.0);
+    agent1.add_preference(bundle!["B"], 8.0);
+    assert_eq!(auction.arrive(agent1).unwrap(), bundle!["B"]);
+    assert_eq!(auction.available("A"), 1);
+    assert_eq!(auction.available("B"), 0);
+
+    // B is committed, so Agent2 settles for A and C
+    let mut agent2 = Agent::new("Agent2", bundle![]);
+    agent2.add_preference(bundle!["B", "C"], 10.0);
+    agent2.add_preference(bundle!["A", "C"], 6.0);
+    assert_eq!(auction.arrive(agent2).unwrap(), bundle!["A", "C"]);
+    assert_eq!(auction.allocation().get_bundle("Agent1"), Some(&bundle!["B"]));
+    assert!(auction.unallocated_goods().is_empty());
+
+    // Nothing left: a newcomer keeps an empty bundle
+    let mut agent3 = Agent::new("Agent3", bundle![]);
+    agent3.add_preference(bundle!["A"], 20.0);
+    assert_eq!(auction.arrive(agent3).unwrap(), bundle![]);
+
+    let mechanism = BRACEMechanism::new(0.01);
+    assert!(mechanism.verify_feasibility(auction.allocation(), &goods));
+    assert!(mechanism.verify_individual_rationality(auction.agents(), auction.allocation()));
+}
+
+#[test]
+fn test_online_rejects_invalid_arrivals() {
+    let mut auction = OnlineAuction::new(vec![good("A")]).unwrap();
+    auction.arrive(Agent::new("Agent1", bundle!["A"])).unwrap();
+
+    let duplicate = auction.arrive(Agent::new("Agent1", bundle![]));
+    assert!(matches!(duplicate, Err(AuctionError::DuplicateAgent(id)) if id == "Agent1"));
+
+    let committed = auction.arrive(Agent::new("Agent2", bundle!["A"]));
+    assert!(matches!(committed, Err(AuctionError::CommittedGood { .. })));
+
+    let unknown = auction.arrive(Agent::new("Agent3", bundle!["Z"]));
+    assert!(matches!(unknown, Err(AuctionError::UnknownEndowedGood { .. })));
+    assert_eq!(auction.agents().len(), 1);
+}
@@ -0,0 +1,232 @@
+use brace_sybil::cooperative::{shapley_values, ShapleyConfig};
+use brace_sybil::payments::{
+    vcg_payments, CoreSelecting, FirstPrice, NoPayments, PaymentRule, PostedPrices, Vcg,
+};
+use brace_sybil::pricing::{core_selecting_payments, PriceVector, MAX_CORE_AGENTS};
+use brace_sybil::wdp::solve_exact;
+use brace_sybil::{Agent, AgentId, Allocation, BRACEMechanism, Bundle, CombinatorialAuction, Good};
+use std::collections::{HashMap, HashSet};
+
+fn good(id: &str) -> Good {
+    Good::new(id, format!("Good {}", id))
+}
+
+fn bundle(goods: &[&Good]) -> Bundle {
+    goods.iter().map(|g| (*g).clone()).collect()
+}
+
+#[test]
+fn test_vcg_payments_single_minded() {
+    let a = good("A");
+    let b = good("B");
+    let goods = vec![a.clone(), b.clone()];
+
+    let mut agent1 = Agent::new("Agent1".to_string(), HashSet::new());
+    agent1.add_preference(bundle(&[&a, &b]), 10.0);
+    let mut agent2 = Agent::new("Agent2".to_string(), HashSet::new());
+    agent2.add_preference(bundle(&[&a]), 6.0);
+    let mut agent3 = Agent::new("Agent3".to_string(), HashSet::new());
+    agent3.add_preference(bundle(&[&b]), 3.0);
+    let agents = vec![agent1, agent2, agent3];
+
+    let (allocation, welfare) = solve_exact(&agents, &goods);
+    assert_eq!(welfare, 10.0);
+    assert_eq!(allocation.get_bundle("Agent1").unwrap().len(), 2);
+
+    // The winner pays the externality it imposes on the others
+    let payments = vcg_payments(&agents, &goods, &allocation);
+    assert_eq!(payments[&AgentId::from("Agent1")], 9.0);
+    assert_eq!(payments[&AgentId::from("Agent2")], 0.0);
+    assert_eq!(payments[&AgentId::from("Agent3")], 0.0);
+}
+
+#[test]
+fn test_core_payments_cover_losing_coalition() {
+    // Classic threshold problem: two local bidders jointly outbid the
+    // global bidder's VCG price, so VCG revenue is outside the core
+    let a = good("A");
+    let b = good("B");
+    let goods = vec![a.clone(), b.clone()];
+
+    let mut local1 = Agent::new("Local1".to_string(), HashSet::new());
+    local1.add_preference(bundle(&[&a]), 8.0);
+    let mut local2 = Agent::new("Local2".to_string(), HashSet::new());
+    local2.add_preference(bundle(&[&b]), 8.0);
+    let mut global = Agent::new("Global".to_string(), HashSet::new());
+    global.add_preference(bundle(&[&a, &b]), 10.0);
+    let agents = vec![local1, local2, global];
+
+    let (allocation, _) = solve_exact(&agents, &goods);
+    let vcg = vcg_payments(&agents, &goods, &allocation);
+    assert_eq!(vcg[&AgentId::from("Local1")], 2.0);
+    assert_eq!(vcg[&AgentId::from("Local2")], 2.0);
+
+    // Core revenue must reach the global bidder's value of 10
+    let core = core_selecting_payments(&agents, &goods, &allocation);
+    assert!((core[&AgentId::from("Local1")] - 5.0).abs() < 1e-6);
+    assert!((core[&AgentId::from("Local2")] - 5.0).abs() < 1e-6);
+    assert_eq!(core[&AgentId::from("Global")], 0.0);
+}
+
+#[test]
+fn test_core_payments_agent_cap() {
+    // The threshold problem again, with many global bidders: past the cap
+    // coalitions are not enumerated, so the local winners pay their VCG
+    // prices of 2 each instead of the core's 5
+    let a = good("A");
+    let b = good("B");
+    let goods = vec![a.clone(), b.clone()];
+    let mut agents = Vec::new();
+    for k in 0..MAX_CORE_AGENTS + 58 {
+        let mut agent = Agent::new(format!("Global{}", k), HashSet::new());
+        agent.add_preference(bundle(&[&a, &b]), 10.0);
+        agents.push(agent);
+    }
+    let mut local1 = Agent::new("Local1", HashSet::new());
+    local1.add_preference(bundle(&[&a]), 8.0);
+    let mut local2 = Agent::new("Local2", HashSet::new());
+    local2.add_preference(bundle(&[&b]), 8.0);
+    agents.extend([local1, local2]);
+    assert!(agents.len() >= 64);
+
+    let (allocation, _) = solve_exact(&agents, &goods);
+    let core = core_selecting_payments(&agents, &goods, &allocation);
+    assert_eq!(core, vcg_payments(&agents, &goods, &allocation));
+    assert_eq!(core[&AgentId::from("Local1")], 2.0);
+}
+
+#[test]
+fn test_shapley_values_split_welfare() {
+    let a = good("A");
+    let goods = vec![a.clone()];
+    let mut keen = Agent::new("Keen", HashSet::new());
+    keen.add_preference(bundle(&[&a]), 10.0);
+    let mut casual = Agent::new("Casual", HashSet::new());
+    casual.add_preference(bundle(&[&a]), 6.0);
+    let agents = vec![keen, casual];
+
+    // Keen adds 10 alone or 4 after Casual; Casual adds 6 alone or nothing
+    let exact = shapley_values(&agents, &goods, &ShapleyConfig::default());
+    assert!((exact[&AgentId::from("Keen")] - 7.0).abs() < 1e-9);
+    assert!((exact[&AgentId::from("Casual")] - 3.0).abs() < 1e-9);
+
+    let sampled = ShapleyConfig {
+        exact_limit: 0,
+        samples: 2000,
+        seed: 5,
+    };
+    let estimate = shapley_values(&agents, &goods, &sampled);
+    assert!((estimate[&AgentId::from("Keen")] - 7.0).abs() < 0.5);
+    let total: f64 = estimate.values().sum();
+    assert!((total - 10.0).abs() < 1e-9);
+
+    let result = CombinatorialAuction::new(agents, goods, 0.01)
+        .unwrap()
+        .with_shapley_values(ShapleyConfig::default())
+        .run()
+        .unwrap();
+    assert_eq!(result.shapley_values, Some(exact));
+}
+
+#[test]
+fn test_payment_rules() {
+    let a = good("A");
+    let b = good("B");
+    let goods = vec![a.clone(), b.clone()];
+    let mut agent1 = Agent::new("Agent1", bundle(&[&a]));
+    agent1.add_preference(bundle(&[&b]), 10.0);
+    agent1.add_preference(bundle(&[&a]), 5.0);
+    let mut agent2 = Agent::new("Agent2", bundle(&[&b]));
+    agent2.add_preference(bundle(&[&a]), 10.0);
+    agent2.add_preference(bundle(&[&b]), 5.0);
+    let agents = vec![agent1, agent2];
+    let id = AgentId::from("Agent1");
+
+    let auction = || CombinatorialAuction::new(agents.clone(), goods.clone(), 0.01).unwrap();
+
+    // By default agents trade at the final prices, which balances out in an exchange
+    let posted = auction().run().unwrap();
+    let expected = posted.prices[&b.id] - posted.prices[&a.id];
+    assert!((posted.payments[&id] - expected).abs() < 1e-9);
+    assert!(posted.revenue.abs() < 1e-9);
+    assert!((posted.net_utility[&id] - (10.0 - expected)).abs() < 1e-9);
+    let explicit = auction().with_payment_rule(PostedPrices).run().unwrap();
+    assert_eq!(explicit.payments, posted.payments);
+
+    // Each agent gains 5 from the swap and pays all of it
+    let first_price = auction().with_payment_rule(FirstPrice).run().unwrap();
+    assert_eq!(first_price.payments[&id], 5.0);
+    assert_eq!(first_price.revenue, 10.0);
+
+    // Neither agent keeps the other from anything, so VCG charges nothing
+    let vcg = auction().with_payment_rule(Vcg).run().unwrap();
+    assert!(vcg.payments[&id].abs() < 1e-9);
+    let core = auction().with_payment_rule(CoreSelecting).run().unwrap();
+    assert!(core.revenue >= vcg.revenue - 1e-9);
+
+    let none = auction().with_payment_rule(NoPayments).run().unwrap();
+    assert_eq!(none.revenue, 0.0);
+    assert_eq!(none.net_utility[&id], 10.0);
+}
+
+/// Charges every agent the same flat fee
+struct Flat(f64);
+
+impl PaymentRule for Flat {
+    fn payments(
+        &self,
+        agents: &[Agent],
+        _goods: &[Good],
+        _allocation: &Allocation,
+        _prices: &PriceVector,
+    ) -> HashMap<AgentId, f64> {
+        agents.iter().map(|agent| (agent.id, self.0)).collect()
+    }
+}
+
+#[test]
+fn test_quasi_linear_utilities() {
+    let a = good("A");
+    let b = good("B");
+    let goods = vec![a.clone(), b.clone()];
+    let mut agent1 = Agent::new("Agent1", bundle(&[&a]));
+    agent1.add_preference(bundle(&[&b]), 10.0);
+    agent1.add_preference(bundle(&[&a]), 5.0);
+    agent1.money = 20.0;
+    let mut agent2 = Agent::new("Agent2", bundle(&[&b]));
+    agent2.add_preference(bundle(&[&a]), 10.0);
+    agent2.add_preference(bundle(&[&b]), 5.0);
+    let agents = vec![agent1, agent2];
+    let id = AgentId::from("Agent1");
+    assert_eq!(agents[0].utility(&bundle(&[&b]), 3.0), 27.0);
+    assert_eq!(agents[0].utility(&bundle(&[&a]), -2.0), 27.0);
+
+    let auction = || CombinatorialAuction::new(agents.clone(), goods.clone(), 0.01).unwrap();
+
+    // Money is carried through: what is left after paying, and in welfare
+    let posted = auction().run().unwrap();
+    assert!(posted.is_individually_rational);
+    assert!((posted.money[&id] - (20.0 - posted.payments[&id])).abs() < 1e-9);
+    assert!((posted.quasi_linear_welfare - (posted.total_welfare + 20.0)).abs() < 1e-9);
+
+    // Paying all of the gain leaves each agent exactly as well off
+    let first_price = auction().with_payment_rule(FirstPrice).run().unwrap();
+    assert!(first_price.is_individually_rational);
+    assert_eq!(first_price.money[&id], 15.0);
+
+    // Paying more than the gain does not, although the swap is still preferred
+    let overcharged = auction().with_payment_rule(Flat(6.0)).run().unwrap();
+    assert!(!overcharged.is_individually_rational);
+    assert_eq!(overcharged.money[&AgentId::from("Agent2")], -6.0);
+    let mechanism = BRACEMechanism::new(0.01);
+    assert!(mechanism.verify_individual_rationality(&agents, &overcharged.allocation));
+    let fee = |amount: f64| agents.iter().map(|agent| (agent.id, amount)).collect();
+    let allocation = &overcharged.allocation;
+    assert!(!mechanism.verify_quasi_linear_individual_rationality(&agents, allocation, &fee(6.0)));
+    assert!(mechanism.verify_quasi_linear_individual_rationality(&agents, allocation, &fee(5.0)));
+
+    let json = serde_json::to_string(&agents[0]).unwrap();
+    let restored: Agent = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.money, 20.0);
+    assert!(!serde_json::to_string(&agents[1]).unwrap().contains("money"));
+}
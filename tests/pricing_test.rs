@@ -0,0 +1,467 @@
+use brace_sybil::pricing::{
+    compute_bundle_prices, compute_equilibrium_prices, compute_personalized_prices,
+    ce_violations, excess_demand, max_ce_violation, supports_allocation, PriceAdjustmentConfig,
+    PricingAlgorithm, StepRule,
+};
+use brace_sybil::{
+    Agent, AgentId, Allocation, BRACEMechanism, Bundle, CombinatorialAuction, EpsilonPolicy, Good, GoodId,
+    PriceVector,
+};
+use std::collections::HashSet;
+
+fn good(id: &str) -> Good {
+    Good::new(id, format!("Good {}", id))
+}
+
+fn bundle(goods: &[&Good]) -> Bundle {
+    goods.iter().map(|g| (*g).clone()).collect()
+}
+
+#[test]
+fn test_demand_respects_budget() {
+    let a = good("A");
+    let b = good("B");
+
+    let mut agent = Agent::new("Agent1".to_string(), HashSet::new());
+    agent.add_preference(bundle(&[&a, &b]), 10.0);
+    agent.add_preference(bundle(&[&a]), 5.0);
+
+    let mut prices = PriceVector::new();
+    prices.set_price("A".to_string(), 3.0);
+    prices.set_price("B".to_string(), 3.0);
+
+    // Without a budget the pair is the best deal
+    assert_eq!(prices.demand_set(&agent), vec![bundle(&[&a, &b])]);
+
+    agent.budget = Some(5.0);
+    assert_eq!(prices.demand_set(&agent), vec![bundle(&[&a])]);
+    assert_eq!(prices.budget_relaxation(&agent, &bundle(&[&a, &b]), None), 1.0);
+}
+
+#[test]
+fn test_auction_reports_budget_relaxation() {
+    let a = good("A");
+    let mut agent = Agent::new("Agent1".to_string(), bundle(&[&a]));
+    agent.add_preference(bundle(&[&a]), 5.0);
+    agent.budget = Some(0.0);
+
+    let result = CombinatorialAuction::new(vec![agent], vec![a], 0.01).unwrap().run().unwrap();
+    let relaxation = result.budget_relaxation[&AgentId::from("Agent1")];
+    assert!(relaxation >= 0.0);
+    assert_eq!(relaxation, result.prices[&GoodId::from("A")]);
+}
+
+#[test]
+fn test_reserve_prices() {
+    let a = good("A");
+    let b = good("B").with_reserve_price(4.0);
+
+    let mut agent = Agent::new("Agent1".to_string(), bundle(&[&a]));
+    agent.add_preference(bundle(&[&a]), 5.0);
+    agent.add_preference(bundle(&[&b]), 3.0);
+
+    let auction = CombinatorialAuction::new(vec![agent.clone()], vec![a.clone(), b.clone()], 0.01).unwrap();
+    let result = auction.run().unwrap();
+
+    // The seller keeps B, whose price never drops below its reserve
+    assert_eq!(result.unallocated_goods, vec![b.id]);
+    assert!(result.prices[&GoodId::from("B")] >= 4.0);
+    assert!(result.is_feasible);
+
+    // Handing B to an agent who values it below the reserve is rejected
+    let mut allocation = result.allocation.clone();
+    allocation.assign("Agent1".to_string(), bundle(&[&b]));
+    let mechanism = BRACEMechanism::new(0.01);
+    assert!(!mechanism.verify_reserve_prices(&[agent], &allocation));
+}
+
+#[test]
+fn test_personalized_prices_support_allocation() {
+    let a = good("A");
+    let b = good("B");
+    let goods = vec![a.clone(), b.clone()];
+
+    let mut agent1 = Agent::new("Agent1".to_string(), bundle(&[&a]));
+    agent1.add_preference(bundle(&[&a, &b]), 10.0);
+    agent1.add_preference(bundle(&[&a]), 5.0);
+    let mut agent2 = Agent::new("Agent2".to_string(), bundle(&[&b]));
+    agent2.add_preference(bundle(&[&b]), 1.0);
+    let agents = vec![agent1, agent2];
+
+    let mut allocation = Allocation::new();
+    allocation.assign("Agent1", bundle(&[&a]));
+    allocation.assign("Agent2", bundle(&[&b]));
+
+    // At zero prices Agent1 would rather have both goods
+    assert!(!supports_allocation(&PriceVector::new(), &agents, &allocation, 0.01));
+
+    let personalized = compute_personalized_prices(&agents, &goods, &allocation, 0.01);
+    assert!(personalized.get_price("Agent1", "B") >= 5.0);
+    assert_eq!(personalized.get_price("Agent2", "B"), 0.0);
+    for agent in &agents {
+        let prices = personalized.agent_prices(agent.id);
+        assert!(supports_allocation(&prices, std::slice::from_ref(agent), &allocation, 0.01));
+    }
+}
+
+#[test]
+fn test_bundle_prices_support_complements() {
+    let a = good("A");
+    let b = good("B");
+
+    let mut pair = Agent::new("Pair", HashSet::new());
+    pair.add_preference(bundle(&[&a, &b]), 3.0);
+    let mut left = Agent::new("Left", HashSet::new());
+    left.add_preference(bundle(&[&a]), 2.0);
+    let mut right = Agent::new("Right", HashSet::new());
+    right.add_preference(bundle(&[&b]), 2.0);
+    let agents = vec![pair, left, right];
+
+    let mut allocation = Allocation::new();
+    allocation.assign("Pair", bundle(&[&a, &b]));
+
+    // Linear prices would need p(A) >= 2, p(B) >= 2 and p(A) + p(B) <= 3
+    let prices = compute_bundle_prices(&agents, &allocation);
+    assert!(prices.has_bundle_prices());
+    assert_eq!(prices.get_bundle_price(&bundle(&[&a])), Some(2.0));
+    assert_eq!(prices.bundle_price(&bundle(&[&a, &b])), 0.0);
+    assert!(supports_allocation(&prices, &agents, &allocation, 1e-9));
+
+    // The bundle table takes precedence over item prices
+    let mut linear = PriceVector::new();
+    linear.set_price("A", 1.0);
+    linear.set_price("B", 1.0);
+    assert_eq!(linear.bundle_price(&bundle(&[&a, &b])), 2.0);
+    linear.set_bundle_price(&bundle(&[&a, &b]), 1.5);
+    assert_eq!(linear.bundle_price(&bundle(&[&a, &b])), 1.5);
+    linear.clear_bundle_price(&bundle(&[&a, &b]));
+    assert_eq!(linear.bundle_price(&bundle(&[&a, &b])), 2.0);
+}
+
+#[test]
+fn test_price_vector_arithmetic() {
+    let a = good("A");
+    let b = good("B");
+    let mut p = PriceVector::new();
+    p.set_price("A", 1.0);
+    p.set_price("B", -2.0);
+    let mut q = PriceVector::new();
+    q.set_price("A", 3.0);
+    q.set_price("C", 4.0);
+
+    let sum = p.add(&q);
+    assert_eq!(sum.get_price("A"), 4.0);
+    assert_eq!(sum.get_price("B"), -2.0);
+    assert_eq!(sum.get_price("C"), 4.0);
+
+    let halfway = p.lerp(&q, 0.5);
+    assert_eq!(halfway.get_price("A"), 2.0);
+    assert_eq!(halfway.get_price("B"), -1.0);
+    assert_eq!(halfway.get_price("C"), 2.0);
+
+    // |1 - 3| + |-2 - 0| + |0 - 4|
+    assert_eq!(p.l1_distance(&q), 8.0);
+    assert_eq!(p.linf_distance(&q), 4.0);
+    assert_eq!(p.linf_distance(&p), 0.0);
+
+    // Scaling and projection also cover bundle prices
+    p.set_bundle_price(&bundle(&[&a, &b]), -1.0);
+    let doubled = p.scale(2.0);
+    assert_eq!(doubled.get_price("A"), 2.0);
+    assert_eq!(doubled.bundle_price(&bundle(&[&a, &b])), -2.0);
+    let projected = p.project_nonnegative();
+    assert_eq!(projected.get_price("A"), 1.0);
+    assert_eq!(projected.get_price("B"), 0.0);
+    assert_eq!(projected.bundle_price(&bundle(&[&a, &b])), 0.0);
+}
+
+#[test]
+fn test_price_versions_invalidate_net_utilities() {
+    let a = good("A");
+    let b = good("B");
+    let mut agent = Agent::new("Agent1", Bundle::new());
+    agent.add_preference(bundle(&[&a, &b]), 10.0);
+    agent.add_preference(bundle(&[&a]), 6.0);
+
+    let mut prices = PriceVector::new();
+    prices.set_price("A", 2.0);
+    prices.set_price("B", 3.0);
+    let version = prices.version();
+    assert_eq!(prices.net_utility(&agent, &bundle(&[&a, &b])), 5.0);
+    assert_eq!(prices.demand_set(&agent), vec![bundle(&[&a, &b])]);
+
+    // Clones keep the version; separately built vectors never share one
+    let copy = prices.clone();
+    assert_eq!(copy.version(), version);
+    assert_eq!(copy.net_utility(&agent, &bundle(&[&a, &b])), 5.0);
+    assert_ne!(PriceVector::new().version(), PriceVector::new().version());
+
+    // Every kind of price change moves to a new version and re-prices bundles
+    prices.set_price("B", 4.5);
+    assert_ne!(prices.version(), version);
+    assert_eq!(prices.net_utility(&agent, &bundle(&[&a, &b])), 3.5);
+    assert_eq!(prices.demand_set(&agent), vec![bundle(&[&a])]);
+    assert_eq!(copy.net_utility(&agent, &bundle(&[&a, &b])), 5.0);
+
+    let before = prices.version();
+    prices.set_bundle_price(&bundle(&[&a, &b]), 1.0);
+    assert_ne!(prices.version(), before);
+    assert_eq!(prices.net_utility(&agent, &bundle(&[&a, &b])), 9.0);
+    prices.clear_bundle_price(&bundle(&[&a, &b]));
+    assert_eq!(prices.net_utility(&agent, &bundle(&[&a, &b])), 3.5);
+
+    // Preferences are read fresh, so changing them needs no new version
+    agent.set_preference(bundle(&[&a, &b]), 20.0);
+    assert_eq!(prices.net_utility(&agent, &bundle(&[&a, &b])), 13.5);
+}
+
+/// Agent1 (A = 5) is allocated A over Agent2 (A = 3.5); B is unwanted
+fn contested_good() -> (Vec<Agent>, Vec<Good>, Allocation) {
+    let a = good("A");
+    let b = good("B");
+
+    let mut first = Agent::new("Agent1", HashSet::new());
+    first.add_preference(bundle(&[&a]), 5.0);
+    let mut second = Agent::new("Agent2", HashSet::new());
+    second.add_preference(bundle(&[&a]), 3.5);
+
+    let mut allocation = Allocation::new();
+    allocation.assign("Agent1", bundle(&[&a]));
+    allocation.assign("Agent2", Bundle::new());
+    (vec![first, second], vec![a, b], allocation)
+}
+
+#[test]
+fn test_price_adjustment_config() {
+    let (agents, goods, allocation) = contested_good();
+
+    // A is over-demanded until its price reaches Agent2's value
+    let config = PriceAdjustmentConfig {
+        step_size: 1.0,
+        max_iterations: 3,
+        ..Default::default()
+    };
+    let prices = compute_equilibrium_prices(&agents, &goods, &allocation, 0.01, &config);
+    assert!((prices.get_price("A") - 3.0).abs() < 1e-9);
+
+    // Damping halves the step each iteration: 1 + 0.5 + 0.25
+    let damped = PriceAdjustmentConfig { damping: 0.5, ..config };
+    let prices = compute_equilibrium_prices(&agents, &goods, &allocation, 0.01, &damped);
+    assert!((prices.get_price("A") - 1.75).abs() < 1e-9);
+
+    // The numeraire is pinned at 1
+    let normalized = PriceAdjustmentConfig {
+        numeraire: Some(GoodId::from("B")),
+        ..config
+    };
+    let prices = compute_equilibrium_prices(&agents, &goods, &allocation, 0.01, &normalized);
+    assert_eq!(prices.get_price("B"), 1.0);
+}
+
+#[test]
+fn test_price_adjustment_lowers_under_demanded_prices() {
+    let (agents, goods, allocation) = contested_good();
+
+    // 10 overshoots both values, so A falls back to 5, where Agent1 is
+    // indifferent and keeps it
+    let config = PriceAdjustmentConfig {
+        step_size: 10.0,
+        damping: 0.5,
+        ..Default::default()
+    };
+    let prices = compute_equilibrium_prices(&agents, &goods, &allocation, 0.01, &config);
+    assert!((prices.get_price("A") - 5.0).abs() < 1e-9);
+    assert!(supports_allocation(&prices, &agents, &allocation, 1e-9));
+    // Nobody wants B, so it stays at its reserve
+    assert_eq!(prices.get_price("B"), 0.0);
+}
+
+#[test]
+fn test_step_rules() {
+    let (agents, goods, allocation) = contested_good();
+
+    let config = PriceAdjustmentConfig {
+        step_size: 1.0,
+        max_iterations: 3,
+        ..Default::default()
+    };
+    let price_of_a = |step_rule| {
+        let config = PriceAdjustmentConfig { step_rule, ..config };
+        compute_equilibrium_prices(&agents, &goods, &allocation, 0.01, &config).get_price("A")
+    };
+
+    // 1 + 1/sqrt(2) + 1/sqrt(3)
+    let diminishing = 1.0 + 0.5_f64.sqrt() + (1.0_f64 / 3.0).sqrt();
+    assert!((price_of_a(StepRule::Diminishing) - diminishing).abs() < 1e-9);
+
+    // Raising A always narrows Agent2's gap, so the full step is taken
+    let backtracking = StepRule::Backtracking {
+        shrink: 0.5,
+        max_tries: 3,
+    };
+    assert!((price_of_a(backtracking) - 3.0).abs() < 1e-9);
+
+    // The first step equals the whole gap (3.5) and lands on equilibrium
+    assert!((price_of_a(StepRule::Polyak) - 3.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_subgradient_dual_prices() {
+    let (agents, goods, allocation) = contested_good();
+
+    // The dual value meets the welfare of 5 once A costs at least 3.5
+    let config = PriceAdjustmentConfig {
+        algorithm: PricingAlgorithm::Subgradient,
+        ..Default::default()
+    };
+    let prices = compute_equilibrium_prices(&agents, &goods, &allocation, 0.01, &config);
+    let price = prices.get_price("A");
+    assert!((3.49..=5.0).contains(&price));
+    assert!(supports_allocation(&prices, &agents, &allocation, 0.01));
+    assert_eq!(prices.get_price("B"), 0.0);
+
+    // A Polyak step closes the duality gap of 3.5 at once
+    let polyak = PriceAdjustmentConfig {
+        step_rule: StepRule::Polyak,
+        ..config
+    };
+    let prices = compute_equilibrium_prices(&agents, &goods, &allocation, 0.01, &polyak);
+    assert!((prices.get_price("A") - 3.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_per_agent_epsilon_policy() {
+    let a = good("A").with_reserve_price(10.0);
+
+    let mut agent = Agent::new("Agent1", HashSet::new());
+    agent.add_preference(bundle(&[&a]), 9.5);
+    agent.budget = Some(9.0);
+
+    let mut allocation = Allocation::new();
+    allocation.assign("Agent1", bundle(&[&a]));
+    let mut prices = PriceVector::new();
+    prices.set_price("A", 10.0);
+
+    let strict = BRACEMechanism::new(0.01);
+    assert!(!strict.verify_reserve_prices(&[agent.clone()], &allocation));
+    assert!(!strict.verify_budget_relaxation(&[agent.clone()], &allocation, &prices));
+
+    // Agent1 tolerates a larger relaxation than everyone else
+    let policy = EpsilonPolicy::uniform(0.01).with_agent("Agent1", 1.0);
+    assert_eq!(policy.for_agent("Other"), 0.01);
+    let tolerant = BRACEMechanism::new(policy);
+    assert!(tolerant.verify_reserve_prices(&[agent.clone()], &allocation));
+    assert!(tolerant.verify_budget_relaxation(&[agent], &allocation, &prices));
+}
+
+#[test]
+fn test_demand_oracle() {
+    use brace_sybil::bundle;
+    use brace_sybil::valuation::{AdditiveValuation, DemandOracle, FnValuation, UnitDemandValuation};
+
+    let a = Good::new("A", "A");
+    let b = Good::new("B", "B");
+    let mut prices = PriceVector::new();
+    prices.set_price("A", 1.0);
+    prices.set_price("B", 5.0);
+
+    let additive = AdditiveValuation::new().with(&a, 3.0).with(&b, 4.0);
+    assert_eq!(additive.demand(&prices), vec![bundle!["A"]]);
+    let unit = UnitDemandValuation::new().with(&a, 5.0).with(&b, 2.0);
+    prices.set_price("B", 0.0);
+    prices.set_price("A", 4.0);
+    assert_eq!(unit.demand(&prices), vec![bundle!["B"]]);
+
+    // An agent with no listed bundles still has a demand through its oracle
+    #[derive(Debug)]
+    struct WantsEverything;
+    impl DemandOracle for WantsEverything {
+        fn demand(&self, _prices: &PriceVector) -> Vec<Bundle> {
+            vec![bundle!["A", "B"]]
+        }
+    }
+    let mut agent = Agent::with_valuation(
+        "Agent1",
+        Bundle::new(),
+        FnValuation::new(|bundle| 10.0 * bundle.len() as f64),
+    );
+    assert!(agent.preference_bundles().is_empty());
+    agent.set_demand_oracle(WantsEverything);
+    assert_eq!(prices.demand_set(&agent), vec![bundle!["A", "B"]]);
+    agent.budget = Some(1.0);
+    assert!(prices.demand_set(&agent).is_empty());
+
+    // Pricing consults the oracle: the agent is in demand for its allocation
+    let mut allocation = Allocation::new();
+    allocation.assign("Agent1", bundle!["A", "B"]);
+    agent.budget = None;
+    let agents = vec![agent];
+    let prices = compute_equilibrium_prices(
+        &agents,
+        &[a, b],
+        &allocation,
+        0.01,
+        &PriceAdjustmentConfig::default(),
+    );
+    assert!(supports_allocation(&prices, &agents, &allocation, 0.01));
+}
+
+#[test]
+fn test_ce_violation_measures_forgone_utility() {
+    let a = good("A");
+    let b = good("B");
+
+    let mut agent1 = Agent::new("Agent1", bundle(&[&a]));
+    agent1.add_preference(bundle(&[&a, &b]), 10.0);
+    agent1.add_preference(bundle(&[&a]), 5.0);
+    let mut agent2 = Agent::new("Agent2", bundle(&[&b]));
+    agent2.add_preference(bundle(&[&b]), 1.0);
+    let agents = vec![agent1, agent2];
+
+    let mut allocation = Allocation::new();
+    allocation.assign("Agent1", bundle(&[&a]));
+    allocation.assign("Agent2", bundle(&[&b]));
+
+    // With B priced at 2, Agent1 forgoes (10 - 2) - 5 = 3
+    let mut prices = PriceVector::new();
+    prices.set_price("B", 2.0);
+    let violations = ce_violations(&prices, &agents, &allocation);
+    assert!((violations[&AgentId::from("Agent1")] - 3.0).abs() < 1e-9);
+    assert_eq!(violations[&AgentId::from("Agent2")], 0.0);
+    assert!((max_ce_violation(&prices, &agents, &allocation) - 3.0).abs() < 1e-9);
+
+    prices.set_price("B", 5.0);
+    assert_eq!(max_ce_violation(&prices, &agents, &allocation), 0.0);
+}
+
+#[test]
+fn test_excess_demand_reports_imbalances() {
+    let a = good("A");
+    let b = good("B");
+    let c = good("C").with_reserve_price(1.0);
+    let goods = vec![a.clone(), b.clone(), c.clone()];
+
+    let mut agent1 = Agent::new("Agent1", HashSet::new());
+    agent1.add_preference(bundle(&[&a]), 5.0);
+    let mut agent2 = Agent::new("Agent2", HashSet::new());
+    agent2.add_preference(bundle(&[&a]), 4.0);
+    agent2.add_preference(bundle(&[&b]), 1.0);
+    let agents = vec![agent1, agent2];
+
+    // At zero prices both want A; B is left over
+    let mut prices = PriceVector::new();
+    prices.set_price("C", 1.0);
+    let excess = excess_demand(&agents, &goods, &prices);
+    assert_eq!(excess.get("A"), 1.0);
+    assert_eq!(excess.get("B"), -1.0);
+    assert_eq!(excess.over_demanded(), vec![GoodId::from("A")]);
+    assert_eq!(excess.under_demanded(), vec![GoodId::from("B"), GoodId::from("C")]);
+    assert_eq!(excess.residual(), 1.0);
+
+    // Pricing A between the two values clears A and B; C may stay unsold at its reserve
+    prices.set_price("A", 4.5);
+    let excess = excess_demand(&agents, &goods, &prices);
+    assert_eq!(excess.over_demanded(), Vec::<GoodId>::new());
+    assert_eq!(excess.get("C"), -1.0);
+    assert!(excess.clears(1e-9));
+}
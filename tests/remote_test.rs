@@ -0,0 +1,77 @@
+#![cfg(feature = "tokio")]
+
+use brace_sybil::pricing::PriceVector;
+use brace_sybil::remote::{AsyncClockAuction, Bidder};
+use brace_sybil::{bundle, Agent, AgentId, Bundle, Good};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Stands in for a participant behind a network connection
+struct Remote {
+    id: AgentId,
+    /// Value of each single good; wants at most one
+    values: Vec<(Good, f64)>,
+    queries: AtomicUsize,
+}
+
+impl Bidder for Remote {
+    fn id(&self) -> AgentId {
+        self.id
+    }
+
+    async fn demand(&self, prices: &PriceVector) -> Bundle {
+        self.queries.fetch_add(1, Ordering::SeqCst);
+        tokio::task::yield_now().await;
+        self.values
+            .iter()
+            .map(|(good, value)| (good, value - prices.get_price(good.id)))
+            .filter(|(_, surplus)| *surplus > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(good, _)| Bundle::from([good.clone()]))
+            .unwrap_or_default()
+    }
+}
+
+#[tokio::test]
+async fn test_clock_with_remote_bidders() {
+    let goods: Vec<Good> = ["A", "B"].iter().map(|id| Good::new(*id, *id)).collect();
+    let remote = |id: &str, a: f64, b: f64| {
+        Arc::new(Remote {
+            id: id.into(),
+            values: vec![(goods[0].clone(), a), (goods[1].clone(), b)],
+            queries: AtomicUsize::new(0),
+        })
+    };
+    let bidders = vec![remote("Bidder1", 10.0, 4.0), remote("Bidder2", 8.0, 6.5)];
+
+    let result = AsyncClockAuction::new(1.0).run(&bidders, &goods).await;
+    assert!(result.converged);
+    assert_eq!(result.allocation.get_bundle("Bidder1"), Some(&bundle!["A"]));
+    assert_eq!(result.allocation.get_bundle("Bidder2"), Some(&bundle!["B"]));
+    // A rose until Bidder2 preferred B
+    assert_eq!(result.payments[&AgentId::from("Bidder1")], 2.0);
+    assert_eq!(result.payments[&AgentId::from("Bidder2")], 0.0);
+    for bidder in &bidders {
+        assert_eq!(bidder.queries.load(Ordering::SeqCst), result.rounds.len());
+    }
+}
+
+#[tokio::test]
+async fn test_clock_with_local_agents() {
+    let goods = vec![Good::new("A", "A")];
+    let agents: Vec<Arc<Agent>> = [("Agent1", 5.0), ("Agent2", 3.0)]
+        .iter()
+        .map(|(id, value)| Agent::builder(*id).prefer(["A"], *value).build(&goods).unwrap())
+        .map(Arc::new)
+        .collect();
+
+    let result = AsyncClockAuction::new(0.5).run(&agents, &goods).await;
+    assert!(result.converged);
+    assert_eq!(result.allocation.get_bundle("Agent1"), Some(&bundle!["A"]));
+    assert!(result.allocation.get_bundle("Agent2").unwrap().is_empty());
+    assert_eq!(result.payments[&AgentId::from("Agent1")], 3.0);
+
+    let capped = AsyncClockAuction::new(0.5).with_max_rounds(2).run(&agents, &goods).await;
+    assert!(!capped.converged);
+    assert_eq!(capped.rounds.len(), 2);
+}
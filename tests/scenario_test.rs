@@ -0,0 +1,161 @@
+use brace_sybil::io::ParseError;
+use brace_sybil::scenario::Scenario;
+use brace_sybil::{
+    AuctionError, AuctionInstance, MechanismKind, Objective, TieBreaker,
+};
+use std::fs;
+
+const SWAP_TOML: &str = r#"
+epsilon = 0.01
+
+[mechanism]
+objective = "Egalitarian"
+tie_breaker = { Seeded = 7 }
+vcg = true
+
+[[goods]]
+id = "A"
+
+[[goods]]
+id = "B"
+name = "Good B"
+reserve_price = 1.0
+
+[[agents]]
+id = "Agent1"
+endowment = ["A"]
+preferences = [
+    { goods = ["B"], value = 10.0 },
+    { goods = ["A"], value = 5.0 },
+]
+
+[[agents]]
+id = "Agent2"
+endowment = ["B"]
+budget = 20.0
+preferences = [
+    { goods = ["A"], value = 10.0 },
+    { goods = ["B"], value = 5.0 },
+]
+"#;
+
+const SWAP_YAML: &str = r#"
+epsilon: 0.01
+goods:
+  - id: A
+  - id: B
+agents:
+  - id: Agent1
+    endowment: [A]
+    preferences:
+      - { goods: [B], value: 10.0 }
+      - { goods: [A], value: 5.0 }
+  - id: Agent2
+    endowment: [B]
+    preferences:
+      - { goods: [A], value: 10.0 }
+      - { goods: [B], value: 5.0 }
+"#;
+
+#[test]
+fn test_toml_scenario() {
+    let scenario = Scenario::from_toml(SWAP_TOML).unwrap();
+    assert_eq!(scenario.mechanism.objective, Objective::Egalitarian);
+    assert_eq!(scenario.mechanism.tie_breaker, TieBreaker::Seeded(7));
+    assert!(scenario.mechanism.vcg && !scenario.mechanism.core);
+
+    let instance = scenario.to_instance().unwrap();
+    assert_eq!(instance.goods[0].name, "A");
+    assert_eq!(instance.goods[1].name, "Good B");
+    assert_eq!(instance.goods[1].reserve_price, 1.0);
+    assert_eq!(instance.agents[1].budget, Some(20.0));
+
+    let result = scenario.into_auction().unwrap().run().unwrap();
+    assert_eq!(result.objective, Objective::Egalitarian);
+    assert!(result.vcg_payments.is_some());
+    assert_eq!(result.total_welfare, 20.0);
+}
+
+#[test]
+fn test_yaml_scenario_from_path() {
+    let path = std::env::temp_dir().join(format!("brace-scenario-{}.yaml", std::process::id()));
+    fs::write(&path, SWAP_YAML).unwrap();
+    let instance = AuctionInstance::from_path(&path);
+    fs::remove_file(&path).unwrap();
+
+    let instance = instance.unwrap();
+    assert_eq!(instance.agents.len(), 2);
+    assert_eq!(instance.epsilon, 0.01);
+    assert_eq!(instance.goods[0].supply, 1);
+}
+
+#[test]
+fn test_scenario_bid_expressions() {
+    let toml = SWAP_TOML.replace(
+        "budget = 20.0\n",
+        "budget = 20.0\nbid = \"(A & B):30 XOR A:12\"\n",
+    );
+    let instance = Scenario::from_toml(&toml).unwrap().to_instance().unwrap();
+    let agent = &instance.agents[1];
+    let all = instance.goods.iter().cloned().collect();
+    assert_eq!(agent.preference(&all), 30.0);
+    // Listed preferences still take precedence
+    let a = instance.goods.iter().filter(|good| good.id == "A").cloned().collect();
+    assert_eq!(agent.preference(&a), 10.0);
+
+    let unknown = SWAP_TOML.replace("budget = 20.0\n", "budget = 20.0\nbid = \"C:1\"\n");
+    let scenario = Scenario::from_toml(&unknown).unwrap();
+    assert!(matches!(
+        scenario.to_instance(),
+        Err(AuctionError::Parse(ParseError::BidSyntax { column: 1, .. }))
+    ));
+}
+
+#[test]
+fn test_scenario_errors() {
+    let unknown = SWAP_YAML.replace("endowment: [A]", "endowment: [C]");
+    let scenario = Scenario::from_yaml(&unknown).unwrap();
+    assert!(matches!(
+        scenario.to_instance(),
+        Err(AuctionError::UnknownEndowedGood { .. })
+    ));
+
+    assert!(matches!(Scenario::from_toml("epsilon = \"high\""), Err(ParseError::Toml(_))));
+
+    let path = std::env::temp_dir().join(format!("brace-scenario-{}.ini", std::process::id()));
+    fs::write(&path, "").unwrap();
+    let result = Scenario::from_path(&path);
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(ParseError::UnsupportedFormat(ext)) if ext == "ini"));
+}
+
+#[test]
+fn test_scenario_mechanism_kind() {
+    // Agent3 has no endowment; BRACE trades endowments, greedy pools them
+    let toml = SWAP_TOML.to_string()
+        + "\n[[agents]]\nid = \"Agent3\"\npreferences = [{ goods = [\"A\"], value = 50.0 }]\n";
+    let scenario = Scenario::from_toml(&toml).unwrap();
+    assert_eq!(scenario.mechanism.kind, MechanismKind::Brace);
+    assert_eq!(scenario.into_auction().unwrap().run().unwrap().total_welfare, 20.0);
+
+    let greedy = toml.replace("vcg = true\n", "vcg = true\nkind = \"Greedy\"\n");
+    let scenario = Scenario::from_toml(&greedy).unwrap();
+    assert_eq!(scenario.mechanism.kind, MechanismKind::Greedy);
+    let result = scenario.into_auction().unwrap().run().unwrap();
+    assert_eq!(result.total_welfare, 60.0);
+    let bundle = result.allocation.get_bundle("Agent3").unwrap();
+    assert!(bundle.iter().any(|good| good.id == "A"));
+    assert!(result.prices.is_empty());
+
+    let rsd = toml.replace("vcg = true\n", "vcg = true\nkind = { RandomSerialDictatorship = 3 }\n");
+    let scenario = Scenario::from_toml(&rsd).unwrap();
+    assert_eq!(scenario.mechanism.kind, MechanismKind::RandomSerialDictatorship(3));
+
+    // Constraints the alternative mechanism would ignore are rejected
+    let conflicting = format!("conflicts = [[\"A\", \"B\"]]\n{}", rsd);
+    let error = Scenario::from_toml(&conflicting).unwrap().into_auction().err().unwrap();
+    assert!(matches!(
+        error,
+        AuctionError::UnsupportedSetting { kind: MechanismKind::RandomSerialDictatorship(3), .. }
+    ));
+}
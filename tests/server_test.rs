@@ -0,0 +1,102 @@
+#![cfg(feature = "server")]
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use axum::Router;
+use brace_sybil::server::{router, router_with_solver, MAX_BODY_BYTES, MAX_GOODS};
+use brace_sybil::{Agent, Good};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use tower::ServiceExt;
+
+async fn call(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(body.map_or(Body::empty(), |b| Body::from(b.to_string())))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, value)
+}
+
+fn trader(id: &str, own: &Good, wanted: &Good) -> Agent {
+    let mut agent = Agent::new(id, HashSet::from([own.clone()]));
+    agent.add_preference(HashSet::from([wanted.clone()]), 10.0);
+    agent.add_preference(HashSet::from([own.clone()]), 5.0);
+    agent
+}
+
+#[tokio::test]
+async fn test_submit_and_solve() {
+    let app = router();
+    let a = Good::new("A", "Good A");
+    let b = Good::new("B", "Good B");
+
+    let (status, created) =
+        call(&app, "POST", "/auctions", Some(json!({ "goods": [a, b], "epsilon": 0.01 }))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let id = created["id"].as_u64().unwrap();
+
+    let (status, _) = call(&app, "GET", &format!("/auctions/{}/result", id), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    for agent in [trader("Agent1", &a, &b), trader("Agent2", &b, &a)] {
+        let body = serde_json::to_value(&agent).unwrap();
+        let (status, _) = call(&app, "POST", &format!("/auctions/{}/agents", id), Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    // The same agent cannot bid twice
+    let body = serde_json::to_value(trader("Agent1", &a, &b)).unwrap();
+    let (status, error) = call(&app, "POST", &format!("/auctions/{}/agents", id), Some(body)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(error["error"].as_str().unwrap().contains("duplicate agent"));
+
+    let (status, solved) = call(&app, "POST", &format!("/auctions/{}/solve", id), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(solved["total_welfare"], 20.0);
+
+    let (status, fetched) = call(&app, "GET", &format!("/auctions/{}/result", id), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched, solved);
+
+    let (status, _) = call(&app, "POST", "/auctions/99/solve", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_solver_panic_is_an_error_response() {
+    let app = router_with_solver(|_| panic!("solver bug"));
+    let goods = [Good::new("A", "Good A")];
+    let (_, created) =
+        call(&app, "POST", "/auctions", Some(json!({ "goods": goods, "epsilon": 0.01 }))).await;
+    let id = created["id"].as_u64().unwrap();
+
+    let (status, error) = call(&app, "POST", &format!("/auctions/{}/solve", id), None).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(error["error"].as_str().unwrap().starts_with("solver failed"));
+    let (status, _) = call(&app, "GET", &format!("/auctions/{}/result", id), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_request_limits() {
+    let app = router();
+    let goods: Vec<Good> =
+        (0..=MAX_GOODS).map(|k| Good::new(format!("G{}", k), "Limit")).collect();
+    let (status, error) =
+        call(&app, "POST", "/auctions", Some(json!({ "goods": goods, "epsilon": 0.01 }))).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(error["error"].as_str().unwrap().contains("goods"));
+
+    // Oversized bodies are refused before they are parsed (or any ID interned)
+    let name = "x".repeat(MAX_BODY_BYTES);
+    let goods = json!([{ "id": "Big", "name": name }]);
+    let (status, _) =
+        call(&app, "POST", "/auctions", Some(json!({ "goods": goods, "epsilon": 0.01 }))).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -0,0 +1,43 @@
+use brace_sybil::gen::{generate, GeneratorConfig};
+use brace_sybil::simulation::Simulation;
+use brace_sybil::{auction, bundle};
+
+#[test]
+fn test_simulation_endowments_evolve() {
+    let instance = auction! {
+        goods: ["A", "B"],
+        agents: [
+            "Agent1" => { endow: ["A"], prefer: [["B"] => 10.0, ["A"] => 5.0] },
+            "Agent2" => { endow: ["B"], prefer: [["A"] => 10.0, ["B"] => 5.0] },
+        ],
+        epsilon: 0.01,
+    };
+    let simulation = Simulation::new(instance.agents, instance.goods, instance.epsilon);
+    let report = simulation.run(10).unwrap();
+
+    // The swap happens in round 0; round 1 has nothing left to trade
+    assert_eq!(report.rounds.len(), 2);
+    assert_eq!(report.rounds[0].agents_traded, 2);
+    assert_eq!(report.stable_from, Some(1));
+    assert_eq!(report.welfare(), vec![20.0, 20.0]);
+    assert_eq!(report.price_path("A").len(), 2);
+    assert_eq!(report.final_agents[0].endowment, bundle!["B"]);
+}
+
+#[test]
+fn test_simulation_welfare_never_falls() {
+    let config = GeneratorConfig {
+        num_goods: 6,
+        num_agents: 4,
+        ..GeneratorConfig::default()
+    };
+    for seed in 0..5 {
+        let instance = generate(&config, seed);
+        let report = Simulation::new(instance.agents, instance.goods, instance.epsilon)
+            .run(5)
+            .unwrap();
+        let welfare = report.welfare();
+        assert!(welfare.windows(2).all(|w| w[1] >= w[0] - 1e-9));
+        assert!(report.rounds.iter().all(|round| round.is_individually_rational));
+    }
+}
@@ -0,0 +1,53 @@
+use brace_sybil::gen::random_instance;
+use brace_sybil::sybil::{audit_false_names, split_agent, SybilConfig};
+use brace_sybil::{Agent, BRACEMechanism, Bundle, Good};
+
+fn good(id: &str) -> Good {
+    Good::new(id, format!("Good {}", id))
+}
+
+fn bundle(goods: &[&Good]) -> Bundle {
+    goods.iter().map(|g| (*g).clone()).collect()
+}
+
+#[test]
+fn test_split_agent_partitions_endowment_and_preferences() {
+    let a = good("A");
+    let b = good("B");
+
+    let mut agent = Agent::new("Agent1", bundle(&[&a, &b]));
+    agent.add_preference(bundle(&[&a]), 3.0);
+    agent.add_preference(bundle(&[&b]), 4.0);
+
+    // Endowment A, B then bundles {A}, {B}
+    let split = split_agent(&agent, 2, &[0, 1, 1, 0]);
+    assert_eq!(split.len(), 2);
+    assert_eq!(split[0].id, "Agent1#1");
+    assert_eq!(split[0].endowment, bundle(&[&a]));
+    assert_eq!(split[1].endowment, bundle(&[&b]));
+    assert_eq!(split[0].preference(&bundle(&[&b])), 4.0);
+    assert_eq!(split[0].preference(&bundle(&[&a])), 0.0);
+    assert_eq!(split[1].preference(&bundle(&[&a])), 3.0);
+}
+
+#[test]
+fn test_false_name_proofness_matches_reports() {
+    let instance = random_instance(3, 2, 2, 11);
+    let mechanism = BRACEMechanism::new(instance.epsilon);
+    let config = SybilConfig {
+        identities: 2,
+        max_splits: 32,
+    };
+
+    let reports = audit_false_names(&mechanism, &instance.agents, &instance.goods, &config);
+    assert_eq!(reports.len(), 2);
+    for report in &reports {
+        assert!(report.gain >= 0.0);
+        assert_eq!(report.best_split.is_some(), report.gain > 0.0);
+    }
+    let proof = mechanism.verify_false_name_proofness(&instance.agents, &instance.goods);
+    if !proof {
+        let full = audit_false_names(&mechanism, &instance.agents, &instance.goods, &SybilConfig::default());
+        assert!(full.iter().any(|r| r.gain > 0.0));
+    }
+}
@@ -0,0 +1,55 @@
+use brace_sybil::lp::solve_lp_relaxation;
+use brace_sybil::sybil::{audit_false_names, SybilConfig};
+use brace_sybil::testcases::{self, by_name, NAMES};
+use brace_sybil::verify::check_allocation;
+use brace_sybil::wdp::{solve_exact, GreedySolver, WdpSolver};
+use brace_sybil::{Allocation, BRACEMechanism, CombinatorialAuction};
+
+#[test]
+fn test_every_name_builds_a_valid_instance() {
+    assert_eq!(testcases::all().len(), NAMES.len());
+    for name in NAMES {
+        let instance = by_name(name).unwrap();
+        assert!(CombinatorialAuction::from_instance(instance).unwrap().run().is_ok());
+    }
+    assert!(by_name("no-such-case").is_none());
+}
+
+#[test]
+fn test_no_linear_prices_has_integrality_gap() {
+    let instance = testcases::no_linear_prices();
+    let (_, optimal) = solve_exact(&instance.agents, &instance.goods);
+    // Competitive linear prices exist exactly when the LP relaxation is tight
+    let lp = solve_lp_relaxation(&instance.agents, &instance.goods);
+    assert_eq!(optimal, 3.0);
+    assert!(lp.welfare > optimal + 0.1);
+}
+
+#[test]
+fn test_greedy_trap_misleads_greedy() {
+    let instance = testcases::greedy_trap();
+    assert_eq!(GreedySolver.solve(&instance.agents, &instance.goods).1, 10.0);
+    assert_eq!(solve_exact(&instance.agents, &instance.goods).1, 12.0);
+}
+
+#[test]
+fn test_housing_cycle_needs_three_way_trade() {
+    let instance = testcases::housing_cycle();
+    let mut endowments = Allocation::new();
+    for agent in &instance.agents {
+        endowments.assign(agent.id, agent.endowment.clone());
+    }
+    assert!(BRACEMechanism::new(0.01).verify_ordinal_efficiency(&instance.agents, &endowments));
+    assert!(!check_allocation(&instance.agents, &instance.goods, &endowments).is_pareto_optimal);
+}
+
+#[test]
+fn test_sybil_split_rewards_false_names() {
+    let instance = testcases::sybil_split();
+    let mechanism = BRACEMechanism::new(instance.epsilon);
+    let config = SybilConfig::default();
+    let reports = audit_false_names(&mechanism, &instance.agents, &instance.goods, &config);
+    assert_eq!(reports[0].honest_utility, 5.0);
+    assert_eq!(reports[0].best_split_utility, 10.0);
+    assert_eq!(reports[1].gain, 0.0);
+}
@@ -0,0 +1,72 @@
+use brace_sybil::verify::{check_allocation, exhaustive, feasible_allocations};
+use brace_sybil::{bundle, Agent, Allocation, BRACEMechanism, Good};
+
+/// Each agent owns one house and wants the next agent's
+fn housing_cycle() -> (Vec<Agent>, Vec<Good>) {
+    let goods: Vec<Good> = ["A", "B", "C"].iter().map(|id| Good::new(*id, *id)).collect();
+    let agents = [("Agent1", "A", "B"), ("Agent2", "B", "C"), ("Agent3", "C", "A")]
+        .iter()
+        .map(|(id, own, wanted)| {
+            Agent::builder(*id)
+                .endow([*own])
+                .prefer([*wanted], 10.0)
+                .prefer([*own], 1.0)
+                .build(&goods)
+                .unwrap()
+        })
+        .collect();
+    (agents, goods)
+}
+
+#[test]
+fn test_exhaustive_confirms_brace_outcome() {
+    let (agents, goods) = housing_cycle();
+    // Each of 3 goods goes to one of 3 agents or to nobody
+    assert_eq!(feasible_allocations(&agents, &goods).len(), 64);
+
+    let report = exhaustive(&agents, &goods);
+    assert_eq!(report.allocations, 64);
+    assert!(report.is_pareto_optimal);
+    assert!(report.is_ordinal_efficient);
+    assert!(report.is_welfare_optimal);
+    assert_eq!(report.welfare, 30.0);
+}
+
+#[test]
+fn test_exhaustive_catches_what_pairwise_swaps_miss() {
+    let (agents, goods) = housing_cycle();
+    let mut endowments = Allocation::new();
+    for agent in &agents {
+        endowments.assign(agent.id, agent.endowment.clone());
+    }
+
+    // No two agents gain from swapping, but the three-way trade helps everyone
+    assert!(BRACEMechanism::new(0.01).verify_ordinal_efficiency(&agents, &endowments));
+    let report = check_allocation(&agents, &goods, &endowments);
+    assert!(!report.is_ordinal_efficient);
+    assert!(!report.is_pareto_optimal);
+    assert!(!report.is_welfare_optimal);
+    assert_eq!(report.optimal_welfare, 30.0);
+
+    let better = report.pareto_improvement.unwrap();
+    for agent in &agents {
+        let mine = endowments.get_bundle(agent.id).unwrap();
+        assert!(!agent.prefers(mine, better.get_bundle(agent.id).unwrap()));
+    }
+}
+
+#[test]
+fn test_feasible_allocations_respect_capacity() {
+    let goods = vec![Good::new("A", "A"), Good::new("B", "B")];
+    let agent = Agent::builder("Agent1")
+        .max_goods(1)
+        .prefer(["A", "B"], 3.0)
+        .build(&goods)
+        .unwrap();
+    let allocations = feasible_allocations(&[agent], &goods);
+    // Nothing, A alone or B alone
+    assert_eq!(allocations.len(), 3);
+    assert!(allocations
+        .iter()
+        .all(|a| a.get_bundle("Agent1").unwrap() != &bundle!["A", "B"]));
+}
@@ -0,0 +1,29 @@
+use brace_sybil::viz::{to_dot, to_dot_with_trades};
+use brace_sybil::{auction, AgentId, CombinatorialAuction};
+
+#[test]
+fn test_dot_export() {
+    let instance = auction! {
+        goods: ["A", "B"],
+        agents: [
+            "Agent1" => { endow: ["A"], prefer: [["B"] => 10.0, ["A"] => 5.0] },
+            "Agent2" => { endow: ["B"], prefer: [["A"] => 10.0, ["B"] => 5.0] },
+        ],
+        epsilon: 0.01,
+    };
+    let auction = CombinatorialAuction::from_instance(instance).unwrap();
+    let result = auction.run().unwrap();
+
+    let dot = to_dot(&result, auction.agents());
+    assert!(dot.starts_with("digraph auction {"));
+    assert!(dot.trim_end().ends_with('}'));
+    assert!(dot.contains("\"agent:Agent1\" [shape=ellipse, label=\"Agent1\"];"));
+    assert!(dot.contains("\"good:A\" [shape=box"));
+    assert!(dot.contains("\"agent:Agent1\" -> \"good:A\" [style=dashed"));
+    assert!(dot.contains("\"good:B\" -> \"agent:Agent1\" [penwidth=2];"));
+    assert!(!dot.contains("color=red"));
+
+    let trades = vec![vec![AgentId::from("Agent1"), AgentId::from("Agent2")]];
+    let dot = to_dot_with_trades(&result, auction.agents(), &trades);
+    assert!(dot.contains("\"agent:Agent1\" -> \"agent:Agent2\" [color=red, dir=both, label=\"trade 1\"];"));
+}
@@ -0,0 +1,58 @@
+use brace_sybil::gen::{generate, GeneratorConfig};
+use brace_sybil::wdp::{solve_dp, solve_exact, MAX_DP_GOODS};
+use brace_sybil::{bundle, Agent, BRACEMechanism, Good};
+
+#[test]
+fn test_dp_matches_branch_and_bound() {
+    let config = GeneratorConfig {
+        num_goods: 8,
+        num_agents: 5,
+        bundles_per_agent: 4,
+        ..GeneratorConfig::default()
+    };
+    let mechanism = BRACEMechanism::new(0.01);
+    for seed in 0..20 {
+        let instance = generate(&config, seed);
+        let (allocation, welfare) = solve_dp(&instance.agents, &instance.goods).unwrap();
+        let (_, exact) = solve_exact(&instance.agents, &instance.goods);
+        assert!((welfare - exact).abs() < 1e-9, "seed {}", seed);
+        assert!(mechanism.verify_feasibility(&allocation, &instance.goods));
+        let total: f64 = instance
+            .agents
+            .iter()
+            .map(|agent| agent.preference(allocation.get_bundle(agent.id).unwrap()))
+            .sum();
+        assert!((total - welfare).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_dp_declines_large_or_multi_unit_instances() {
+    let many: Vec<Good> = (0..=MAX_DP_GOODS)
+        .map(|i| Good::new(format!("G{}", i), "good"))
+        .collect();
+    assert!(solve_dp(&[], &many).is_none());
+
+    let multi = vec![Good::new("A", "A").with_supply(2)];
+    assert!(solve_dp(&[], &multi).is_none());
+}
+
+#[test]
+fn test_exact_solver_respects_capacity() {
+    let goods: Vec<Good> = ["A", "B"].iter().map(|id| Good::new(*id, *id)).collect();
+    let capped = Agent::builder("Capped")
+        .max_goods(1)
+        .prefer(["A", "B"], 10.0)
+        .prefer(["A"], 3.0)
+        .build(&goods)
+        .unwrap();
+    let other = Agent::builder("Other").prefer(["B"], 2.0).build(&goods).unwrap();
+    let agents = vec![capped, other];
+
+    // The pair is worth the most but exceeds the capacity of one good
+    let (allocation, welfare) = solve_exact(&agents, &goods);
+    assert_eq!(allocation.get_bundle("Capped"), Some(&bundle!["A"]));
+    assert_eq!(welfare, 5.0);
+    let mechanism = BRACEMechanism::new(0.01);
+    assert!(mechanism.verify_capacity(&agents, &allocation));
+}